@@ -1,5 +1,6 @@
 mod document_context;
 mod meta;
+mod oas;
 mod schema_document;
 
 pub use document_context::*;
@@ -1,10 +1,14 @@
 use super::find_version_node;
+use super::oas;
 use super::schema_document::SchemaDocument;
 use crate::documents;
 use crate::error::Error;
-use crate::models::DocumentSchemaItem;
+use crate::models::{ApiOperation, DocumentSchemaItem};
+use crate::utilities::Fetcher;
 use crate::utilities::NodeCache;
 use crate::utilities::NodeLocation;
+use crate::utilities::SourcePosition;
+use crate::validation::{OutputFormat, OutputUnit, Validator};
 use gloo::utils::format::JsValueSerdeExt;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
@@ -20,8 +24,15 @@ pub struct DocumentConfiguration {
   pub document_node: serde_json::Value,
 }
 
-pub type DocumentFactory =
-  dyn Fn(rc::Weak<DocumentContext>, DocumentConfiguration) -> rc::Rc<dyn SchemaDocument>;
+pub type DocumentFactory = dyn Fn(
+  rc::Weak<DocumentContext>,
+  DocumentConfiguration,
+) -> Result<rc::Rc<dyn SchemaDocument>, Error>;
+
+/**
+The retrieval, given and antecedent location of a document that is still to be loaded
+*/
+type QueueItem = (NodeLocation, NodeLocation, Option<NodeLocation>);
 
 /**
 This class loads document nodes and documents. Every node has a few locations:
@@ -114,112 +125,94 @@ impl DocumentContext {
     Ok(())
   }
 
+  /// Use `fetcher` to retrieve documents that are not loaded yet
+  ///
+  pub fn set_fetcher(&self, fetcher: impl Fetcher + 'static) {
+    self.cache.borrow_mut().set_fetcher(fetcher);
+  }
+
   pub fn register_well_known_factories(self: &mut rc::Rc<Self>) -> Result<(), Error> {
     self.register_factory(
       documents::draft_2020_12::META_SCHEMA_ID,
       Box::new(|context, configuration| {
-        rc::Rc::new(
-          documents::draft_2020_12::Document::new(
-            context,
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::draft_2020_12::Document::new(
+          context,
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::draft_2019_09::META_SCHEMA_ID,
       Box::new(|context, configuration| {
-        rc::Rc::new(
-          documents::draft_2019_09::Document::new(
-            context,
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::draft_2019_09::Document::new(
+          context,
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::draft_07::META_SCHEMA_ID,
       Box::new(|_context, configuration| {
-        rc::Rc::new(
-          documents::draft_07::Document::new(
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::draft_07::Document::new(
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::draft_06::META_SCHEMA_ID,
       Box::new(|_context, configuration| {
-        rc::Rc::new(
-          documents::draft_06::Document::new(
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::draft_06::Document::new(
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::draft_04::META_SCHEMA_ID,
       Box::new(|_context, configuration| {
-        rc::Rc::new(
-          documents::draft_04::Document::new(
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::draft_04::Document::new(
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::oas_v3_1::META_SCHEMA_ID,
       Box::new(|context, configuration| {
-        rc::Rc::new(
-          documents::oas_v3_1::Document::new(
-            context,
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::oas_v3_1::Document::new(
+          context,
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::oas_v3_0::META_SCHEMA_ID,
       Box::new(|_context, configuration| {
-        rc::Rc::new(
-          documents::oas_v3_0::Document::new(
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::oas_v3_0::Document::new(
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     self.register_factory(
       documents::swagger_v2::META_SCHEMA_ID,
       Box::new(|_context, configuration| {
-        rc::Rc::new(
-          documents::swagger_v2::Document::new(
-            configuration.given_location,
-            configuration.antecedent_location,
-            configuration.document_node.into(),
-          )
-          .unwrap(),
-        )
+        Ok(rc::Rc::new(documents::swagger_v2::Document::new(
+          configuration.given_location,
+          configuration.antecedent_location,
+          configuration.document_node.into(),
+        )?))
       }),
     )?;
     Ok(())
@@ -229,7 +222,6 @@ impl DocumentContext {
   Load nodes from a location. The retrieval location is the physical location of the node,
   it should be a root location
   */
-  #[allow(clippy::await_holding_refcell_ref)]
   pub async fn load_from_location(
     self: &rc::Rc<Self>,
     retrieval_location: NodeLocation,
@@ -246,142 +238,215 @@ impl DocumentContext {
       return Ok(());
     }
 
+    let mut errors = Vec::new();
     let mut queue = Vec::new();
+    // embedded documents are loaded before any referenced document, a reference may point to
+    // an embedded document that is not loaded yet
+    let mut embedded_queue = Vec::new();
     queue.push((retrieval_location, given_location, antecedent_location));
-    // drain the queue
-    while let Some((retrieval_location, given_location, document_antecedent_location)) = queue.pop()
+    // drain the queue, a document that fails to load is reported but does not stop
+    // other documents from loading
+    while let Some((retrieval_location, given_location, document_antecedent_location)) =
+      embedded_queue.pop().or_else(|| queue.pop())
     {
-      // let's load some documents! This involves figuring out the document type
-      // via the meta schema id and some interesting logic. This logic is about
-      // nodes being loaded multiple times. If this is the case of course
-      // we do not want to double load the document. This is
-      // how we check for that:
-      if self.documents.borrow().contains_key(&retrieval_location) {
-        continue;
-      }
-
-      // There might be a situation where we load a node that is already loaded as
-      // part of a larger document. The node is then a child of that document. If
-      // that is the case then we don't want to load another document but just use
-      // the larger, parent document.
-      if self
-        .node_to_document_retrieval_locations
-        .borrow()
-        .contains_key(&retrieval_location)
+      match self
+        .load_document(
+          retrieval_location,
+          given_location,
+          document_antecedent_location,
+          default_meta_schema_id,
+        )
+        .await
       {
-        continue;
+        Ok((referenced_locations, embedded_locations)) => {
+          queue.extend(referenced_locations);
+          embedded_queue.extend(embedded_locations);
+        }
+        Err(error) => errors.push(error),
       }
+    }
 
-      // Ensure the node is in the cache
-      self
-        .cache
-        .borrow_mut()
-        .load_from_location(&retrieval_location)
-        .await?;
+    Error::from_errors(errors)
+  }
 
-      // Get the node from the cache
-      let document_node = self
-        .cache
-        .borrow()
-        .get_node(&retrieval_location)
-        .ok_or_else(|| Error::DocumentNodeNotFound(retrieval_location.clone()))?
-        .clone();
+  /**
+  Load a single document. Returns the retrieval, given and antecedent locations of the
+  documents that are referenced by the document, and of the documents that are embedded in
+  the document.
+  */
+  #[allow(clippy::await_holding_refcell_ref)]
+  async fn load_document(
+    self: &rc::Rc<Self>,
+    retrieval_location: NodeLocation,
+    given_location: NodeLocation,
+    document_antecedent_location: Option<NodeLocation>,
+    default_meta_schema_id: &str,
+  ) -> Result<(Vec<QueueItem>, Vec<QueueItem>), Error> {
+    let mut queue = Vec::new();
+    let mut embedded_queue = Vec::new();
+
+    // let's load some documents! This involves figuring out the document type
+    // via the meta schema id and some interesting logic. This logic is about
+    // nodes being loaded multiple times. If this is the case of course
+    // we do not want to double load the document. This is
+    // how we check for that:
+    if self.documents.borrow().contains_key(&retrieval_location) {
+      return Ok((queue, embedded_queue));
+    }
 
-      let factory = {
-        let cache = self.cache.borrow();
+    // There might be a situation where we load a node that is already loaded as
+    // part of a larger document. The node is then a child of that document. If
+    // that is the case then we don't want to load another document but just use
+    // the larger, parent document.
+    if self
+      .node_to_document_retrieval_locations
+      .borrow()
+      .contains_key(&retrieval_location)
+    {
+      return Ok((queue, embedded_queue));
+    }
 
-        let version_retrieval_location = find_version_node(&cache, &retrieval_location)
-          .unwrap_or_else(|| retrieval_location.clone());
-        let version_node = cache
-          .get_node(&version_retrieval_location)
-          .ok_or_else(|| Error::VersionNodeNotFound(version_retrieval_location.clone()))?;
-        let meta_schema_id =
-          documents::discover_meta_schema_id(version_node).unwrap_or(default_meta_schema_id);
+    // The node might also be known by it's identity, this is the case for embedded
+    // documents. Those are loaded from the embedding document, not retrieved.
+    if self
+      .retrieval_to_identity_locations
+      .borrow()
+      .contains_key(&given_location)
+    {
+      return Ok((queue, embedded_queue));
+    }
 
-        self
-          .factories
-          .get(meta_schema_id)
-          .ok_or_else(|| Error::FactoryNotFound(meta_schema_id.to_owned()))?
-      };
+    // Ensure the node is in the cache
+    self
+      .cache
+      .borrow_mut()
+      .load_from_location(&retrieval_location)
+      .await?;
 
-      let document = factory(
-        rc::Rc::downgrade(self),
-        DocumentConfiguration {
-          retrieval_location: retrieval_location.clone(),
-          given_location: given_location.clone(),
-          antecedent_location: document_antecedent_location.clone(),
-          document_node,
-        },
-      );
+    // Get the node from the cache
+    let document_node = self
+      .cache
+      .borrow()
+      .get_node(&retrieval_location)
+      .ok_or_else(|| Error::DocumentNodeNotFound(retrieval_location.clone()))?
+      .clone();
 
-      let document_identity_location = document.get_identity_location();
+    let factory = {
+      let cache = self.cache.borrow();
 
-      if self
-        .documents
+      let version_retrieval_location = find_version_node(&cache, &retrieval_location)
+        .unwrap_or_else(|| retrieval_location.clone());
+      let version_node = cache
+        .get_node(&version_retrieval_location)
+        .ok_or_else(|| Error::VersionNodeNotFound(version_retrieval_location.clone()))?;
+      let meta_schema_id =
+        documents::discover_meta_schema_id(version_node).unwrap_or(default_meta_schema_id);
+
+      self
+        .factories
+        .get(meta_schema_id)
+        .ok_or_else(|| Error::FactoryNotFound(meta_schema_id.to_owned()))?
+    };
+
+    let document = factory(
+      rc::Rc::downgrade(self),
+      DocumentConfiguration {
+        retrieval_location: retrieval_location.clone(),
+        given_location: given_location.clone(),
+        antecedent_location: document_antecedent_location.clone(),
+        document_node,
+      },
+    )?;
+
+    let document_identity_location = document.get_identity_location();
+
+    if self
+      .documents
+      .borrow_mut()
+      .insert(retrieval_location.clone(), document.clone())
+      .is_some()
+    {
+      Err(Error::Conflict(retrieval_location.clone()))?;
+    }
+
+    // Map node pointers and anchors to this document
+    for (node_retrieval_location, node_identity_location) in iter::empty()
+      .chain(document.get_node_pointers().into_iter().map(|pointer| {
+        (
+          retrieval_location.push_pointer(pointer.clone()),
+          document_identity_location.push_pointer(pointer.clone()),
+        )
+      }))
+      .chain(document.get_node_anchors().into_iter().map(|anchor| {
+        // an anchor replaces the pointer, so anchors in embedded documents are
+        // retrieved via their pointer
+        let anchor_pointer = document
+          .resolve_anchor(&anchor)
+          .or_else(|| document.resolve_antecedent_anchor(&anchor))
+          .filter(|_pointer| !retrieval_location.is_root());
+        (
+          match anchor_pointer {
+            Some(pointer) => retrieval_location.push_pointer(pointer),
+            None => retrieval_location.set_anchor(anchor.clone()),
+          },
+          document_identity_location.set_anchor(anchor.clone()),
+        )
+      }))
+    {
+      // it is possible that the node is already related to a document, this
+      // is the case when a child node is loaded before the parent document.
+      if let Some(document_retrieval_location) = self
+        .node_to_document_retrieval_locations
         .borrow_mut()
-        .insert(retrieval_location.clone(), document.clone())
-        .is_some()
+        .insert(node_retrieval_location.clone(), retrieval_location.clone())
+        .filter(|document_retrieval_location| *document_retrieval_location != retrieval_location)
       {
-        Err(Error::Conflict)?;
+        // document might already be removed
+        self
+          .documents
+          .borrow_mut()
+          .remove(&document_retrieval_location);
       }
 
-      // Map node pointers and anchors to this document
-      for (node_retrieval_location, node_identity_location) in iter::empty()
-        .chain(document.get_node_pointers().into_iter().map(|pointer| {
-          (
-            retrieval_location.push_pointer(pointer.clone()),
-            document_identity_location.push_pointer(pointer.clone()),
-          )
-        }))
-        .chain(document.get_node_anchors().into_iter().map(|anchor| {
-          (
-            retrieval_location.set_anchor(anchor.clone()),
-            document_identity_location.set_anchor(anchor.clone()),
-          )
-        }))
-      {
-        // it is possible that the node is already related to a document, this
-        // is the case when a child node is loaded before the parent document.
-        if let Some(document_retrieval_location) = self
-          .node_to_document_retrieval_locations
-          .borrow_mut()
-          .insert(node_retrieval_location.clone(), retrieval_location.clone())
-        {
-          // document might already be removed
-          self
-            .documents
-            .borrow_mut()
-            .remove(&document_retrieval_location);
-        }
+      // possibly overwrite value
+      self.identity_to_retrieval_locations.borrow_mut().insert(
+        node_retrieval_location.clone(),
+        node_identity_location.clone(),
+      );
 
-        // possibly overwrite value
-        self.identity_to_retrieval_locations.borrow_mut().insert(
-          node_retrieval_location.clone(),
-          node_identity_location.clone(),
-        );
+      // possibly overwrite value
+      self.retrieval_to_identity_locations.borrow_mut().insert(
+        node_identity_location.clone(),
+        node_retrieval_location.clone(),
+      );
+    }
 
-        // possibly overwrite value
-        self.retrieval_to_identity_locations.borrow_mut().insert(
-          node_identity_location.clone(),
-          node_retrieval_location.clone(),
-        );
-      }
+    let referenced_locations = document.get_referenced_locations();
+    for referenced_location in referenced_locations {
+      let retrieval_location = retrieval_location.join(&referenced_location);
+      let given_location = document_identity_location.join(&referenced_location);
+
+      queue.push((
+        retrieval_location,
+        given_location,
+        // antecedent location points to the identity location!
+        Some(document_identity_location.clone()),
+      ));
+    }
 
-      let referenced_locations = document.get_referenced_locations();
-      for referenced_location in referenced_locations {
-        let retrieval_location = retrieval_location.join(&referenced_location);
-        let given_location = document_identity_location.join(&referenced_location);
+    let embedded_locations = document.get_embedded_locations();
+    for (pointer, embedded_location) in embedded_locations {
+      let retrieval_location = retrieval_location.push_pointer(pointer);
+      let given_location = document_identity_location.join(&embedded_location);
 
-        queue.push((
-          retrieval_location,
-          given_location,
-          // antecedent location points to the identity location!
-          Some(document_identity_location.clone()),
-        ));
-      }
+      embedded_queue.push((
+        retrieval_location,
+        given_location,
+        Some(document_identity_location.clone()),
+      ));
     }
 
-    Ok(())
+    Ok((queue, embedded_queue))
   }
 
   pub async fn load_from_node(
@@ -411,13 +476,54 @@ impl DocumentContext {
     self.explicit_locations.borrow().iter().cloned().collect()
   }
 
-  pub fn get_schema_nodes(&self) -> BTreeMap<NodeLocation, DocumentSchemaItem> {
-    self
-      .documents
-      .borrow()
+  /**
+  All schema nodes of all documents, indexed by their identity location. If nodes can not
+  be read, every one of them is reported.
+  */
+  pub fn get_schema_nodes(&self) -> Result<BTreeMap<NodeLocation, DocumentSchemaItem>, Error> {
+    let documents = self.documents.borrow();
+
+    // Sub nodes are located by their pointer in the document, but embedded documents
+    // are located by their own identity.
+    let embedded_locations: BTreeMap<_, _> = documents
       .values()
-      .flat_map(|document| document.get_schema_nodes())
-      .collect()
+      .flat_map(|document| {
+        let document_identity_location = document.get_identity_location();
+        document
+          .get_embedded_locations()
+          .into_iter()
+          .map(move |(pointer, embedded_location)| {
+            (
+              document_identity_location.push_pointer(pointer),
+              document_identity_location.join(&embedded_location),
+            )
+          })
+      })
+      .collect();
+
+    let schema_nodes: Vec<BTreeMap<_, _>> = Error::collect_results(
+      documents
+        .values()
+        .map(|document| document.get_schema_nodes()),
+    )?;
+
+    Ok(
+      schema_nodes
+        .into_iter()
+        .flatten()
+        .map(|(location, item)| {
+          let item =
+            item.map_keys(|location| embedded_locations.get(location).unwrap_or(location).clone());
+          (location, item)
+        })
+        .collect(),
+    )
+  }
+
+  /// Where the node at the retrieval location starts in the source text of its
+  /// document, if the document was loaded from a location.
+  pub fn get_source_position(&self, retrieval_location: &NodeLocation) -> Option<SourcePosition> {
+    self.cache.borrow().get_source_position(retrieval_location)
   }
 
   pub fn resolve_document_retrieval_location(
@@ -476,7 +582,11 @@ impl DocumentContext {
     let mut document_identity_location = document_identity_location.clone();
 
     loop {
-      let result = self.get_document(&document_identity_location)?;
+      // documents are indexed by their retrieval location
+      let node_retrieval_location = self.resolve_retrieval_location(&document_identity_location)?;
+      let document_retrieval_location =
+        self.resolve_document_retrieval_location(&node_retrieval_location)?;
+      let result = self.get_document(&document_retrieval_location)?;
       results.push(result.clone());
 
       let Some(antecedent_location) = result.get_antecedent_location() else {
@@ -488,6 +598,271 @@ impl DocumentContext {
 
     Ok(results)
   }
+
+  /**
+  Validate every retrieved document against the meta schema of it's dialect, api descriptions
+  are validated against the schema of the description. Embedded documents are validated as
+  part of the document that embeds them.
+
+  The well known meta schemas are embedded, other meta schemas are retrieved via the fetcher,
+  like any other document. If a meta schema cannot be retrieved this is an error. Meta schemas
+  are loaded in a separate context so they do not end up in the schema nodes of this context.
+  The result contains the validation output of every document that is not valid, indexed by
+  the retrieval location of the document.
+  */
+  pub async fn validate_documents(
+    self: &rc::Rc<Self>,
+    default_meta_schema_id: &str,
+  ) -> Result<BTreeMap<NodeLocation, OutputUnit>, Error> {
+    let document_retrieval_locations: BTreeSet<_> = self
+      .documents
+      .borrow()
+      .keys()
+      .map(|location| location.set_root())
+      .collect();
+
+    self.load_well_known_schemas()?;
+
+    let mut meta_context = rc::Rc::new(Self {
+      cache: self.cache.clone(),
+      ..Default::default()
+    });
+    meta_context.register_well_known_factories()?;
+
+    let mut document_schema_locations = BTreeMap::new();
+    for document_retrieval_location in &document_retrieval_locations {
+      let mut document_node = self
+        .cache
+        .borrow()
+        .get_node(document_retrieval_location)
+        .ok_or_else(|| Error::DocumentNodeNotFound(document_retrieval_location.clone()))?
+        .clone();
+      let document_schema_id =
+        documents::discover_document_schema_id(&document_node, default_meta_schema_id)
+          .ok_or_else(|| Error::FactoryNotFound(document_retrieval_location.to_string()))?;
+      let document_schema_location: NodeLocation = document_schema_id.parse()?;
+
+      // schema objects of api descriptions do not allow `$schema`, but a document that is
+      // only a schema object uses it to select the dialect
+      if [
+        documents::oas_v3_0::META_SCHEMA_ID,
+        documents::swagger_v2::META_SCHEMA_ID,
+      ]
+      .contains(&document_schema_id)
+        && let Some(document_object) = document_node.as_object_mut()
+      {
+        document_object.remove("$schema");
+      }
+
+      // meta schemas are retrieved by their id and always specify their own meta schema
+      let meta_schema_retrieval_location = document_schema_location.set_root();
+      meta_context
+        .load_from_location(
+          meta_schema_retrieval_location.clone(),
+          meta_schema_retrieval_location,
+          None,
+          default_meta_schema_id,
+        )
+        .await?;
+
+      document_schema_locations.insert(
+        document_retrieval_location.clone(),
+        (document_schema_location, document_node),
+      );
+    }
+
+    let validator = Validator::from_document_context(&meta_context)?;
+
+    let mut results = BTreeMap::new();
+    for (document_retrieval_location, (document_schema_location, document_node)) in
+      document_schema_locations
+    {
+      let document_schema_location =
+        meta_context.resolve_identity_location(&document_schema_location)?;
+      let output = validator.validate(
+        &document_schema_location,
+        &document_node,
+        OutputFormat::Basic,
+      )?;
+      if !output.valid {
+        results.insert(document_retrieval_location, output);
+      }
+    }
+
+    Ok(results)
+  }
+
+  /**
+  Put the embedded copies of the well known meta schemas in the cache, so they are not
+  fetched. Schemas that are already in the cache are left alone.
+  */
+  pub fn load_well_known_schemas(&self) -> Result<(), Error> {
+    let mut cache = self.cache.borrow_mut();
+    for (id, text) in documents::get_well_known_schemas() {
+      let retrieval_location = id.parse::<NodeLocation>()?.set_root();
+      if cache.get_node(&retrieval_location).is_some() {
+        continue;
+      }
+
+      let node = serde_json::from_str(text).map_err(|error| Error::SerializationError {
+        location: retrieval_location.clone(),
+        message: error.to_string(),
+        line: Some(error.line()),
+        column: Some(error.column()),
+      })?;
+      cache.load_from_node(&retrieval_location, node)?;
+    }
+
+    Ok(())
+  }
+
+  /**
+  The operations in the api description at the retrieval location, empty if the document is
+  not an api description.
+  */
+  pub fn get_api_operations(
+    &self,
+    retrieval_location: &NodeLocation,
+  ) -> Result<Vec<ApiOperation>, Error> {
+    let cache = self.cache.borrow();
+    let node = cache
+      .get_node(retrieval_location)
+      .ok_or_else(|| Error::DocumentNodeNotFound(retrieval_location.clone()))?;
+    let identity_location = self.resolve_identity_location(retrieval_location)?;
+
+    Ok(oas::select_api_operations(node, &identity_location))
+  }
+
+  /**
+  Bundle the document at the retrieval location, and every document it references, into one
+  compound draft 2020-12 document. Every loaded resource is embedded under `$defs` with it's
+  identity as `$id`, references to other resources are rewritten to the identity of the
+  resource so they resolve from the embedded resource.
+
+  Identities are made relative to the entry document, so the bundle does not depend on where
+  the documents were loaded from. If a base location is given, the identities are resolved
+  from the base location. Identities that cannot be made relative, like the ones on another
+  origin, are kept as is.
+  */
+  pub fn bundle(
+    &self,
+    retrieval_location: &NodeLocation,
+    base_location: Option<&NodeLocation>,
+    default_meta_schema_id: &str,
+  ) -> Result<serde_json::Value, Error> {
+    let cache = self.cache.borrow();
+    let entry_identity_location = self.resolve_identity_location(retrieval_location)?;
+
+    // identities that are derived from the retrieval location are made relative to the
+    // retrieval location of the entry
+    let to_bundle_location = |location: &NodeLocation| {
+      let relative_location = location
+        .make_relative(&entry_identity_location)
+        .or_else(|| location.make_relative(retrieval_location));
+      match (relative_location, base_location) {
+        (Some(relative_location), Some(base_location)) => base_location.join(&relative_location),
+        (Some(relative_location), None) => relative_location,
+        (None, _) => location.clone(),
+      }
+    };
+
+    // every resource is the root of a retrieved document, embedded documents are part
+    // of those
+    let resource_retrieval_locations: BTreeSet<_> = self
+      .documents
+      .borrow()
+      .keys()
+      .map(|location| location.set_root())
+      .collect();
+
+    let mut definitions = serde_json::Map::new();
+    for resource_retrieval_location in resource_retrieval_locations {
+      let resource_node = cache
+        .get_node(&resource_retrieval_location)
+        .ok_or_else(|| Error::DocumentNodeNotFound(resource_retrieval_location.clone()))?;
+      let resource_identity_location = self
+        .resolve_identity_location(&resource_retrieval_location)
+        .unwrap_or(resource_retrieval_location);
+      let resource_id = to_resource_id(&to_bundle_location(&resource_identity_location));
+      let meta_schema_id =
+        documents::discover_meta_schema_id(resource_node).unwrap_or(default_meta_schema_id);
+
+      let mut resource_node = match resource_node {
+        serde_json::Value::Object(resource_node) => resource_node.clone(),
+        resource_node => serde_json::Map::from_iter([(
+          "allOf".to_owned(),
+          serde_json::Value::Array(vec![resource_node.clone()]),
+        )]),
+      };
+      resource_node.insert(
+        "$schema".to_owned(),
+        serde_json::Value::String(meta_schema_id.to_owned()),
+      );
+      resource_node.insert(
+        "$id".to_owned(),
+        serde_json::Value::String(resource_id.clone()),
+      );
+
+      let mut resource_node = serde_json::Value::Object(resource_node);
+      rewrite_references(&mut resource_node, &resource_identity_location)?;
+
+      definitions.insert(resource_id, resource_node);
+    }
+
+    Ok(serde_json::json!({
+      "$schema": documents::draft_2020_12::META_SCHEMA_ID,
+      "$ref": to_bundle_location(&entry_identity_location).to_string(),
+      "$defs": definitions,
+    }))
+  }
+}
+
+/**
+A resource id has no (empty) fragment
+*/
+fn to_resource_id(location: &NodeLocation) -> String {
+  location.to_string().trim_end_matches('#').to_owned()
+}
+
+/**
+Rewrite every reference in the node that points outside of the resource it is in, to the
+location of the node it points to relative to the resource. Resources keep their place
+relative to each other in the bundle, so the relative reference resolves to the same node.
+*/
+fn rewrite_references(
+  node: &mut serde_json::Value,
+  base_location: &NodeLocation,
+) -> Result<(), Error> {
+  match node {
+    serde_json::Value::Object(node) => {
+      let base_location = match node.get("$id").and_then(|id| id.as_str()) {
+        Some(id) => base_location.join(&id.parse()?),
+        None => base_location.clone(),
+      };
+
+      if let Some(serde_json::Value::String(reference)) = node.get_mut("$ref") {
+        let reference_location = base_location.join(&reference.parse()?);
+        if reference_location.set_root() != base_location.set_root() {
+          *reference = reference_location
+            .make_relative(&base_location)
+            .unwrap_or(reference_location)
+            .to_string();
+        }
+      }
+
+      for sub_node in node.values_mut() {
+        rewrite_references(sub_node, &base_location)?;
+      }
+    }
+    serde_json::Value::Array(node) => {
+      for sub_node in node {
+        rewrite_references(sub_node, base_location)?;
+      }
+    }
+    _ => {}
+  }
+
+  Ok(())
 }
 
 #[wasm_bindgen]
@@ -542,13 +917,18 @@ impl DocumentContextContainer {
     node: &JsValue,
     default_meta_schema_id: &str,
   ) -> Result<(), js_sys::Error> {
-    let retrieval_location = retrieval_location.parse()?;
+    let retrieval_location: NodeLocation = retrieval_location.parse()?;
     let given_location = given_location.parse()?;
     let antecedent_location = antecedent_location
       .map(|location| location.parse())
       .transpose()?;
 
-    let node = JsValue::into_serde(node).unwrap();
+    let node = JsValue::into_serde(node).map_err(|error| Error::SerializationError {
+      location: retrieval_location.clone(),
+      message: error.to_string(),
+      line: None,
+      column: None,
+    })?;
 
     Ok(
       self
@@ -591,7 +971,7 @@ impl From<DocumentContextContainer> for rc::Rc<DocumentContext> {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::models::SchemaType;
+  use crate::models::{ApiHeader, ApiParameterLocation, SchemaType};
 
   #[tokio::test]
   async fn test_load_string_from_location() {
@@ -612,10 +992,601 @@ mod tests {
       .await
       .unwrap();
 
-    let mut nodes = document_context.get_schema_nodes();
+    let mut nodes = document_context.get_schema_nodes().unwrap();
     assert_eq!(nodes.len(), 1);
 
     let (_key, node) = nodes.pop_last().unwrap();
     assert_eq!(node.types, Some(vec![SchemaType::String]));
   }
+
+  #[tokio::test]
+  async fn test_load_with_fetcher() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    document_context.set_fetcher(
+      crate::utilities::MemoryFetcher::new()
+        .with_document(
+          "https://schemas.example.com/entry.json",
+          r#"{ "type": "array", "items": { "$ref": "item.json" } }"#,
+        )
+        .with_document(
+          "https://schemas.example.com/item.json",
+          r#"{ "type": "string" }"#,
+        ),
+    );
+
+    let location: NodeLocation = "https://schemas.example.com/entry.json".parse().unwrap();
+
+    document_context
+      .load_from_location(
+        location.clone(),
+        location.clone(),
+        None,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let nodes = document_context.get_schema_nodes().unwrap();
+    let item_location: NodeLocation = "https://schemas.example.com/item.json".parse().unwrap();
+    assert_eq!(
+      nodes.get(&item_location).unwrap().types,
+      Some(vec![SchemaType::String])
+    );
+  }
+
+  #[tokio::test]
+  async fn test_load_sub_nodes() {
+    for (meta_schema_id, keywords) in [
+      (
+        documents::draft_06::META_SCHEMA_ID,
+        &["not", "propertyNames", "contains"][..],
+      ),
+      (
+        documents::draft_07::META_SCHEMA_ID,
+        &["if", "then", "else", "not", "propertyNames", "contains"][..],
+      ),
+      (
+        documents::draft_2019_09::META_SCHEMA_ID,
+        &["if", "then", "else", "not", "propertyNames", "contains"][..],
+      ),
+    ] {
+      let mut document_context = rc::Rc::new(DocumentContext::default());
+      document_context.register_well_known_factories().unwrap();
+
+      let location: NodeLocation = "/schema.json".parse().unwrap();
+      let node = serde_json::Value::Object(
+        keywords
+          .iter()
+          .map(|keyword| (keyword.to_string(), serde_json::json!({ "type": "string" })))
+          .collect(),
+      );
+      document_context
+        .load_from_node(
+          location.clone(),
+          location.clone(),
+          None,
+          node,
+          meta_schema_id,
+        )
+        .await
+        .unwrap();
+
+      let nodes = document_context.get_schema_nodes().unwrap();
+      for keyword in keywords {
+        let sub_location: NodeLocation = format!("/schema.json#/{}", keyword).parse().unwrap();
+        assert_eq!(
+          nodes.get(&sub_location).map(|node| node.types.clone()),
+          Some(Some(vec![SchemaType::String])),
+          "{} {}",
+          meta_schema_id,
+          keyword
+        );
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn test_get_api_operations() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+
+    let location: NodeLocation = "/api.json".parse().unwrap();
+    document_context
+      .load_from_node(
+        location.clone(),
+        location.clone(),
+        None,
+        serde_json::json!({
+          "openapi": "3.0.3",
+          "paths": {
+            "/pets/{petId}": {
+              "parameters": [{ "$ref": "#/components/parameters/PetId" }],
+              "get": {
+                "operationId": "getPet",
+                "parameters": [{ "name": "verbose", "in": "query", "schema": { "type": "boolean" } }],
+                "responses": {
+                  "200": {
+                    "headers": {
+                      "content-type": { "schema": { "type": "string" } },
+                      "x-next": { "required": true, "schema": { "type": "string" } }
+                    },
+                    "content": {
+                      "text/plain": { "schema": { "type": "string" } },
+                      "application/json": { "schema": { "type": "object" } }
+                    }
+                  },
+                  "4XX": { "description": "Error" }
+                }
+              }
+            }
+          },
+          "components": {
+            "parameters": {
+              "PetId": { "name": "petId", "in": "path", "schema": { "type": "string" } }
+            }
+          }
+        }),
+        documents::oas_v3_0::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let operations = document_context.get_api_operations(&location).unwrap();
+    assert_eq!(operations.len(), 1);
+
+    let operation = &operations[0];
+    assert_eq!(operation.name, vec!["getPet".to_owned()]);
+    assert_eq!(operation.method, "get");
+    assert_eq!(operation.path, "/pets/{petId}");
+
+    let parameters: Vec<_> = operation
+      .parameters
+      .iter()
+      .map(|parameter| {
+        (
+          parameter.name.as_str(),
+          parameter.location,
+          parameter.required,
+        )
+      })
+      .collect();
+    assert_eq!(
+      parameters,
+      vec![
+        ("petId", ApiParameterLocation::Path, true),
+        ("verbose", ApiParameterLocation::Query, false),
+      ]
+    );
+    assert_eq!(
+      operation.parameters[0].schema,
+      Some(
+        "/api.json#/components/parameters/PetId/schema"
+          .parse()
+          .unwrap()
+      )
+    );
+
+    let body = operation.responses[0].body.as_ref().unwrap();
+    assert_eq!(body.content_type, "application/json");
+    assert_eq!(
+      operation.responses[0].headers,
+      vec![ApiHeader {
+        name: "x-next".to_owned(),
+        required: true,
+        schema: Some(
+          "/api.json#/paths/~1pets~1%7BpetId%7D/get/responses/200/headers/x-next/schema"
+            .parse()
+            .unwrap()
+        ),
+      }]
+    );
+    assert_eq!(operation.responses[1].get_status_range(), Some((400, 499)));
+    assert_eq!(operation.responses[1].body, None);
+
+    // every schema in the operation is a schema node
+    let nodes = document_context.get_schema_nodes().unwrap();
+    assert!(nodes.contains_key(body.schema.as_ref().unwrap()));
+    assert!(nodes.contains_key(operation.parameters[0].schema.as_ref().unwrap()));
+    assert!(nodes.contains_key(operation.responses[0].headers[0].schema.as_ref().unwrap()));
+  }
+
+  #[tokio::test]
+  async fn test_bundle() {
+    let bundle = load_bundle("/home/build/project").await;
+
+    assert_eq!(bundle["$ref"], "entry.json#");
+    let name = &bundle["$defs"]["../common/name.json"];
+    assert_eq!(name["$id"], "../common/name.json");
+    assert_eq!(name["$schema"], documents::draft_07::META_SCHEMA_ID);
+    let entry = &bundle["$defs"]["entry.json"];
+    assert_eq!(entry["$id"], "entry.json");
+    assert_eq!(
+      entry["properties"]["name"]["$ref"],
+      "../common/name.json#/definitions/name"
+    );
+    assert_eq!(entry["properties"]["self"]["$ref"], "#");
+
+    // the bundle does not depend on where the documents were loaded from
+    assert_eq!(load_bundle("/tmp/checkout").await, bundle);
+
+    // the bundle loads without retrieving anything and validates like the original
+    let mut bundle_context = rc::Rc::new(DocumentContext::default());
+    bundle_context.register_well_known_factories().unwrap();
+
+    let bundle_location: NodeLocation = "/bundle.json".parse().unwrap();
+    bundle_context
+      .load_from_node(
+        bundle_location.clone(),
+        bundle_location.clone(),
+        None,
+        bundle,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let validator = crate::validation::Validator::from_document_context(&bundle_context).unwrap();
+    assert!(
+      validator
+        .is_valid(&bundle_location, &serde_json::json!({ "name": "ab" }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&bundle_location, &serde_json::json!({ "name": "a" }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(
+          &bundle_location,
+          &serde_json::json!({ "self": { "name": 1 } })
+        )
+        .unwrap()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_bundle_base_location() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+
+    let entry_location: NodeLocation = "/home/build/schemas/entry.json".parse().unwrap();
+    document_context
+      .load_from_node(
+        entry_location.clone(),
+        entry_location.clone(),
+        None,
+        serde_json::json!({ "type": "string" }),
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let base_location: NodeLocation = "https://schemas.example.com/v1/".parse().unwrap();
+    let bundle = document_context
+      .bundle(
+        &entry_location,
+        Some(&base_location),
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .unwrap();
+
+    assert_eq!(bundle["$ref"], "https://schemas.example.com/v1/entry.json#");
+    assert_eq!(
+      bundle["$defs"]["https://schemas.example.com/v1/entry.json"]["$id"],
+      "https://schemas.example.com/v1/entry.json"
+    );
+  }
+
+  async fn load_bundle(directory: &str) -> serde_json::Value {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+
+    let name_location: NodeLocation = format!("{directory}/common/name.json").parse().unwrap();
+    let entry_location: NodeLocation = format!("{directory}/schemas/entry.json").parse().unwrap();
+
+    document_context
+      .load_from_node(
+        name_location.clone(),
+        name_location.clone(),
+        None,
+        serde_json::json!({
+          "$schema": documents::draft_07::META_SCHEMA_ID,
+          "definitions": {
+            "name": { "type": "string", "minLength": 2 }
+          }
+        }),
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+    document_context
+      .load_from_node(
+        entry_location.clone(),
+        entry_location.clone(),
+        None,
+        serde_json::json!({
+          "type": "object",
+          "properties": {
+            "name": { "$ref": "../common/name.json#/definitions/name" },
+            "self": { "$ref": "#" }
+          }
+        }),
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    document_context
+      .bundle(
+        &entry_location,
+        None,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .unwrap()
+  }
+
+  #[tokio::test]
+  async fn test_validate_documents() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    // only the documents are served, the meta schema is the embedded copy
+    document_context.set_fetcher(
+      crate::utilities::MemoryFetcher::new()
+        .with_document(
+          "https://schemas.example.com/valid.json",
+          r#"{ "type": "object", "properties": { "a": { "$ref": "invalid.json" } } }"#,
+        )
+        .with_document(
+          "https://schemas.example.com/invalid.json",
+          r#"{ "type": "object", "properties": { "b": { "type": "strng" }, "c": { "minLength": -1 } } }"#,
+        ),
+    );
+
+    let location: NodeLocation = "https://schemas.example.com/valid.json".parse().unwrap();
+
+    document_context
+      .load_from_location(
+        location.clone(),
+        location.clone(),
+        None,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let results = document_context
+      .validate_documents(documents::draft_2020_12::META_SCHEMA_ID)
+      .await
+      .unwrap();
+
+    let invalid_location: NodeLocation =
+      "https://schemas.example.com/invalid.json".parse().unwrap();
+    assert_eq!(
+      results.keys().cloned().collect::<Vec<_>>(),
+      vec![invalid_location.clone()]
+    );
+    let instance_locations: BTreeSet<_> = results[&invalid_location]
+      .errors
+      .iter()
+      .filter(|unit| unit.error.is_some())
+      .map(|unit| unit.instance_location.as_str())
+      .collect();
+    assert!(instance_locations.contains("/properties/b/type"));
+    assert!(instance_locations.contains("/properties/c/minLength"));
+
+    // the meta schema is loaded in it's own context
+    assert!(
+      document_context
+        .resolve_document_retrieval_location(
+          &documents::draft_2020_12::META_SCHEMA_ID.parse().unwrap()
+        )
+        .is_err()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_validate_documents_unavailable() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    // a dialect that we can load, but of which the meta schema cannot be retrieved
+    document_context
+      .register_factory(
+        "https://schemas.example.com/meta.json",
+        Box::new(|context, configuration| {
+          Ok(rc::Rc::new(documents::draft_2020_12::Document::new(
+            context,
+            configuration.given_location,
+            configuration.antecedent_location,
+            configuration.document_node.into(),
+          )?))
+        }),
+      )
+      .unwrap();
+    document_context.set_fetcher(crate::utilities::MemoryFetcher::new().with_document(
+      "https://schemas.example.com/a.json",
+      r#"{ "$schema": "https://schemas.example.com/meta.json", "type": "object" }"#,
+    ));
+
+    let location: NodeLocation = "https://schemas.example.com/a.json".parse().unwrap();
+
+    document_context
+      .load_from_location(
+        location.clone(),
+        location.clone(),
+        None,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    assert!(matches!(
+      document_context
+        .validate_documents(documents::draft_2020_12::META_SCHEMA_ID)
+        .await,
+      Err(Error::FetchError(..))
+    ));
+  }
+
+  #[tokio::test]
+  async fn test_validate_documents_oas_v3_1_dialect() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    // the dialect and it's vocabulary are embedded copies, nothing else is served
+    document_context.set_fetcher(crate::utilities::MemoryFetcher::new().with_document(
+      "https://schemas.example.com/a.json",
+      r#"{ "$ref": "https://spec.openapis.org/oas/3.1/dialect/base" }"#,
+    ));
+    document_context.load_well_known_schemas().unwrap();
+
+    let location: NodeLocation = "https://schemas.example.com/a.json".parse().unwrap();
+
+    document_context
+      .load_from_location(
+        location.clone(),
+        location.clone(),
+        None,
+        documents::oas_v3_1::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let results = document_context
+      .validate_documents(documents::oas_v3_1::META_SCHEMA_ID)
+      .await
+      .unwrap();
+    assert!(results.is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_load_errors() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    document_context.set_fetcher(
+      crate::utilities::MemoryFetcher::new()
+        .with_document(
+          "https://schemas.example.com/entry.json",
+          r#"{
+            "properties": {
+              "a": { "$ref": "broken.yaml" },
+              "b": { "$ref": "missing.json" },
+              "c": { "$ref": "typo.json" }
+            }
+          }"#,
+        )
+        .with_document("https://schemas.example.com/broken.yaml", "a: [\nb: 1\n")
+        .with_document(
+          "https://schemas.example.com/typo.json",
+          r#"{ "properties": { "d": { "type": "strng" }, "e": { "type": ["string", "nmber"] } } }"#,
+        ),
+    );
+
+    let location: NodeLocation = "https://schemas.example.com/entry.json".parse().unwrap();
+
+    // every document that could not be loaded is reported
+    let error = document_context
+      .load_from_location(
+        location.clone(),
+        location.clone(),
+        None,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap_err();
+    let Error::Multiple(errors) = error else {
+      panic!("expected multiple errors, got {}", error);
+    };
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors.iter().any(|error| matches!(
+      error,
+      Error::SerializationError {
+        location,
+        line: Some(_),
+        column: Some(_),
+        ..
+      } if *location == "https://schemas.example.com/broken.yaml".parse().unwrap()
+    )));
+    assert!(errors.iter().any(|error| matches!(
+      error,
+      Error::FetchError(location, crate::utilities::FetchTextError::NotFound)
+        if *location == "https://schemas.example.com/missing.json".parse().unwrap()
+    )));
+
+    // and every node that could not be read
+    let error = document_context.get_schema_nodes().unwrap_err();
+    let Error::Multiple(errors) = error else {
+      panic!("expected multiple errors");
+    };
+    let mut types: Vec<_> = errors
+      .iter()
+      .filter_map(|error| match error {
+        Error::InvalidType(location, r#type) => Some((location.to_string(), r#type.as_str())),
+        _ => None,
+      })
+      .collect();
+    types.sort();
+    assert_eq!(
+      types,
+      vec![
+        (
+          "https://schemas.example.com/typo.json#/properties/d".to_owned(),
+          "strng"
+        ),
+        (
+          "https://schemas.example.com/typo.json#/properties/e".to_owned(),
+          "nmber"
+        ),
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_get_source_position() {
+    let mut document_context = rc::Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    document_context.set_fetcher(
+      crate::utilities::MemoryFetcher::new()
+        .with_document(
+          "https://schemas.example.com/entry.json",
+          "{\n  \"$defs\": {\n    \"a\": { \"$ref\": \"other.yaml#/$defs/b\" }\n  }\n}\n",
+        )
+        .with_document(
+          "https://schemas.example.com/other.yaml",
+          "$defs:\n  b:\n    type: string\n",
+        ),
+    );
+
+    let location: NodeLocation = "https://schemas.example.com/entry.json".parse().unwrap();
+    document_context
+      .load_from_location(
+        location.clone(),
+        location.clone(),
+        None,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let position = document_context
+      .get_source_position(&location.set_pointer(vec!["$defs".into(), "a".into()]))
+      .unwrap();
+    assert_eq!((position.line, position.column), (3, 10));
+
+    let other_location: NodeLocation = "https://schemas.example.com/other.yaml".parse().unwrap();
+    let position = document_context
+      .get_source_position(&other_location.set_pointer(vec![
+        "$defs".into(),
+        "b".into(),
+        "type".into(),
+      ]))
+      .unwrap();
+    assert_eq!(position.location, other_location);
+    assert_eq!((position.line, position.column), (3, 11));
+    assert_eq!(
+      position.to_string(),
+      "https://schemas.example.com/other.yaml:3:11"
+    );
+  }
 }
@@ -1 +1,6 @@
 pub const META_SCHEMA_ID: &str = "http://json-schema.org/draft-04/schema#";
+
+/// Copies of the meta schemas of this dialect, indexed by their id. Documents are validated
+/// against these so no network is needed.
+pub const WELL_KNOWN_SCHEMAS: &[(&str, &str)] =
+  &[(META_SCHEMA_ID, include_str!("schemas/schema.json"))];
@@ -1,8 +1,9 @@
 use crate::{
+  error::Error,
   models::{DocumentSchemaItem, SchemaType},
   utilities::{JsonValue, NodeLocation},
 };
-use std::iter::empty;
+use std::{collections::BTreeMap, iter::empty};
 
 #[derive(Clone, Debug)]
 pub struct Node(JsonValue);
@@ -20,7 +21,10 @@ impl From<JsonValue> for Node {
 }
 
 impl Node {
-  pub fn to_document_schema_item(&self, location: NodeLocation) -> DocumentSchemaItem {
+  pub fn to_document_schema_item(
+    &self,
+    location: NodeLocation,
+  ) -> Result<DocumentSchemaItem, Error> {
     let types = if let Some(value) = self.0.as_bool() {
       if value {
         Some(vec![SchemaType::Any])
@@ -28,26 +32,54 @@ impl Node {
         Some(vec![SchemaType::Never])
       }
     } else if let Some(value) = self.0.string("type") {
-      Some(vec![(value).parse().unwrap()])
+      Some(vec![value.parse().map_err(|_error| {
+        Error::InvalidType(location.clone(), value.to_owned())
+      })?])
     } else {
       self
         .0
         .string_list("type")
-        .map(|value| value.map(|value| value.parse().unwrap()).collect())
+        .map(|value| {
+          value
+            .map(|value| {
+              value
+                .parse()
+                .map_err(|_error| Error::InvalidType(location.clone(), value.to_owned()))
+            })
+            .collect::<Result<_, _>>()
+        })
+        .transpose()?
     };
 
-    let reference = self.select_reference().map(|value| {
-      let reference_location = value.parse().unwrap();
-      location.join(&reference_location)
-    });
+    let reference = self
+      .select_reference()
+      .map(|value| {
+        value
+          .parse()
+          .map(|reference_location| location.join(&reference_location))
+          .map_err(|_error| Error::InvalidReference(location.clone(), value.to_owned()))
+      })
+      .transpose()?;
+
+    // dependencies holds lists of property names and schemas
+    let dependent_required: Option<BTreeMap<_, _>> = self
+      .0
+      .string_list_object("dependencies")
+      .map(|value| {
+        value
+          .map(|(name, required)| (name.to_owned(), required.map(str::to_owned).collect()))
+          .collect()
+      })
+      .filter(|value: &BTreeMap<_, _>| !value.is_empty());
 
-    DocumentSchemaItem {
+    Ok(DocumentSchemaItem {
       location: Some(location.clone()),
       name: None,
 
       exact: Some(true),
 
       reference,
+      reference_keyword: None,
       types,
 
       // meta
@@ -65,37 +97,42 @@ impl Node {
       minimum_inclusive: if self.0.bool("exclusiveMinimum").unwrap_or_default() {
         None
       } else {
-        self.0.float("minimum")
+        self.0.decimal("minimum")
       },
       minimum_exclusive: if self.0.bool("exclusiveMinimum").unwrap_or_default() {
-        self.0.float("minimum")
+        self.0.decimal("minimum")
       } else {
         None
       },
       maximum_inclusive: if self.0.bool("exclusiveMaximum").unwrap_or_default() {
         None
       } else {
-        self.0.float("maximum")
+        self.0.decimal("maximum")
       },
       maximum_exclusive: if self.0.bool("exclusiveMaximum").unwrap_or_default() {
-        self.0.float("maximum")
+        self.0.decimal("maximum")
       } else {
         None
       },
-      multiple_of: self.0.float("multipleOf"),
+      multiple_of: self.0.decimal("multipleOf"),
       minimum_length: self.0.unsigned_integer("minLength"),
       maximum_length: self.0.unsigned_integer("maxLength"),
       value_pattern: self.0.string("pattern").map(str::to_owned),
       value_format: self.0.string("format").map(str::to_owned),
+      content_encoding: None,
+      content_media_type: None,
       minimum_items: self.0.unsigned_integer("minItems"),
       maximum_items: self.0.unsigned_integer("maxItems"),
       unique_items: self.0.bool("uniqueItems"),
+      minimum_contains: None,
+      maximum_contains: None,
       minimum_properties: self.0.unsigned_integer("minProperties"),
       maximum_properties: self.0.unsigned_integer("maxProperties"),
       required: self
         .0
         .string_list("required")
         .map(|value| value.map(str::to_owned).collect()),
+      dependent_required: dependent_required.clone(),
 
       r#if: None,
       then: None,
@@ -107,6 +144,9 @@ impl Node {
         .or_else(|| self.0.node_location(&location, "additionalItems")),
       property_names: None,
       contains: None,
+      unevaluated_properties: None,
+      unevaluated_items: None,
+      content_schema: None,
 
       all_of: self
         .0
@@ -131,8 +171,17 @@ impl Node {
 
       dependent_schemas: self
         .0
-        .node_location_object(&location, "dependentSchemas")
-        .map(|value| value.collect()),
+        .node_location_object(&location, "dependencies")
+        .map(|value| {
+          value
+            .filter(|(name, _location)| {
+              dependent_required
+                .as_ref()
+                .is_none_or(|dependent_required| !dependent_required.contains_key(name))
+            })
+            .collect()
+        })
+        .filter(|value: &BTreeMap<_, _>| !value.is_empty()),
       object_properties: self
         .0
         .node_location_object(&location, "properties")
@@ -141,7 +190,7 @@ impl Node {
         .0
         .node_location_object(&location, "patternProperties")
         .map(|value| value.collect()),
-    }
+    })
   }
 }
 
@@ -197,9 +246,10 @@ impl Node {
       .chain(
         self
           .0
-          .node_entry_object(pointer, "dependentSchemas")
+          .node_entry_object(pointer, "dependencies")
           .into_iter()
-          .flatten(),
+          .flatten()
+          .filter(|(_pointer, node)| !node.as_value().is_array()),
       )
       .chain(
         self
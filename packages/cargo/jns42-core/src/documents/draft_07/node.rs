@@ -1,6 +1,7 @@
-use std::iter;
+use std::{collections::BTreeMap, iter};
 
 use crate::{
+  error::Error,
   models::{DocumentSchemaItem, SchemaType},
   utilities::{JsonValue, NodeLocation},
 };
@@ -21,7 +22,10 @@ impl From<JsonValue> for Node {
 }
 
 impl Node {
-  pub fn to_document_schema_item(&self, location: NodeLocation) -> DocumentSchemaItem {
+  pub fn to_document_schema_item(
+    &self,
+    location: NodeLocation,
+  ) -> Result<DocumentSchemaItem, Error> {
     let types = if let Some(value) = self.0.as_bool() {
       if value {
         Some(vec![SchemaType::Any])
@@ -29,26 +33,54 @@ impl Node {
         Some(vec![SchemaType::Never])
       }
     } else if let Some(value) = self.0.string("type") {
-      Some(vec![(value).parse().unwrap()])
+      Some(vec![value.parse().map_err(|_error| {
+        Error::InvalidType(location.clone(), value.to_owned())
+      })?])
     } else {
       self
         .0
         .string_list("type")
-        .map(|value| value.map(|value| value.parse().unwrap()).collect())
+        .map(|value| {
+          value
+            .map(|value| {
+              value
+                .parse()
+                .map_err(|_error| Error::InvalidType(location.clone(), value.to_owned()))
+            })
+            .collect::<Result<_, _>>()
+        })
+        .transpose()?
     };
 
-    let reference = self.select_reference().map(|value| {
-      let reference_location = value.parse().unwrap();
-      location.join(&reference_location)
-    });
+    let reference = self
+      .select_reference()
+      .map(|value| {
+        value
+          .parse()
+          .map(|reference_location| location.join(&reference_location))
+          .map_err(|_error| Error::InvalidReference(location.clone(), value.to_owned()))
+      })
+      .transpose()?;
+
+    // dependencies holds lists of property names and schemas
+    let dependent_required: Option<BTreeMap<_, _>> = self
+      .0
+      .string_list_object("dependencies")
+      .map(|value| {
+        value
+          .map(|(name, required)| (name.to_owned(), required.map(str::to_owned).collect()))
+          .collect()
+      })
+      .filter(|value: &BTreeMap<_, _>| !value.is_empty());
 
-    DocumentSchemaItem {
+    Ok(DocumentSchemaItem {
       location: Some(location.clone()),
       name: None,
 
       exact: Some(true),
 
       reference,
+      reference_keyword: None,
       types,
 
       // meta
@@ -76,24 +108,29 @@ impl Node {
             .map(|value| value.cloned().collect())
         }),
 
-      minimum_inclusive: self.0.float("minimum"),
-      minimum_exclusive: self.0.float("exclusiveMinimum"),
-      maximum_inclusive: self.0.float("maximum"),
-      maximum_exclusive: self.0.float("exclusiveMaximum"),
-      multiple_of: self.0.float("multipleOf"),
+      minimum_inclusive: self.0.decimal("minimum"),
+      minimum_exclusive: self.0.decimal("exclusiveMinimum"),
+      maximum_inclusive: self.0.decimal("maximum"),
+      maximum_exclusive: self.0.decimal("exclusiveMaximum"),
+      multiple_of: self.0.decimal("multipleOf"),
       minimum_length: self.0.unsigned_integer("minLength"),
       maximum_length: self.0.unsigned_integer("maxLength"),
       value_pattern: self.0.string("pattern").map(str::to_owned),
       value_format: self.0.string("format").map(str::to_owned),
+      content_encoding: self.0.string("contentEncoding").map(str::to_owned),
+      content_media_type: self.0.string("contentMediaType").map(str::to_owned),
       minimum_items: self.0.unsigned_integer("minItems"),
       maximum_items: self.0.unsigned_integer("maxItems"),
       unique_items: self.0.bool("uniqueItems"),
+      minimum_contains: None,
+      maximum_contains: None,
       minimum_properties: self.0.unsigned_integer("minProperties"),
       maximum_properties: self.0.unsigned_integer("maxProperties"),
       required: self
         .0
         .string_list("required")
         .map(|value| value.map(str::to_owned).collect()),
+      dependent_required: dependent_required.clone(),
 
       r#if: self.0.node_location(&location, "if"),
       then: self.0.node_location(&location, "then"),
@@ -105,6 +142,9 @@ impl Node {
         .or_else(|| self.0.node_location(&location, "additionalItems")),
       property_names: self.0.node_location(&location, "propertyNames"),
       contains: self.0.node_location(&location, "contains"),
+      unevaluated_properties: None,
+      unevaluated_items: None,
+      content_schema: None,
 
       all_of: self
         .0
@@ -129,8 +169,17 @@ impl Node {
 
       dependent_schemas: self
         .0
-        .node_location_object(&location, "dependentSchemas")
-        .map(|value| value.collect()),
+        .node_location_object(&location, "dependencies")
+        .map(|value| {
+          value
+            .filter(|(name, _location)| {
+              dependent_required
+                .as_ref()
+                .is_none_or(|dependent_required| !dependent_required.contains_key(name))
+            })
+            .collect()
+        })
+        .filter(|value: &BTreeMap<_, _>| !value.is_empty()),
       object_properties: self
         .0
         .node_location_object(&location, "properties")
@@ -139,7 +188,7 @@ impl Node {
         .0
         .node_location_object(&location, "patternProperties")
         .map(|value| value.collect()),
-    }
+    })
   }
 }
 
@@ -160,9 +209,14 @@ impl Node {
     pointer: &[String],
   ) -> impl Iterator<Item = (Vec<String>, Node)> + '_ {
     iter::empty()
+      .chain(self.0.node_entry(pointer, "if"))
+      .chain(self.0.node_entry(pointer, "then"))
+      .chain(self.0.node_entry(pointer, "else"))
       .chain(self.0.node_entry(pointer, "not"))
+      .chain(self.0.node_entry(pointer, "propertyNames"))
       .chain(self.0.node_entry(pointer, "additionalProperties"))
       .chain(self.0.node_entry(pointer, "items"))
+      .chain(self.0.node_entry(pointer, "contains"))
       .chain(self.0.node_entry(pointer, "additionalItems"))
       .chain(
         self
@@ -195,9 +249,10 @@ impl Node {
       .chain(
         self
           .0
-          .node_entry_object(pointer, "dependentSchemas")
+          .node_entry_object(pointer, "dependencies")
           .into_iter()
-          .flatten(),
+          .flatten()
+          .filter(|(_pointer, node)| !node.as_value().is_array()),
       )
       .chain(
         self
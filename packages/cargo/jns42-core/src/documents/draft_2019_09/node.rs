@@ -1,5 +1,6 @@
 use super::Document;
 use crate::{
+  error::Error,
   models::{DocumentSchemaItem, SchemaType},
   utilities::{JsonValue, NodeLocation},
 };
@@ -25,7 +26,7 @@ impl Node {
     &self,
     location: NodeLocation,
     document: &Document,
-  ) -> DocumentSchemaItem {
+  ) -> Result<DocumentSchemaItem, Error> {
     let types = if let Some(value) = self.0.as_bool() {
       if value {
         Some(vec![SchemaType::Any])
@@ -33,12 +34,23 @@ impl Node {
         Some(vec![SchemaType::Never])
       }
     } else if let Some(value) = self.0.string("type") {
-      Some(vec![(value).parse().unwrap()])
+      Some(vec![value.parse().map_err(|_error| {
+        Error::InvalidType(location.clone(), value.to_owned())
+      })?])
     } else {
       self
         .0
         .string_list("type")
-        .map(|value| value.map(|value| value.parse().unwrap()).collect())
+        .map(|value| {
+          value
+            .map(|value| {
+              value
+                .parse()
+                .map_err(|_error| Error::InvalidType(location.clone(), value.to_owned()))
+            })
+            .collect::<Result<_, _>>()
+        })
+        .transpose()?
     };
 
     let reference = None
@@ -46,22 +58,28 @@ impl Node {
         self
           .0
           .string("$ref")
-          .map(|value| document.resolve_reference(value).unwrap())
+          .map(|value| document.resolve_reference(value))
       })
       .or_else(|| {
         self
           .0
           .string("$recursiveRef")
-          .map(|value| document.resolve_recursive_reference(value).unwrap())
-      });
+          .map(|value| document.resolve_recursive_reference(value))
+      })
+      .transpose()?;
+    let reference_keyword = ["$ref", "$recursiveRef"]
+      .into_iter()
+      .find(|keyword| self.0.string(keyword).is_some())
+      .map(str::to_owned);
 
-    DocumentSchemaItem {
+    Ok(DocumentSchemaItem {
       location: Some(location.clone()),
       name: None,
 
       exact: Some(true),
 
       reference,
+      reference_keyword,
       types,
 
       // meta
@@ -89,24 +107,33 @@ impl Node {
             .map(|value| value.cloned().collect())
         }),
 
-      minimum_inclusive: self.0.float("minimum"),
-      minimum_exclusive: self.0.float("exclusiveMinimum"),
-      maximum_inclusive: self.0.float("maximum"),
-      maximum_exclusive: self.0.float("exclusiveMaximum"),
-      multiple_of: self.0.float("multipleOf"),
+      minimum_inclusive: self.0.decimal("minimum"),
+      minimum_exclusive: self.0.decimal("exclusiveMinimum"),
+      maximum_inclusive: self.0.decimal("maximum"),
+      maximum_exclusive: self.0.decimal("exclusiveMaximum"),
+      multiple_of: self.0.decimal("multipleOf"),
       minimum_length: self.0.unsigned_integer("minLength"),
       maximum_length: self.0.unsigned_integer("maxLength"),
       value_pattern: self.0.string("pattern").map(str::to_owned),
       value_format: self.0.string("format").map(str::to_owned),
+      content_encoding: self.0.string("contentEncoding").map(str::to_owned),
+      content_media_type: self.0.string("contentMediaType").map(str::to_owned),
       minimum_items: self.0.unsigned_integer("minItems"),
       maximum_items: self.0.unsigned_integer("maxItems"),
       unique_items: self.0.bool("uniqueItems"),
+      minimum_contains: self.0.unsigned_integer("minContains"),
+      maximum_contains: self.0.unsigned_integer("maxContains"),
       minimum_properties: self.0.unsigned_integer("minProperties"),
       maximum_properties: self.0.unsigned_integer("maxProperties"),
       required: self
         .0
         .string_list("required")
         .map(|value| value.map(str::to_owned).collect()),
+      dependent_required: self.0.string_list_object("dependentRequired").map(|value| {
+        value
+          .map(|(name, required)| (name.to_owned(), required.map(str::to_owned).collect()))
+          .collect()
+      }),
 
       r#if: self.0.node_location(&location, "if"),
       then: self.0.node_location(&location, "then"),
@@ -118,6 +145,9 @@ impl Node {
         .or_else(|| self.0.node_location(&location, "additionalItems")),
       property_names: self.0.node_location(&location, "propertyNames"),
       contains: self.0.node_location(&location, "contains"),
+      unevaluated_properties: self.0.node_location(&location, "unevaluatedProperties"),
+      unevaluated_items: self.0.node_location(&location, "unevaluatedItems"),
+      content_schema: self.0.node_location(&location, "contentSchema"),
 
       all_of: self
         .0
@@ -152,7 +182,7 @@ impl Node {
         .0
         .node_location_object(&location, "patternProperties")
         .map(|value| value.collect()),
-    }
+    })
   }
 }
 
@@ -181,9 +211,17 @@ impl Node {
     pointer: &[String],
   ) -> impl Iterator<Item = (Vec<String>, Node)> + '_ {
     iter::empty()
+      .chain(self.0.node_entry(pointer, "if"))
+      .chain(self.0.node_entry(pointer, "then"))
+      .chain(self.0.node_entry(pointer, "else"))
       .chain(self.0.node_entry(pointer, "not"))
+      .chain(self.0.node_entry(pointer, "propertyNames"))
       .chain(self.0.node_entry(pointer, "additionalProperties"))
+      .chain(self.0.node_entry(pointer, "unevaluatedProperties"))
+      .chain(self.0.node_entry(pointer, "unevaluatedItems"))
+      .chain(self.0.node_entry(pointer, "contentSchema"))
       .chain(self.0.node_entry(pointer, "items"))
+      .chain(self.0.node_entry(pointer, "contains"))
       .chain(self.0.node_entry(pointer, "additionalItems"))
       .chain(
         self
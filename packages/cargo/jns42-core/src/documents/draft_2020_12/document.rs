@@ -18,6 +18,10 @@ pub struct Document {
   */
   nodes: BTreeMap<Vec<String>, Node>,
   referenced_locations: Vec<NodeLocation>,
+  /**
+  Identifiers of embedded documents, indexed by their pointer
+  */
+  embedded_locations: BTreeMap<Vec<String>, NodeLocation>,
 
   // maps anchors to their pointers
   anchors: BTreeMap<String, Vec<String>>,
@@ -34,7 +38,9 @@ impl Document {
     let node_id = document_node.select_id();
 
     let identity_location = if let Some(node_id) = node_id {
-      let node_location = node_id.parse()?;
+      let node_location = node_id
+        .parse()
+        .map_err(|_error| Error::InvalidIdentifier(given_location.clone(), node_id.to_owned()))?;
       if let Some(antecedent_location) = &antecedent_location {
         antecedent_location.join(&node_location)
       } else {
@@ -46,6 +52,7 @@ impl Document {
 
     let mut nodes = BTreeMap::new();
     let mut referenced_locations = Vec::new();
+    let mut embedded_locations = BTreeMap::new();
     let mut anchors = BTreeMap::new();
     let mut dynamic_anchors = BTreeMap::new();
 
@@ -55,30 +62,43 @@ impl Document {
       assert!(nodes.insert(node_pointer.clone(), node.clone()).is_none());
 
       if let Some(node_anchor) = node.select_anchor() {
-        assert!(
-          anchors
-            .insert(node_anchor.to_owned(), node_pointer.clone())
-            .is_none()
-        );
+        let previous_pointer = anchors.insert(node_anchor.to_owned(), node_pointer.clone());
+        if previous_pointer.is_some() {
+          Err(Error::DuplicateAnchor(
+            identity_location.set_anchor(node_anchor.to_owned()),
+          ))?
+        }
       }
 
       if let Some(node_dynamic_anchor) = node.select_dynamic_anchor() {
-        assert!(
-          dynamic_anchors
-            .insert(node_dynamic_anchor.to_owned(), node_pointer.clone())
-            .is_none()
-        );
+        let previous_pointer =
+          dynamic_anchors.insert(node_dynamic_anchor.to_owned(), node_pointer.clone());
+        if previous_pointer.is_some() {
+          Err(Error::DuplicateAnchor(
+            identity_location.set_anchor(node_dynamic_anchor.to_owned()),
+          ))?
+        }
       }
 
       if let Some(node_reference) = node.select_reference() {
-        let reference_location: NodeLocation = node_reference.parse()?;
+        let reference_location: NodeLocation = node_reference.parse().map_err(|_error| {
+          Error::InvalidReference(
+            identity_location.push_pointer(node_pointer.clone()),
+            node_reference.to_owned(),
+          )
+        })?;
         referenced_locations.push(reference_location);
       }
 
       for (sub_pointer, sub_node) in node.select_sub_nodes(&node_pointer) {
         if let Some(node_id) = sub_node.select_id() {
-          let id_location: NodeLocation = node_id.parse()?;
-          referenced_locations.push(id_location);
+          let id_location: NodeLocation = node_id.parse().map_err(|_error| {
+            Error::InvalidIdentifier(
+              identity_location.push_pointer(sub_pointer.clone()),
+              node_id.to_owned(),
+            )
+          })?;
+          embedded_locations.insert(sub_pointer, id_location);
           /*
           if we found an embedded document then we don't include it in the nodes
           */
@@ -95,6 +115,7 @@ impl Document {
       identity_location,
       nodes,
       referenced_locations,
+      embedded_locations,
       anchors,
       dynamic_anchors,
     })
@@ -142,7 +163,7 @@ impl Document {
           return Ok(reference_location);
         };
       } else {
-        return Err(Error::Unknown);
+        return Err(Error::ReferenceNotFound(reference_location));
       }
     }
 
@@ -175,18 +196,18 @@ impl SchemaDocument for Document {
     self.referenced_locations.clone()
   }
 
-  fn get_schema_nodes(&self) -> BTreeMap<NodeLocation, DocumentSchemaItem> {
-    self
-      .nodes
-      .iter()
-      .map(|(pointer, node)| {
-        let location = self.get_identity_location().push_pointer(pointer.clone());
-        (
-          location.clone(),
-          node.to_document_schema_item(location, self),
-        )
-      })
-      .collect()
+  fn get_embedded_locations(&self) -> BTreeMap<Vec<String>, NodeLocation> {
+    self.embedded_locations.clone()
+  }
+
+  fn get_schema_nodes(&self) -> Result<BTreeMap<NodeLocation, DocumentSchemaItem>, Error> {
+    Error::collect_results(self.nodes.iter().map(|(pointer, node)| {
+      let location = self.get_identity_location().push_pointer(pointer.clone());
+      Ok((
+        location.clone(),
+        node.to_document_schema_item(location, self)?,
+      ))
+    }))
   }
 
   fn resolve_anchor(&self, anchor: &str) -> Option<Vec<String>> {
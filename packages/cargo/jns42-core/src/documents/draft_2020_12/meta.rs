@@ -1 +1,39 @@
 pub const META_SCHEMA_ID: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Copies of the meta schemas of this dialect, indexed by their id. Documents are validated
+/// against these so no network is needed.
+pub const WELL_KNOWN_SCHEMAS: &[(&str, &str)] = &[
+  (META_SCHEMA_ID, include_str!("schemas/schema.json")),
+  (
+    "https://json-schema.org/draft/2020-12/meta/core",
+    include_str!("schemas/core.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/applicator",
+    include_str!("schemas/applicator.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/unevaluated",
+    include_str!("schemas/unevaluated.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/validation",
+    include_str!("schemas/validation.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/meta-data",
+    include_str!("schemas/meta-data.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/format-annotation",
+    include_str!("schemas/format-annotation.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/format-assertion",
+    include_str!("schemas/format-assertion.json"),
+  ),
+  (
+    "https://json-schema.org/draft/2020-12/meta/content",
+    include_str!("schemas/content.json"),
+  ),
+];
@@ -25,6 +25,53 @@ pub fn discover_meta_schema_id(version_node: &serde_json::Value) -> Option<&str>
   None
 }
 
+/// Every embedded copy of a meta schema or api description schema, as id and text.
+///
+pub fn get_well_known_schemas() -> impl Iterator<Item = (&'static str, &'static str)> {
+  [
+    super::draft_04::WELL_KNOWN_SCHEMAS,
+    super::draft_06::WELL_KNOWN_SCHEMAS,
+    super::draft_07::WELL_KNOWN_SCHEMAS,
+    super::draft_2019_09::WELL_KNOWN_SCHEMAS,
+    super::draft_2020_12::WELL_KNOWN_SCHEMAS,
+    super::oas_v3_0::WELL_KNOWN_SCHEMAS,
+    super::oas_v3_1::WELL_KNOWN_SCHEMAS,
+    super::swagger_v2::WELL_KNOWN_SCHEMAS,
+  ]
+  .into_iter()
+  .flatten()
+  .copied()
+}
+
+/// Discover the id of the schema that the whole document is validated against. For api
+/// descriptions this is the schema of the description, for schema documents this is the
+/// meta schema.
+///
+pub fn discover_document_schema_id<'a>(
+  document_node: &'a serde_json::Value,
+  default_meta_schema_id: &'a str,
+) -> Option<&'a str> {
+  if select_swagger(document_node).is_some() {
+    return Some(super::swagger_v2::DOCUMENT_SCHEMA_ID);
+  }
+
+  if let Some(version) = select_openapi(document_node) {
+    let version = Version::parse(version).ok()?;
+
+    if version.major == 3 && version.minor == 0 {
+      return Some(super::oas_v3_0::DOCUMENT_SCHEMA_ID);
+    }
+
+    if version.major == 3 && version.minor == 1 {
+      return Some(super::oas_v3_1::DOCUMENT_SCHEMA_ID);
+    }
+
+    return None;
+  }
+
+  Some(select_schema(document_node).unwrap_or(default_meta_schema_id))
+}
+
 /// find the nearest ancestor (or self) node that defines the version of the
 /// node's schema
 ///
@@ -54,6 +101,10 @@ fn select_openapi(node: &serde_json::Value) -> Option<&str> {
   node.as_object()?.get("openapi")?.as_str()
 }
 
+fn select_swagger(node: &serde_json::Value) -> Option<&str> {
+  node.as_object()?.get("swagger")?.as_str()
+}
+
 fn select_json_schema_dialect(node: &serde_json::Value) -> Option<&str> {
   node.as_object()?.get("jsonSchemaDialect")?.as_str()
 }
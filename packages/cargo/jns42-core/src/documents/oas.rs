@@ -0,0 +1,546 @@
+/*
+Walks an OpenAPI (3.0 or 3.1) description to find all schemas in it. The schemas are
+returned with their pointer and a name that is derived from where they are used in the
+api, like the operationId and status code of a response.
+*/
+
+use crate::models::{
+  ApiBody, ApiHeader, ApiOperation, ApiParameter, ApiParameterLocation, ApiResponse,
+};
+use crate::utilities::NodeLocation;
+use std::iter;
+
+const METHODS: [&str; 8] = [
+  "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub(crate) struct ApiSchema<'v> {
+  pub pointer: Vec<String>,
+  pub name: Vec<String>,
+  pub node: &'v serde_json::Value,
+}
+
+/// All schemas in the api description, or nothing if the node is not an api description.
+pub(crate) fn select_api_schemas(node: &serde_json::Value) -> Vec<ApiSchema<'_>> {
+  let mut result = Vec::new();
+
+  let Some(node) = node.as_object() else {
+    return result;
+  };
+  if !node.contains_key("openapi") {
+    return result;
+  }
+
+  let mut walker = Walker {
+    result: &mut result,
+  };
+
+  for (path, path_item) in entries(node.get("paths")) {
+    let pointer = vec!["paths".to_owned(), path.to_owned()];
+    let name: Vec<_> = path
+      .split('/')
+      .map(|part| part.trim_start_matches('{').trim_end_matches('}'))
+      .filter(|part| !part.is_empty())
+      .map(str::to_owned)
+      .collect();
+    walker.path_item(pointer, name, path_item);
+  }
+
+  for (webhook, path_item) in entries(node.get("webhooks")) {
+    let pointer = vec!["webhooks".to_owned(), webhook.to_owned()];
+    walker.path_item(pointer, vec![webhook.to_owned()], path_item);
+  }
+
+  let components = node.get("components");
+  let component_entries = |field: &'static str| {
+    entries(components.and_then(|components| components.get(field))).map(move |(key, node)| {
+      (
+        vec!["components".to_owned(), field.to_owned(), key.to_owned()],
+        key,
+        node,
+      )
+    })
+  };
+
+  for (pointer, key, node) in component_entries("schemas") {
+    walker.schema(pointer, vec![key.to_owned()], node);
+  }
+  for (pointer, key, node) in component_entries("parameters") {
+    walker.parameter(pointer, vec![key.to_owned()], node);
+  }
+  for (pointer, key, node) in component_entries("requestBodies") {
+    walker.content(pointer, vec![format!("{} request body", key)], node);
+  }
+  for (pointer, key, node) in component_entries("responses") {
+    walker.response(pointer, vec![format!("{} response", key)], node);
+  }
+  for (pointer, key, node) in component_entries("headers") {
+    walker.header(pointer, vec![format!("{} header", key)], node);
+  }
+  for (pointer, key, node) in component_entries("pathItems") {
+    walker.path_item(pointer, vec![key.to_owned()], node);
+  }
+
+  result
+}
+
+struct Walker<'r, 'v> {
+  result: &'r mut Vec<ApiSchema<'v>>,
+}
+
+impl<'v> Walker<'_, 'v> {
+  fn schema(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    self.result.push(ApiSchema {
+      pointer,
+      name,
+      node,
+    });
+  }
+
+  fn path_item(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    if is_reference(node) {
+      return;
+    }
+
+    self.parameters(&pointer, &name, node);
+
+    for method in METHODS {
+      let Some(operation) = node.get(method) else {
+        continue;
+      };
+      let pointer = push(&pointer, [method]);
+      let name = match operation
+        .get("operationId")
+        .and_then(|value| value.as_str())
+      {
+        Some(operation_id) => vec![operation_id.to_owned()],
+        None => push(&[method.to_owned()], name.iter().map(String::as_str)),
+      };
+      self.operation(pointer, name, operation);
+    }
+  }
+
+  fn operation(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    self.parameters(&pointer, &name, node);
+
+    // bodies and responses are named as a whole, so every operation gets its own names
+    let operation_name = name.join(" ");
+
+    if let Some(request_body) = node.get("requestBody").filter(|node| !is_reference(node)) {
+      self.content(
+        push(&pointer, ["requestBody"]),
+        vec![format!("{} request body", operation_name)],
+        request_body,
+      );
+    }
+
+    for (status, response) in entries(node.get("responses")) {
+      self.response(
+        push(&pointer, ["responses", status]),
+        vec![format!("{} response {}", operation_name, status)],
+        response,
+      );
+    }
+  }
+
+  fn parameters(&mut self, pointer: &[String], name: &[String], node: &'v serde_json::Value) {
+    let Some(parameters) = node.get("parameters").and_then(|value| value.as_array()) else {
+      return;
+    };
+
+    for (index, parameter) in parameters.iter().enumerate() {
+      let Some(parameter_name) = parameter.get("name").and_then(|value| value.as_str()) else {
+        continue;
+      };
+      self.parameter(
+        push(pointer, ["parameters", index.to_string().as_str()]),
+        push(name, [parameter_name, "parameter"]),
+        parameter,
+      );
+    }
+  }
+
+  fn parameter(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    if is_reference(node) {
+      return;
+    }
+
+    if let Some(schema) = node.get("schema") {
+      self.schema(push(&pointer, ["schema"]), name.clone(), schema);
+    }
+    self.content(pointer, name, node);
+  }
+
+  fn response(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    if is_reference(node) {
+      return;
+    }
+
+    for (header, header_node) in entries(node.get("headers")) {
+      self.header(
+        push(&pointer, ["headers", header]),
+        push(&name, [format!("{} header", header).as_str()]),
+        header_node,
+      );
+    }
+    self.content(pointer, name, node);
+  }
+
+  fn header(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    if is_reference(node) {
+      return;
+    }
+
+    if let Some(schema) = node.get("schema") {
+      self.schema(push(&pointer, ["schema"]), name.clone(), schema);
+    }
+    self.content(pointer, name, node);
+  }
+
+  fn content(&mut self, pointer: Vec<String>, name: Vec<String>, node: &'v serde_json::Value) {
+    if is_reference(node) {
+      return;
+    }
+
+    let content: Vec<_> = entries(node.get("content")).collect();
+    let is_single = content.len() == 1;
+    for (media_type, media_type_node) in content {
+      let Some(schema) = media_type_node.get("schema") else {
+        continue;
+      };
+      // only use the media type in the name if there is more than one
+      let name = if is_single {
+        name.clone()
+      } else {
+        push(&name, [media_type])
+      };
+      self.schema(
+        push(&pointer, ["content", media_type, "schema"]),
+        name,
+        schema,
+      );
+    }
+  }
+}
+
+/// All operations in the api description, schemas are located relative to `location`, the
+/// identity of the description.
+pub(crate) fn select_api_operations(
+  node: &serde_json::Value,
+  location: &NodeLocation,
+) -> Vec<ApiOperation> {
+  let mut result = Vec::new();
+
+  if node.get("openapi").is_none() {
+    return result;
+  }
+
+  for (path, path_item) in entries(node.get("paths")) {
+    let pointer = vec!["paths".to_owned(), path.to_owned()];
+    let Some((pointer, path_item)) = resolve(node, pointer, path_item) else {
+      continue;
+    };
+    let path_parameters = select_parameters(node, location, &pointer, path_item);
+
+    for method in METHODS {
+      let Some(operation) = path_item.get(method) else {
+        continue;
+      };
+      let pointer = push(&pointer, [method]);
+
+      let name = match operation
+        .get("operationId")
+        .and_then(|value| value.as_str())
+      {
+        Some(operation_id) => vec![operation_id.to_owned()],
+        None => iter::once(method)
+          .chain(path.split('/'))
+          .map(|part| part.trim_start_matches('{').trim_end_matches('}'))
+          .filter(|part| !part.is_empty())
+          .map(str::to_owned)
+          .collect(),
+      };
+
+      // operation parameters override path parameters with the same name and location
+      let mut parameters = select_parameters(node, location, &pointer, operation);
+      for parameter in &path_parameters {
+        if !parameters
+          .iter()
+          .any(|other| other.name == parameter.name && other.location == parameter.location)
+        {
+          parameters.push(parameter.clone());
+        }
+      }
+      // the sort is stable, within a group the order of the description is kept
+      parameters.sort_by_key(|parameter| (parameter.location, !parameter.required));
+
+      let request_body = operation
+        .get("requestBody")
+        .and_then(|request_body| resolve(node, push(&pointer, ["requestBody"]), request_body))
+        .and_then(|(pointer, request_body)| {
+          let required = request_body
+            .get("required")
+            .and_then(|value| value.as_bool())
+            .unwrap_or_default();
+          select_body(location, &pointer, request_body, required)
+        });
+
+      let responses = entries(operation.get("responses"))
+        .filter_map(|(status, response)| {
+          let (pointer, response) = resolve(node, push(&pointer, ["responses", status]), response)?;
+          Some(ApiResponse {
+            status: status.to_owned(),
+            body: select_body(location, &pointer, response, true),
+            headers: select_headers(node, location, &pointer, response),
+          })
+        })
+        .collect();
+
+      result.push(ApiOperation {
+        name,
+        method: method.to_owned(),
+        path: path.to_owned(),
+        parameters,
+        request_body,
+        responses,
+      });
+    }
+  }
+
+  result
+}
+
+fn select_parameters(
+  root: &serde_json::Value,
+  location: &NodeLocation,
+  pointer: &[String],
+  node: &serde_json::Value,
+) -> Vec<ApiParameter> {
+  let Some(parameters) = node.get("parameters").and_then(|value| value.as_array()) else {
+    return Vec::new();
+  };
+
+  parameters
+    .iter()
+    .enumerate()
+    .filter_map(|(index, parameter)| {
+      let pointer = push(pointer, ["parameters", index.to_string().as_str()]);
+      let (pointer, parameter) = resolve(root, pointer, parameter)?;
+
+      let name = parameter.get("name")?.as_str()?.to_owned();
+      let parameter_location = match parameter.get("in")?.as_str()? {
+        "path" => ApiParameterLocation::Path,
+        "query" => ApiParameterLocation::Query,
+        "header" => ApiParameterLocation::Header,
+        "cookie" => ApiParameterLocation::Cookie,
+        _ => return None,
+      };
+      let required = parameter_location == ApiParameterLocation::Path
+        || parameter
+          .get("required")
+          .and_then(|value| value.as_bool())
+          .unwrap_or_default();
+
+      Some(ApiParameter {
+        name,
+        location: parameter_location,
+        required,
+        schema: select_schema(location, &pointer, parameter, required),
+      })
+    })
+    .collect()
+}
+
+fn select_headers(
+  root: &serde_json::Value,
+  location: &NodeLocation,
+  pointer: &[String],
+  node: &serde_json::Value,
+) -> Vec<ApiHeader> {
+  entries(node.get("headers"))
+    // the content type is described by the content of the response
+    .filter(|(name, _)| !name.eq_ignore_ascii_case("content-type"))
+    .filter_map(|(name, header)| {
+      let (pointer, header) = resolve(root, push(pointer, ["headers", name]), header)?;
+      let required = header
+        .get("required")
+        .and_then(|value| value.as_bool())
+        .unwrap_or_default();
+
+      Some(ApiHeader {
+        name: name.to_owned(),
+        required,
+        schema: select_schema(location, &pointer, header, required),
+      })
+    })
+    .collect()
+}
+
+/// The schema of a parameter or header, from the schema field or else from the content.
+fn select_schema(
+  location: &NodeLocation,
+  pointer: &[String],
+  node: &serde_json::Value,
+  required: bool,
+) -> Option<NodeLocation> {
+  match node.get("schema") {
+    Some(_) => Some(location.push_pointer(push(pointer, ["schema"]))),
+    None => select_body(location, pointer, node, required).and_then(|body| body.schema),
+  }
+}
+
+/// The body from the content of the node, json content is preferred.
+fn select_body(
+  location: &NodeLocation,
+  pointer: &[String],
+  node: &serde_json::Value,
+  required: bool,
+) -> Option<ApiBody> {
+  let content: Vec<_> = entries(node.get("content"))
+    .map(|(content_type, media_type)| ApiBody {
+      content_type: content_type.to_owned(),
+      required,
+      schema: media_type
+        .get("schema")
+        .map(|_schema| location.push_pointer(push(pointer, ["content", content_type, "schema"]))),
+    })
+    .collect();
+
+  let json_index = content
+    .iter()
+    .position(|body| body.is_json())
+    .unwrap_or_default();
+  content.into_iter().nth(json_index)
+}
+
+/// Follow references within the description, other references can not be resolved.
+fn resolve<'v>(
+  root: &'v serde_json::Value,
+  mut pointer: Vec<String>,
+  mut node: &'v serde_json::Value,
+) -> Option<(Vec<String>, &'v serde_json::Value)> {
+  // guard against circular references
+  for _ in 0..16 {
+    let Some(reference) = node.get("$ref") else {
+      return Some((pointer, node));
+    };
+    let reference: NodeLocation = reference.as_str()?.parse().ok()?;
+    if !reference.get_path().is_empty() {
+      return None;
+    }
+
+    pointer = reference.get_pointer().unwrap_or_default();
+    node = pointer.iter().try_fold(root, |node, part| match node {
+      serde_json::Value::Array(node) => node.get(part.parse::<usize>().ok()?),
+      serde_json::Value::Object(node) => node.get(part),
+      _ => None,
+    })?;
+  }
+
+  None
+}
+
+fn entries(node: Option<&serde_json::Value>) -> impl Iterator<Item = (&str, &serde_json::Value)> {
+  node
+    .and_then(|node| node.as_object())
+    .into_iter()
+    .flatten()
+    .map(|(key, value)| (key.as_str(), value))
+}
+
+fn is_reference(node: &serde_json::Value) -> bool {
+  node.get("$ref").is_some()
+}
+
+fn push<'a>(base: &[String], parts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+  base
+    .iter()
+    .cloned()
+    .chain(parts.into_iter().map(str::to_owned))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_select_api_schemas() {
+    let node = serde_json::json!({
+      "openapi": "3.0.3",
+      "paths": {
+        "/pets/{petId}": {
+          "parameters": [{ "name": "petId", "in": "path", "schema": { "type": "string" } }],
+          "get": {
+            "operationId": "getPet",
+            "responses": {
+              "200": {
+                "headers": { "x-next": { "schema": { "type": "string" } } },
+                "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } } }
+              },
+              "default": { "$ref": "#/components/responses/Error" }
+            }
+          },
+          "put": {
+            "requestBody": {
+              "content": {
+                "application/json": { "schema": { "$ref": "#/components/schemas/Pet" } },
+                "application/xml": { "schema": { "$ref": "#/components/schemas/Pet" } }
+              }
+            },
+            "responses": {}
+          }
+        }
+      },
+      "components": {
+        "schemas": {
+          "Pet": { "type": "object" }
+        },
+        "responses": {
+          "Error": {
+            "content": { "application/json": { "schema": { "type": "string" } } }
+          }
+        }
+      }
+    });
+
+    let mut schemas: Vec<_> = select_api_schemas(&node)
+      .into_iter()
+      .map(|schema| (schema.pointer.join("/"), schema.name.join(".")))
+      .collect();
+    schemas.sort();
+
+    assert_eq!(
+      schemas,
+      vec![
+        (
+          "components/responses/Error/content/application/json/schema".to_owned(),
+          "Error response".to_owned()
+        ),
+        ("components/schemas/Pet".to_owned(), "Pet".to_owned()),
+        (
+          "paths//pets/{petId}/get/responses/200/content/application/json/schema".to_owned(),
+          "getPet response 200".to_owned()
+        ),
+        (
+          "paths//pets/{petId}/get/responses/200/headers/x-next/schema".to_owned(),
+          "getPet response 200.x-next header".to_owned()
+        ),
+        (
+          "paths//pets/{petId}/parameters/0/schema".to_owned(),
+          "pets.petId.petId.parameter".to_owned()
+        ),
+        (
+          "paths//pets/{petId}/put/requestBody/content/application/json/schema".to_owned(),
+          "put pets petId request body.application/json".to_owned()
+        ),
+        (
+          "paths//pets/{petId}/put/requestBody/content/application/xml/schema".to_owned(),
+          "put pets petId request body.application/xml".to_owned()
+        ),
+      ]
+    );
+
+    assert!(select_api_schemas(&serde_json::json!({ "type": "object" })).is_empty());
+  }
+}
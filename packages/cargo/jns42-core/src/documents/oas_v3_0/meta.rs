@@ -1,2 +1,10 @@
 pub const META_SCHEMA_ID: &str =
   "https://spec.openapis.org/oas/3.0/schema/2021-09-28#/definitions/Schema";
+
+/// The schema of the api description document itself
+pub const DOCUMENT_SCHEMA_ID: &str = "https://spec.openapis.org/oas/3.0/schema/2021-09-28";
+
+/// A copy of the schema of the api description document. Descriptions are validated against
+/// this so no network is needed.
+pub const WELL_KNOWN_SCHEMAS: &[(&str, &str)] =
+  &[(DOCUMENT_SCHEMA_ID, include_str!("schemas/schema.json"))];
@@ -1,4 +1,6 @@
+use crate::documents::oas::select_api_schemas;
 use crate::{
+  error::Error,
   models::{DocumentSchemaItem, SchemaType},
   utilities::{JsonValue, NodeLocation},
 };
@@ -20,7 +22,10 @@ impl From<JsonValue> for Node {
 }
 
 impl Node {
-  pub fn to_document_schema_item(&self, location: NodeLocation) -> DocumentSchemaItem {
+  pub fn to_document_schema_item(
+    &self,
+    location: NodeLocation,
+  ) -> Result<DocumentSchemaItem, Error> {
     let types = {
       let node_type = self.0.string("type");
       let node_nullable = self.0.bool("nullable").unwrap_or_default();
@@ -29,30 +34,40 @@ impl Node {
         None
       } else {
         let types: Vec<_> = empty()
-          .chain(node_type.into_iter().map(|value| value.parse().unwrap()))
+          .chain(node_type.into_iter().map(|value| {
+            value
+              .parse()
+              .map_err(|_error| Error::InvalidType(location.clone(), value.to_owned()))
+          }))
           .chain(if node_nullable {
-            vec![SchemaType::Null]
+            vec![Ok(SchemaType::Null)]
           } else {
             vec![]
           })
-          .collect();
+          .collect::<Result<_, _>>()?;
 
         Some(types)
       }
     };
 
-    let reference = self.select_reference().map(|value| {
-      let reference_location = value.parse().unwrap();
-      location.join(&reference_location)
-    });
+    let reference = self
+      .select_reference()
+      .map(|value| {
+        value
+          .parse()
+          .map(|reference_location| location.join(&reference_location))
+          .map_err(|_error| Error::InvalidReference(location.clone(), value.to_owned()))
+      })
+      .transpose()?;
 
-    DocumentSchemaItem {
+    Ok(DocumentSchemaItem {
       location: Some(location.clone()),
       name: None,
 
       exact: Some(true),
 
       reference,
+      reference_keyword: None,
       types,
 
       // meta
@@ -70,37 +85,42 @@ impl Node {
       minimum_inclusive: if self.0.bool("exclusiveMinimum").unwrap_or_default() {
         None
       } else {
-        self.0.float("minimum")
+        self.0.decimal("minimum")
       },
       minimum_exclusive: if self.0.bool("exclusiveMinimum").unwrap_or_default() {
-        self.0.float("minimum")
+        self.0.decimal("minimum")
       } else {
         None
       },
       maximum_inclusive: if self.0.bool("exclusiveMaximum").unwrap_or_default() {
         None
       } else {
-        self.0.float("maximum")
+        self.0.decimal("maximum")
       },
       maximum_exclusive: if self.0.bool("exclusiveMaximum").unwrap_or_default() {
-        self.0.float("maximum")
+        self.0.decimal("maximum")
       } else {
         None
       },
-      multiple_of: self.0.float("multipleOf"),
+      multiple_of: self.0.decimal("multipleOf"),
       minimum_length: self.0.unsigned_integer("minLength"),
       maximum_length: self.0.unsigned_integer("maxLength"),
       value_pattern: self.0.string("pattern").map(str::to_owned),
       value_format: self.0.string("format").map(str::to_owned),
+      content_encoding: None,
+      content_media_type: None,
       minimum_items: self.0.unsigned_integer("minItems"),
       maximum_items: self.0.unsigned_integer("maxItems"),
       unique_items: self.0.bool("uniqueItems"),
+      minimum_contains: None,
+      maximum_contains: None,
       minimum_properties: self.0.unsigned_integer("minProperties"),
       maximum_properties: self.0.unsigned_integer("maxProperties"),
       required: self
         .0
         .string_list("required")
         .map(|value| value.map(str::to_owned).collect()),
+      dependent_required: None,
 
       r#if: None,
       then: None,
@@ -112,6 +132,9 @@ impl Node {
         .or_else(|| self.0.node_location(&location, "additionalItems")),
       property_names: None,
       contains: None,
+      unevaluated_properties: None,
+      unevaluated_items: None,
+      content_schema: None,
 
       all_of: self
         .0
@@ -143,7 +166,7 @@ impl Node {
         .0
         .node_location_object(&location, "patternProperties")
         .map(|value| value.collect()),
-    }
+    })
   }
 }
 
@@ -159,6 +182,14 @@ impl Node {
     self.0.string("$ref")
   }
 
+  /// Schemas in the api description, with their pointer and a name for the schema
+  ///
+  pub fn select_api_sub_nodes(&self) -> impl Iterator<Item = (Vec<String>, Vec<String>, Node)> {
+    select_api_schemas(self.0.as_value())
+      .into_iter()
+      .map(|schema| (schema.pointer, schema.name, schema.node.clone().into()))
+  }
+
   pub fn select_sub_nodes(
     &self,
     pointer: &[String],
@@ -18,6 +18,14 @@ pub struct Document {
   */
   nodes: BTreeMap<Vec<String>, Node>,
   referenced_locations: Vec<NodeLocation>,
+  /**
+  Identifiers of embedded documents, indexed by their pointer
+  */
+  embedded_locations: BTreeMap<Vec<String>, NodeLocation>,
+  /**
+  Names of the schemas in an api description, indexed by their pointer
+  */
+  api_names: BTreeMap<Vec<String>, Vec<String>>,
 
   // maps anchors to their pointers
   anchors: BTreeMap<String, Vec<String>>,
@@ -34,7 +42,9 @@ impl Document {
     let node_id = document_node.select_id();
 
     let identity_location = if let Some(node_id) = node_id {
-      let node_location = node_id.parse()?;
+      let node_location = node_id
+        .parse()
+        .map_err(|_error| Error::InvalidIdentifier(given_location.clone(), node_id.to_owned()))?;
       if let Some(antecedent_location) = &antecedent_location {
         antecedent_location.join(&node_location)
       } else {
@@ -46,39 +56,71 @@ impl Document {
 
     let mut nodes = BTreeMap::new();
     let mut referenced_locations = Vec::new();
+    let mut embedded_locations = BTreeMap::new();
+    let mut api_names = BTreeMap::new();
     let mut anchors = BTreeMap::new();
     let mut dynamic_anchors = BTreeMap::new();
 
     let mut node_queue = Vec::new();
-    node_queue.push((vec![], document_node));
+    node_queue.push((vec![], document_node.clone()));
+    // an api description is not a schema itself, but has schemas in many places
+    for (sub_pointer, sub_name, sub_node) in document_node.select_api_sub_nodes() {
+      api_names.insert(sub_pointer.clone(), sub_name);
+
+      if let Some(node_id) = sub_node.select_id() {
+        let id_location: NodeLocation = node_id.parse().map_err(|_error| {
+          Error::InvalidIdentifier(
+            identity_location.push_pointer(sub_pointer.clone()),
+            node_id.to_owned(),
+          )
+        })?;
+        embedded_locations.insert(sub_pointer, id_location);
+        continue;
+      }
+
+      node_queue.push((sub_pointer, sub_node));
+    }
     while let Some((node_pointer, node)) = node_queue.pop() {
       assert!(nodes.insert(node_pointer.clone(), node.clone()).is_none());
 
       if let Some(node_anchor) = node.select_anchor() {
-        assert!(
-          anchors
-            .insert(node_anchor.to_owned(), node_pointer.clone())
-            .is_none()
-        );
+        let previous_pointer = anchors.insert(node_anchor.to_owned(), node_pointer.clone());
+        if previous_pointer.is_some() {
+          Err(Error::DuplicateAnchor(
+            identity_location.set_anchor(node_anchor.to_owned()),
+          ))?
+        }
       }
 
       if let Some(node_dynamic_anchor) = node.select_dynamic_anchor() {
-        assert!(
-          dynamic_anchors
-            .insert(node_dynamic_anchor.to_owned(), node_pointer.clone())
-            .is_none()
-        );
+        let previous_pointer =
+          dynamic_anchors.insert(node_dynamic_anchor.to_owned(), node_pointer.clone());
+        if previous_pointer.is_some() {
+          Err(Error::DuplicateAnchor(
+            identity_location.set_anchor(node_dynamic_anchor.to_owned()),
+          ))?
+        }
       }
 
       if let Some(node_ref) = node.select_reference() {
-        let reference_location: NodeLocation = node_ref.parse()?;
+        let reference_location: NodeLocation = node_ref.parse().map_err(|_error| {
+          Error::InvalidReference(
+            identity_location.push_pointer(node_pointer.clone()),
+            node_ref.to_owned(),
+          )
+        })?;
         referenced_locations.push(reference_location.clone());
       }
 
       for (sub_pointer, sub_node) in node.select_sub_nodes(&node_pointer) {
         if let Some(node_id) = sub_node.select_id() {
-          let id_location: NodeLocation = node_id.parse()?;
-          referenced_locations.push(id_location);
+          let id_location: NodeLocation = node_id.parse().map_err(|_error| {
+            Error::InvalidIdentifier(
+              identity_location.push_pointer(sub_pointer.clone()),
+              node_id.to_owned(),
+            )
+          })?;
+          embedded_locations.insert(sub_pointer, id_location);
           /*
           if we found an embedded document then we don't include it in the nodes
           */
@@ -95,6 +137,8 @@ impl Document {
       identity_location,
       nodes,
       referenced_locations,
+      embedded_locations,
+      api_names,
       anchors,
       dynamic_anchors,
     })
@@ -142,7 +186,7 @@ impl Document {
           return Ok(reference_location);
         };
       } else {
-        return Err(Error::Unknown);
+        return Err(Error::ReferenceNotFound(reference_location));
       }
     }
 
@@ -175,18 +219,36 @@ impl SchemaDocument for Document {
     self.referenced_locations.clone()
   }
 
-  fn get_schema_nodes(&self) -> BTreeMap<NodeLocation, DocumentSchemaItem> {
-    self
-      .nodes
-      .iter()
-      .map(|(pointer, node)| {
-        let location = self.get_identity_location().push_pointer(pointer.clone());
-        (
-          location.clone(),
-          node.to_document_schema_item(location, self),
-        )
-      })
-      .collect()
+  fn get_embedded_locations(&self) -> BTreeMap<Vec<String>, NodeLocation> {
+    self.embedded_locations.clone()
+  }
+
+  fn get_schema_nodes(&self) -> Result<BTreeMap<NodeLocation, DocumentSchemaItem>, Error> {
+    Error::collect_results(self.nodes.iter().map(|(pointer, node)| {
+      let location = self.get_identity_location().push_pointer(pointer.clone());
+      let mut item = node.to_document_schema_item(location.clone(), self)?;
+
+      item.name = self.api_names.get(pointer).cloned();
+
+      // the schemas in an api description are definitions of the root
+      if pointer.is_empty() && !self.api_names.is_empty() {
+        item.definitions = Some(
+          item
+            .definitions
+            .into_iter()
+            .flatten()
+            .chain(
+              self
+                .api_names
+                .keys()
+                .map(|pointer| self.get_identity_location().push_pointer(pointer.clone())),
+            )
+            .collect(),
+        );
+      }
+
+      Ok((location, item))
+    }))
   }
 
   fn resolve_anchor(&self, anchor: &str) -> Option<Vec<String>> {
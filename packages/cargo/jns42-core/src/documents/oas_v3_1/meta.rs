@@ -1 +1,16 @@
 pub const META_SCHEMA_ID: &str = "https://spec.openapis.org/oas/3.1/dialect/base";
+
+/// The schema of the api description document itself
+pub const DOCUMENT_SCHEMA_ID: &str = "https://spec.openapis.org/oas/3.1/schema/2022-10-07";
+
+/// Copies of the schema of the api description document and of the meta schemas of the
+/// dialect, indexed by their id. Descriptions are validated against these so no network is
+/// needed.
+pub const WELL_KNOWN_SCHEMAS: &[(&str, &str)] = &[
+  (DOCUMENT_SCHEMA_ID, include_str!("schemas/schema.json")),
+  (META_SCHEMA_ID, include_str!("schemas/dialect.json")),
+  (
+    "https://spec.openapis.org/oas/3.1/meta/base",
+    include_str!("schemas/base.json"),
+  ),
+];
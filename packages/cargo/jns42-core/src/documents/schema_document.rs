@@ -1,8 +1,10 @@
-use crate::{models::DocumentSchemaItem, utilities::NodeLocation};
+use crate::{error::Error, models::DocumentSchemaItem, utilities::NodeLocation};
 use std::collections::BTreeMap;
 
 pub trait SchemaDocument {
   fn get_referenced_locations(&self) -> Vec<NodeLocation>;
+  /// identifiers of documents that are embedded in this document, indexed by their pointer
+  fn get_embedded_locations(&self) -> BTreeMap<Vec<String>, NodeLocation>;
 
   fn get_identity_location(&self) -> NodeLocation;
   //. gets the identity location of the antecedent
@@ -10,8 +12,9 @@ pub trait SchemaDocument {
   fn get_node_pointers(&self) -> Vec<Vec<String>>;
   fn get_node_anchors(&self) -> Vec<String>;
 
-  /// get all schema nodes in a map indexed by their identity location
-  fn get_schema_nodes(&self) -> BTreeMap<NodeLocation, DocumentSchemaItem>;
+  /// get all schema nodes in a map indexed by their identity location, fails with every
+  /// node that could not be read
+  fn get_schema_nodes(&self) -> Result<BTreeMap<NodeLocation, DocumentSchemaItem>, Error>;
 
   fn resolve_anchor(&self, anchor: &str) -> Option<Vec<String>>;
   fn resolve_antecedent_anchor(&self, anchor: &str) -> Option<Vec<String>>;
@@ -1 +1,9 @@
 pub const META_SCHEMA_ID: &str = "http://swagger.io/v2/schema.json#/definitions/schema";
+
+/// The schema of the api description document itself
+pub const DOCUMENT_SCHEMA_ID: &str = "http://swagger.io/v2/schema.json#";
+
+/// A copy of the schema of the api description document. Descriptions are validated against
+/// this so no network is needed.
+pub const WELL_KNOWN_SCHEMAS: &[(&str, &str)] =
+  &[(DOCUMENT_SCHEMA_ID, include_str!("schemas/schema.json"))];
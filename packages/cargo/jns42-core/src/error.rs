@@ -1,10 +1,11 @@
+use crate::models::TransformDiagnostic;
 use crate::utilities::{FetchTextError, NodeCacheError, NodeLocation, ParseLocationError};
 use std::fmt::Display;
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Error {
   Unknown,
-  Conflict,
+  Conflict(NodeLocation),
   DocumentNodeNotFound(NodeLocation),
   VersionNodeNotFound(NodeLocation),
   FactoryNotFound(String),
@@ -13,8 +14,63 @@ pub enum Error {
   DocumentNotFound(NodeLocation),
   ReferenceNotFound(NodeLocation),
   InvalidLocation,
-  FetchError,
-  SerializationError,
+  InvalidPattern(String),
+  /// The reference in the node at the location could not be parsed
+  InvalidReference(NodeLocation, String),
+  /// The identifier of the node at the location could not be parsed
+  InvalidIdentifier(NodeLocation, String),
+  /// The node at the location has a type that we do not know
+  InvalidType(NodeLocation, String),
+  /// The anchor is defined more than once in the document
+  DuplicateAnchor(NodeLocation),
+  FetchError(NodeLocation, FetchTextError),
+  SerializationError {
+    location: NodeLocation,
+    message: String,
+    line: Option<usize>,
+    column: Option<usize>,
+  },
+  /// The transforms did not reach a fixed point within the maximum number of iterations
+  TransformNotConverging(TransformDiagnostic),
+  /// More than one thing went wrong, every error is reported
+  Multiple(Vec<Error>),
+}
+
+impl Error {
+  /// Turn a list of errors in a result, errors are combined if there are more than one
+  pub fn from_errors(errors: impl IntoIterator<Item = Error>) -> Result<(), Self> {
+    let mut errors: Vec<_> = errors
+      .into_iter()
+      .flat_map(|error| match error {
+        Self::Multiple(errors) => errors,
+        error => vec![error],
+      })
+      .collect();
+
+    match errors.len() {
+      0 => Ok(()),
+      1 => Err(errors.pop().unwrap()),
+      _ => Err(Self::Multiple(errors)),
+    }
+  }
+
+  /// Collect the values of the results, or every error if one or more results are errors
+  pub fn collect_results<T, C>(
+    results: impl IntoIterator<Item = Result<T, Error>>,
+  ) -> Result<C, Self>
+  where
+    C: FromIterator<T>,
+  {
+    let mut errors = Vec::new();
+    let values: Vec<_> = results
+      .into_iter()
+      .filter_map(|result| result.map_err(|error| errors.push(error)).ok())
+      .collect();
+
+    Self::from_errors(errors)?;
+
+    Ok(values.into_iter().collect())
+  }
 }
 
 impl std::error::Error for Error {}
@@ -23,7 +79,7 @@ impl Display for Error {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match self {
       Self::Unknown => write!(f, "Unknown"),
-      Self::Conflict => write!(f, "Conflict"),
+      Self::Conflict(location) => write!(f, "Conflict: {}", location),
       Self::DocumentNodeNotFound(location) => write!(f, "DocumentNodeNotFound: {}", location),
       Self::VersionNodeNotFound(location) => write!(f, "VersionNodeNotFound: {}", location),
       Self::FactoryNotFound(location) => write!(f, "FactoryNotFound: {}", location),
@@ -36,8 +92,43 @@ impl Display for Error {
       Self::DocumentNotFound(location) => write!(f, "DocumentNotFound: {}", location),
       Self::ReferenceNotFound(location) => write!(f, "ReferenceNotFound: {}", location),
       Self::InvalidLocation => write!(f, "InvalidLocation"),
-      Self::FetchError => write!(f, "FetchError"),
-      Self::SerializationError => write!(f, "SerializationError"),
+      Self::InvalidPattern(pattern) => write!(f, "InvalidPattern: {}", pattern),
+      Self::InvalidReference(location, reference) => {
+        write!(f, "InvalidReference: {} at {}", reference, location)
+      }
+      Self::InvalidIdentifier(location, identifier) => {
+        write!(f, "InvalidIdentifier: {} at {}", identifier, location)
+      }
+      Self::InvalidType(location, r#type) => write!(f, "InvalidType: {} at {}", r#type, location),
+      Self::DuplicateAnchor(location) => write!(f, "DuplicateAnchor: {}", location),
+      Self::FetchError(location, error) => write!(f, "FetchError: {} ({})", location, error),
+      Self::SerializationError {
+        location,
+        message,
+        line,
+        column,
+      } => {
+        write!(f, "SerializationError: {}", location)?;
+        if let Some(line) = line {
+          write!(f, ":{}", line)?;
+          if let Some(column) = column {
+            write!(f, ":{}", column)?;
+          }
+        }
+        write!(f, " ({})", message)
+      }
+      Self::TransformNotConverging(diagnostic) => {
+        write!(f, "TransformNotConverging: {}", diagnostic)
+      }
+      Self::Multiple(errors) => {
+        for (index, error) in errors.iter().enumerate() {
+          if index > 0 {
+            writeln!(f)?;
+          }
+          write!(f, "{}", error)?;
+        }
+        Ok(())
+      }
     }
   }
 }
@@ -57,21 +148,22 @@ impl From<ParseLocationError> for Error {
   }
 }
 
-impl From<FetchTextError> for Error {
-  fn from(value: FetchTextError) -> Self {
-    match value {
-      FetchTextError::HttpError => Self::FetchError,
-      FetchTextError::IoError => Self::FetchError,
-    }
-  }
-}
-
 impl From<NodeCacheError> for Error {
   fn from(value: NodeCacheError) -> Self {
     match value {
-      NodeCacheError::SerializationError => Self::SerializationError,
-      NodeCacheError::FetchError => Self::FetchError,
-      NodeCacheError::Conflict => Self::Conflict,
+      NodeCacheError::SerializationError {
+        location,
+        message,
+        line,
+        column,
+      } => Self::SerializationError {
+        location,
+        message,
+        line,
+        column,
+      },
+      NodeCacheError::FetchError(location, error) => Self::FetchError(location, error),
+      NodeCacheError::Conflict(location) => Self::Conflict(location),
     }
   }
 }
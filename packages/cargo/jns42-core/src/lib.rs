@@ -1,6 +1,10 @@
+// errors carry the location and the cause of the failure, they are big but rare
+#![allow(clippy::result_large_err)]
+
 pub mod documents;
 pub mod error;
 pub mod models;
 pub mod naming;
 pub mod schema_transforms;
 pub mod utilities;
+pub mod validation;
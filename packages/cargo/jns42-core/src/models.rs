@@ -1,9 +1,13 @@
+mod api_operation;
 mod schema_arena;
 mod schema_item;
 mod schema_transform;
 mod schema_type;
+mod transform_diagnostic;
 
+pub use api_operation::*;
 pub use schema_arena::*;
 pub use schema_item::*;
 pub use schema_transform::*;
 pub use schema_type::*;
+pub use transform_diagnostic::*;
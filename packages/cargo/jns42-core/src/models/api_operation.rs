@@ -0,0 +1,76 @@
+use crate::utilities::NodeLocation;
+
+/// An operation from an api description, schemas are referenced by their identity location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiOperation {
+  /// The operationId, or the method and the path if there is no operationId
+  pub name: Vec<String>,
+  /// Lower case http method
+  pub method: String,
+  /// Path template, like `/pets/{petId}`
+  pub path: String,
+  /// Path parameters first, then query, header and cookie parameters. Required parameters
+  /// come before optional ones in every location.
+  pub parameters: Vec<ApiParameter>,
+  pub request_body: Option<ApiBody>,
+  pub responses: Vec<ApiResponse>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiParameter {
+  pub name: String,
+  pub location: ApiParameterLocation,
+  pub required: bool,
+  pub schema: Option<NodeLocation>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiParameterLocation {
+  Path,
+  Query,
+  Header,
+  Cookie,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiBody {
+  pub content_type: String,
+  pub required: bool,
+  pub schema: Option<NodeLocation>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiResponse {
+  /// Status code, a range like `2XX` or `default`
+  pub status: String,
+  pub body: Option<ApiBody>,
+  /// Headers of the response, without the content type
+  pub headers: Vec<ApiHeader>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApiHeader {
+  pub name: String,
+  pub required: bool,
+  pub schema: Option<NodeLocation>,
+}
+
+impl ApiBody {
+  pub fn is_json(&self) -> bool {
+    let content_type = self.content_type.to_lowercase();
+    content_type == "application/json" || content_type.ends_with("+json")
+  }
+}
+
+impl ApiResponse {
+  /// The range of status codes this response is for, `None` for the default response
+  pub fn get_status_range(&self) -> Option<(u16, u16)> {
+    if let Ok(status) = self.status.parse() {
+      return Some((status, status));
+    }
+
+    let status = self.status.to_uppercase();
+    let class: u16 = status.strip_suffix("XX")?.parse().ok()?;
+    Some((class * 100, class * 100 + 99))
+  }
+}
@@ -1,10 +1,12 @@
 use super::{BoxedSchemaTransform, SchemaTransform, SchemaType, schema_item::ArenaSchemaItem};
 use crate::{
   documents::{DocumentContext, DocumentContextContainer},
+  error::Error,
   models::ArenaSchemaItemContainer,
   utilities::{Arena, NodeLocation},
 };
 use std::{
+  cell::RefCell,
   collections::{BTreeMap, BTreeSet},
   iter,
   rc::Rc,
@@ -14,8 +16,8 @@ use wasm_bindgen::prelude::*;
 pub type SchemaArena = Arena<ArenaSchemaItem>;
 
 impl Arena<ArenaSchemaItem> {
-  pub fn from_document_context(document_context: &Rc<DocumentContext>) -> Self {
-    let schema_nodes = document_context.get_schema_nodes();
+  pub fn from_document_context(document_context: &Rc<DocumentContext>) -> Result<Self, Error> {
+    let schema_nodes = document_context.get_schema_nodes()?;
     let mut implicit_types: BTreeMap<NodeLocation, SchemaType> = BTreeMap::new();
 
     // first load schemas in the arena
@@ -36,14 +38,28 @@ impl Arena<ArenaSchemaItem> {
       }
     }
 
+    let mut errors = Vec::new();
     for (location, key) in &key_map {
       let mut schema = schema_nodes.get(location).unwrap().clone();
 
+      // documents may name a schema, that name replaces the hash of the location
+      let hash = match schema.name.take() {
+        Some(name) => name,
+        None => location
+          .get_hash()
+          .iter()
+          .flat_map(|part| part.split('.').map(str::to_owned).rev().collect::<Vec<_>>())
+          .collect(),
+      };
       schema.name = Some(
         iter::empty()
-          .chain(location.get_path())
-          .chain(location.get_hash())
-          .flat_map(|part| part.split('.').map(str::to_owned).rev().collect::<Vec<_>>())
+          .chain(
+            location
+              .get_path()
+              .iter()
+              .flat_map(|part| part.split('.').map(str::to_owned).rev().collect::<Vec<_>>()),
+          )
+          .chain(hash)
           .collect(),
       );
 
@@ -53,12 +69,28 @@ impl Arena<ArenaSchemaItem> {
           .map(|value| iter::once(*value).collect())
       });
 
-      let item = schema.map_keys(|location| *key_map.get(location).unwrap());
+      // references to schemas that are not loaded are reported, the key of the
+      // missing schema is never used.
+      let missing_locations = RefCell::new(Vec::new());
+      let item = schema.map_keys(|location| {
+        key_map.get(location).copied().unwrap_or_else(|| {
+          missing_locations.borrow_mut().push(location.clone());
+          Default::default()
+        })
+      });
+      errors.extend(
+        missing_locations
+          .into_inner()
+          .into_iter()
+          .map(Error::ReferenceNotFound),
+      );
 
       arena.replace_item(*key, item);
     }
 
-    arena
+    Error::from_errors(errors)?;
+
+    Ok(arena)
   }
 
   /// Resolves the final entry for a given schema key, following any alias chains.
@@ -76,10 +108,7 @@ impl Arena<ArenaSchemaItem> {
     let mut resolved_key = key;
     let mut resolved_item = self.get_item(resolved_key);
 
-    loop {
-      let Some(alias_key) = resolved_item.get_alias_key() else {
-        break;
-      };
+    while let Some(alias_key) = resolved_item.get_alias_key() {
       resolved_key = alias_key;
       resolved_item = self.get_item(resolved_key);
     }
@@ -106,6 +135,49 @@ impl Arena<ArenaSchemaItem> {
   }
 }
 
+impl Arena<ArenaSchemaItem> {
+  /// Writes the arena as a draft 2020-12 document. Every item is written to `$defs`, named
+  /// via `get_name` or by it's key if there is no name. If there is a `root_key`, only the
+  /// items related to it are written and the document refers to the root item.
+  pub fn to_document(
+    &self,
+    root_key: Option<usize>,
+    get_name: impl Fn(usize) -> Option<String>,
+  ) -> serde_json::Value {
+    let keys: Vec<_> = match root_key {
+      Some(root_key) => self.get_all_related(root_key).collect(),
+      None => (0..self.count()).collect(),
+    };
+
+    let get_name = |key: usize| get_name(key).unwrap_or_else(|| key.to_string());
+    let get_pointer = |key: &usize| {
+      let name = get_name(*key).replace('~', "~0").replace('/', "~1");
+      format!("#/$defs/{}", urlencoding::encode(&name))
+    };
+    let get_reference = |key: &usize| serde_json::json!({ "$ref": get_pointer(key) });
+
+    let definitions: serde_json::Map<_, _> = keys
+      .iter()
+      .map(|key| {
+        let item = self.get_item(*key);
+        (get_name(*key), item.to_document_node(&get_reference))
+      })
+      .collect();
+
+    let mut document = serde_json::Map::new();
+    document.insert(
+      "$schema".to_owned(),
+      crate::documents::draft_2020_12::META_SCHEMA_ID.into(),
+    );
+    if let Some(root_key) = root_key {
+      document.insert("$ref".to_owned(), get_pointer(&root_key).into());
+    }
+    document.insert("$defs".to_owned(), definitions.into());
+
+    document.into()
+  }
+}
+
 #[wasm_bindgen]
 #[derive(Clone)]
 pub struct SchemaArenaContainer(SchemaArena);
@@ -121,9 +193,11 @@ impl SchemaArenaContainer {
 #[wasm_bindgen]
 impl SchemaArenaContainer {
   #[wasm_bindgen(js_name = fromDocumentContext)]
-  pub fn from_document_context(document_context: &DocumentContextContainer) -> Self {
+  pub fn from_document_context(
+    document_context: &DocumentContextContainer,
+  ) -> Result<Self, js_sys::Error> {
     let document_context = document_context.clone();
-    SchemaArena::from_document_context(&document_context.into()).into()
+    Ok(SchemaArena::from_document_context(&document_context.into())?.into())
   }
 
   #[wasm_bindgen(js_name = getItem)]
@@ -175,3 +249,123 @@ impl From<SchemaArenaContainer> for SchemaArena {
     value.0
   }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{documents, utilities::Decimal, validation::Validator};
+  use serde_json::json;
+
+  #[test]
+  fn test_to_document() {
+    let mut arena = SchemaArena::new();
+
+    arena.add_item(ArenaSchemaItem {
+      types: Some(vec![SchemaType::Object]),
+      object_properties: Some([("a".to_owned(), 1)].into()),
+      required: Some(["a".to_owned()].into()),
+      ..Default::default()
+    });
+    arena.add_item(ArenaSchemaItem {
+      types: Some(vec![SchemaType::Integer]),
+      minimum_inclusive: Some(Decimal::from(1)),
+      options: Some(vec![json!(1), json!(2)]),
+      ..Default::default()
+    });
+    arena.add_item(ArenaSchemaItem {
+      types: Some(vec![SchemaType::Never]),
+      ..Default::default()
+    });
+
+    let names = ["Root", "A"];
+    let actual = arena.to_document(Some(0), |key| names.get(key).map(|name| name.to_string()));
+    let expected = json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "$ref": "#/$defs/Root",
+      "$defs": {
+        "Root": {
+          "type": "object",
+          "properties": { "a": { "$ref": "#/$defs/A" } },
+          "required": ["a"],
+        },
+        "A": {
+          "type": "integer",
+          "minimum": 1,
+          "enum": [1, 2],
+        },
+      },
+    });
+    assert_eq!(actual, expected);
+
+    let actual = arena.to_document(None, |_key| None);
+    assert_eq!(actual["$defs"]["2"], json!(false));
+  }
+
+  #[tokio::test]
+  async fn test_to_document_round_trip() {
+    let schema = json!({
+      "$schema": "https://json-schema.org/draft/2020-12/schema",
+      "type": "array",
+      "prefixItems": [{ "type": "string", "minLength": 2 }],
+      "items": { "$ref": "#/$defs/number" },
+      "$defs": {
+        "number": {
+          "oneOf": [
+            { "type": "integer", "maximum": 10 },
+            { "type": "number", "minimum": 100, "multipleOf": 0.5 }
+          ]
+        }
+      }
+    });
+
+    let mut document_context = Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    let location: NodeLocation = "/schema.json".parse().unwrap();
+    document_context
+      .load_from_node(
+        location.clone(),
+        location.clone(),
+        None,
+        schema,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+    let arena = SchemaArena::from_document_context(&document_context).unwrap();
+    let root_key = arena
+      .iter()
+      .position(|item| item.location.as_ref() == Some(&location))
+      .unwrap();
+    let document = arena.to_document(Some(root_key), |_key| None);
+
+    // the written document should validate the same as the original
+    let mut document_context = Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    let location: NodeLocation = "/written.json".parse().unwrap();
+    document_context
+      .load_from_node(
+        location.clone(),
+        location.clone(),
+        None,
+        document,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+    let validator = Validator::from_document_context(&document_context).unwrap();
+
+    assert!(
+      validator
+        .is_valid(&location, &json!(["ab", 1, 100.5]))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!(["a", 1])).unwrap());
+    assert!(
+      !validator
+        .is_valid(&location, &json!(["ab", 100.25]))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!(["ab", 50])).unwrap());
+  }
+}
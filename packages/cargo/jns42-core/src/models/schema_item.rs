@@ -1,5 +1,5 @@
 use super::SchemaType;
-use crate::utilities::NodeLocation;
+use crate::utilities::{Decimal, NodeLocation};
 use crate::utilities::{merge_either, merge_option};
 use gloo::utils::format::JsValueSerdeExt;
 use std::collections::BTreeSet;
@@ -32,6 +32,8 @@ where
 
   // applicators
   pub reference: Option<K>,
+  /// the keyword of the reference, `$ref` if there is none
+  pub reference_keyword: Option<String>,
 
   pub r#if: Option<K>,
   pub then: Option<K>,
@@ -43,6 +45,9 @@ where
   pub map_properties: Option<K>,
   pub array_items: Option<K>,
   pub contains: Option<K>,
+  pub unevaluated_properties: Option<K>,
+  pub unevaluated_items: Option<K>,
+  pub content_schema: Option<K>,
 
   pub all_of: Option<BTreeSet<K>>,
   pub any_of: Option<BTreeSet<K>>,
@@ -58,21 +63,26 @@ where
   // assertions
   pub options: Option<Vec<serde_json::Value>>,
   pub required: Option<BTreeSet<String>>,
+  pub dependent_required: Option<BTreeMap<String, BTreeSet<String>>>,
 
-  pub minimum_inclusive: Option<f64>,
-  pub minimum_exclusive: Option<f64>,
-  pub maximum_inclusive: Option<f64>,
-  pub maximum_exclusive: Option<f64>,
-  pub multiple_of: Option<f64>,
+  pub minimum_inclusive: Option<Decimal>,
+  pub minimum_exclusive: Option<Decimal>,
+  pub maximum_inclusive: Option<Decimal>,
+  pub maximum_exclusive: Option<Decimal>,
+  pub multiple_of: Option<Decimal>,
 
   pub minimum_length: Option<u32>,
   pub maximum_length: Option<u32>,
   pub value_pattern: Option<String>,
   pub value_format: Option<String>,
+  pub content_encoding: Option<String>,
+  pub content_media_type: Option<String>,
 
   pub minimum_items: Option<u32>,
   pub maximum_items: Option<u32>,
   pub unique_items: Option<bool>,
+  pub minimum_contains: Option<u32>,
+  pub maximum_contains: Option<u32>,
 
   pub minimum_properties: Option<u32>,
   pub maximum_properties: Option<u32>,
@@ -206,6 +216,20 @@ where
       Some(true)
     };
 
+    // a contains without a minContains needs at least one element, so a minContains of zero
+    // on the other side does not win
+    let minimum_contains = if self.minimum_contains.is_none() && other.minimum_contains.is_none() {
+      None
+    } else {
+      let effective_minimum_contains =
+        |item: &Self| item.minimum_contains.or(item.contains.as_ref().map(|_| 1));
+      merge_option(
+        effective_minimum_contains(self).as_ref(),
+        effective_minimum_contains(other).as_ref(),
+        |base, other| *base.max(other),
+      )
+    };
+
     let exact = merge_option!(exact, &|base, other| base & other);
     let exact = merge_option(exact.as_ref(), exact_merge.as_ref(), |base, other| {
       base & other
@@ -226,6 +250,7 @@ where
       ]),
 
       reference: None,
+      reference_keyword: None,
 
       all_of: None,
       any_of: None,
@@ -245,7 +270,11 @@ where
       property_names: merge_single_key!(property_names),
       map_properties: merge_single_key!(map_properties),
       array_items: merge_single_key!(array_items),
+      // the all of transform only merges items with the same contains
       contains: merge_single_key!(contains),
+      unevaluated_properties: merge_single_key!(unevaluated_properties),
+      unevaluated_items: merge_single_key!(unevaluated_items),
+      content_schema: merge_single_key!(content_schema),
 
       tuple_items: generate_merge_array_keys!(tuple_items),
 
@@ -255,21 +284,36 @@ where
 
       options: union_merge!(options), // TODO should be intersection?
       required: union_merge!(required),
-
-      minimum_inclusive: merge_option!(minimum_inclusive, |base, other| base.min(*other)),
-      minimum_exclusive: merge_option!(minimum_exclusive, |base, other| base.min(*other)),
-      maximum_inclusive: merge_option!(maximum_inclusive, |base, other| base.max(*other)),
-      maximum_exclusive: merge_option!(maximum_exclusive, |base, other| base.max(*other)),
+      dependent_required: merge_option!(dependent_required, |base, other| {
+        let mut merged = base.clone();
+        for (name, required) in other {
+          merged
+            .entry(name.clone())
+            .or_default()
+            .extend(required.iter().cloned());
+        }
+        merged
+      }),
+
+      minimum_inclusive: merge_option!(minimum_inclusive, |base, other| base.min(other).clone()),
+      minimum_exclusive: merge_option!(minimum_exclusive, |base, other| base.min(other).clone()),
+      maximum_inclusive: merge_option!(maximum_inclusive, |base, other| base.max(other).clone()),
+      maximum_exclusive: merge_option!(maximum_exclusive, |base, other| base.max(other).clone()),
       multiple_of: merge_either!(multiple_of), // TODO
 
       minimum_length: merge_option!(minimum_length, |base, other| *base.min(other)),
       maximum_length: merge_option!(maximum_length, |base, other| *base.max(other)),
       value_pattern: merge_either!(value_pattern),
       value_format: merge_either!(value_format),
+      content_encoding: merge_either!(content_encoding),
+      content_media_type: merge_either!(content_media_type),
 
       minimum_items: merge_option!(minimum_items, |base, other| *base.min(other)),
       maximum_items: merge_option!(maximum_items, |base, other| *base.max(other)),
       unique_items: merge_option!(unique_items, |base, other| base | other),
+      // both must hold
+      minimum_contains,
+      maximum_contains: merge_option!(maximum_contains, |base, other| *base.min(other)),
 
       minimum_properties: merge_option!(minimum_properties, |base, other| *base.min(other)),
       maximum_properties: merge_option!(maximum_properties, |base, other| *base.max(other)),
@@ -294,12 +338,16 @@ where
       && self.map_properties.is_none()
       && self.array_items.is_none()
       && self.contains.is_none()
+      && self.unevaluated_properties.is_none()
+      && self.unevaluated_items.is_none()
+      && self.content_schema.is_none()
       && self.tuple_items.is_none()
       && self.object_properties.is_none()
       && self.pattern_properties.is_none()
       && self.dependent_schemas.is_none()
       && self.options.is_none()
       && self.required.is_none()
+      && self.dependent_required.is_none()
       && self.minimum_inclusive.is_none()
       && self.minimum_exclusive.is_none()
       && self.maximum_inclusive.is_none()
@@ -309,15 +357,36 @@ where
       && self.maximum_length.is_none()
       && self.value_pattern.is_none()
       && self.value_format.is_none()
+      && self.content_encoding.is_none()
+      && self.content_media_type.is_none()
       && self.minimum_items.is_none()
       && self.maximum_items.is_none()
       && self.unique_items.is_none()
+      && self.minimum_contains.is_none()
+      && self.maximum_contains.is_none()
       && self.minimum_properties.is_none()
       && self.maximum_properties.is_none();
 
     if is_alias_maybe { self.reference } else { None }
   }
 
+  /// `true` if the item is a string that holds base64 encoded data
+  pub fn is_base64_content(&self) -> bool {
+    self
+      .content_encoding
+      .as_ref()
+      .is_some_and(|value| value.eq_ignore_ascii_case("base64"))
+  }
+
+  /// The schema of the json document in a string, if the string holds one
+  pub fn get_json_content_schema(&self) -> Option<K> {
+    let content_media_type = self.content_media_type.as_ref()?.to_lowercase();
+    if content_media_type != "application/json" && !content_media_type.ends_with("+json") {
+      return None;
+    }
+    self.content_schema
+  }
+
   pub fn get_dependencies(&self) -> impl Iterator<Item = K> + '_ {
     empty()
       .chain(self.reference)
@@ -329,6 +398,9 @@ where
       .chain(self.array_items)
       .chain(self.property_names)
       .chain(self.contains)
+      .chain(self.unevaluated_properties)
+      .chain(self.unevaluated_items)
+      .chain(self.content_schema)
       .chain(self.tuple_items.iter().flat_map(|v| v.iter().copied()))
       .chain(self.all_of.iter().flat_map(|v| v.iter().copied()))
       .chain(self.any_of.iter().flat_map(|v| v.iter().copied()))
@@ -399,28 +471,37 @@ where
 
       options: self.options.clone(),
 
-      minimum_inclusive: self.minimum_inclusive,
-      minimum_exclusive: self.minimum_exclusive,
-      maximum_inclusive: self.maximum_inclusive,
-      maximum_exclusive: self.maximum_exclusive,
-      multiple_of: self.multiple_of,
+      minimum_inclusive: self.minimum_inclusive.clone(),
+      minimum_exclusive: self.minimum_exclusive.clone(),
+      maximum_inclusive: self.maximum_inclusive.clone(),
+      maximum_exclusive: self.maximum_exclusive.clone(),
+      multiple_of: self.multiple_of.clone(),
 
       minimum_length: self.minimum_length,
       maximum_length: self.maximum_length,
       value_pattern: self.value_pattern.clone(),
       value_format: self.value_format.clone(),
+      content_encoding: self.content_encoding.clone(),
+      content_media_type: self.content_media_type.clone(),
 
       maximum_items: self.maximum_items,
       minimum_items: self.minimum_items,
       unique_items: self.unique_items,
+      minimum_contains: self.minimum_contains,
+      maximum_contains: self.maximum_contains,
 
       minimum_properties: self.minimum_properties,
       maximum_properties: self.maximum_properties,
       required: self.required.clone(),
+      dependent_required: self.dependent_required.clone(),
 
       reference: map_single(&self.reference),
+      reference_keyword: self.reference_keyword.clone(),
 
       contains: map_single(&self.contains),
+      unevaluated_properties: map_single(&self.unevaluated_properties),
+      unevaluated_items: map_single(&self.unevaluated_items),
+      content_schema: map_single(&self.content_schema),
       property_names: map_single(&self.property_names),
       map_properties: map_single(&self.map_properties),
       array_items: map_single(&self.array_items),
@@ -442,6 +523,179 @@ where
   }
 }
 
+impl<K> SchemaItem<K>
+where
+  K: Ord,
+{
+  /// Writes the item as a draft 2020-12 schema node, sub schemas are written via `reference`.
+  /// Definitions are not written, they are written by whoever is writing the document.
+  pub fn to_document_node(
+    &self,
+    reference: &impl Fn(&K) -> serde_json::Value,
+  ) -> serde_json::Value {
+    let types: Vec<_> = self
+      .types
+      .iter()
+      .flatten()
+      .filter(|r#type| **r#type != SchemaType::Never)
+      .collect();
+    // only never, so nothing is valid
+    if self.types.as_ref().is_some_and(|types| !types.is_empty()) && types.is_empty() {
+      return false.into();
+    }
+
+    let mut node = serde_json::Map::new();
+    // the reference is a node with a `$ref`
+    if let Some(key) = &self.reference {
+      node.extend(reference(key).as_object().cloned().unwrap_or_default());
+    }
+
+    let mut insert = |name: &str, value: Option<serde_json::Value>| {
+      if let Some(value) = value {
+        node.insert(name.to_owned(), value);
+      }
+    };
+    let list = |value: &Option<Vec<K>>| {
+      value
+        .as_ref()
+        .map(|value| value.iter().map(reference).collect::<serde_json::Value>())
+    };
+    let set = |value: &Option<BTreeSet<K>>| {
+      value
+        .as_ref()
+        .map(|value| value.iter().map(reference).collect::<serde_json::Value>())
+    };
+    let map = |value: &Option<BTreeMap<String, K>>| {
+      value.as_ref().map(|value| {
+        value
+          .iter()
+          .map(|(name, key)| (name.clone(), reference(key)))
+          .collect::<serde_json::Map<_, _>>()
+          .into()
+      })
+    };
+
+    // metadata
+    insert("title", self.title.clone().map(Into::into));
+    insert("description", self.description.clone().map(Into::into));
+    insert("examples", self.examples.clone().map(Into::into));
+    insert("deprecated", self.deprecated.map(Into::into));
+
+    // types, any is the same as no type
+    if !types.contains(&&SchemaType::Any) {
+      match types.as_slice() {
+        [] => {}
+        [r#type] => insert("type", Some(r#type.to_string().into())),
+        types => insert(
+          "type",
+          Some(types.iter().map(|r#type| r#type.to_string()).collect()),
+        ),
+      }
+    }
+
+    // applicators
+    insert("if", self.r#if.as_ref().map(reference));
+    insert("then", self.then.as_ref().map(reference));
+    insert("else", self.r#else.as_ref().map(reference));
+    insert("not", self.not.as_ref().map(reference));
+
+    insert("propertyNames", self.property_names.as_ref().map(reference));
+    insert(
+      "additionalProperties",
+      self.map_properties.as_ref().map(reference),
+    );
+    insert("items", self.array_items.as_ref().map(reference));
+    insert("contains", self.contains.as_ref().map(reference));
+    insert(
+      "unevaluatedProperties",
+      self.unevaluated_properties.as_ref().map(reference),
+    );
+    insert(
+      "unevaluatedItems",
+      self.unevaluated_items.as_ref().map(reference),
+    );
+    insert("contentSchema", self.content_schema.as_ref().map(reference));
+
+    insert("allOf", set(&self.all_of));
+    insert("anyOf", set(&self.any_of));
+    insert("oneOf", set(&self.one_of));
+    insert("prefixItems", list(&self.tuple_items));
+
+    insert("properties", map(&self.object_properties));
+    insert("patternProperties", map(&self.pattern_properties));
+    insert("dependentSchemas", map(&self.dependent_schemas));
+
+    // assertions
+    match self.options.as_deref() {
+      Some([option]) => insert("const", Some(option.clone())),
+      Some(options) => insert("enum", Some(options.to_vec().into())),
+      None => {}
+    }
+    insert(
+      "required",
+      self
+        .required
+        .as_ref()
+        .map(|value| value.iter().cloned().collect()),
+    );
+    insert(
+      "dependentRequired",
+      self.dependent_required.as_ref().map(|value| {
+        value
+          .iter()
+          .map(|(name, required)| (name.clone(), required.iter().cloned().collect()))
+          .collect::<serde_json::Map<_, _>>()
+          .into()
+      }),
+    );
+
+    insert(
+      "minimum",
+      self.minimum_inclusive.as_ref().map(Decimal::to_json),
+    );
+    insert(
+      "exclusiveMinimum",
+      self.minimum_exclusive.as_ref().map(Decimal::to_json),
+    );
+    insert(
+      "maximum",
+      self.maximum_inclusive.as_ref().map(Decimal::to_json),
+    );
+    insert(
+      "exclusiveMaximum",
+      self.maximum_exclusive.as_ref().map(Decimal::to_json),
+    );
+    insert(
+      "multipleOf",
+      self.multiple_of.as_ref().map(Decimal::to_json),
+    );
+
+    insert("minLength", self.minimum_length.map(Into::into));
+    insert("maxLength", self.maximum_length.map(Into::into));
+    insert("pattern", self.value_pattern.clone().map(Into::into));
+    insert("format", self.value_format.clone().map(Into::into));
+    insert(
+      "contentEncoding",
+      self.content_encoding.clone().map(Into::into),
+    );
+    insert(
+      "contentMediaType",
+      self.content_media_type.clone().map(Into::into),
+    );
+
+    insert("minItems", self.minimum_items.map(Into::into));
+    insert("maxItems", self.maximum_items.map(Into::into));
+    insert("uniqueItems", self.unique_items.map(Into::into));
+    insert("minContains", self.minimum_contains.map(Into::into));
+    insert("maxContains", self.maximum_contains.map(Into::into));
+
+    insert("minProperties", self.minimum_properties.map(Into::into));
+    insert("maxProperties", self.maximum_properties.map(Into::into));
+
+    node.into()
+  }
+}
+
 #[wasm_bindgen]
 pub struct ArenaSchemaItemContainer(ArenaSchemaItem);
 
@@ -533,6 +787,18 @@ impl ArenaSchemaItemContainer {
   pub fn contains_get(&self) -> Option<usize> {
     self.0.contains
   }
+  #[wasm_bindgen(getter = unevaluatedProperties)]
+  pub fn unevaluated_properties_get(&self) -> Option<usize> {
+    self.0.unevaluated_properties
+  }
+  #[wasm_bindgen(getter = unevaluatedItems)]
+  pub fn unevaluated_items_get(&self) -> Option<usize> {
+    self.0.unevaluated_items
+  }
+  #[wasm_bindgen(getter = contentSchema)]
+  pub fn content_schema_get(&self) -> Option<usize> {
+    self.0.content_schema
+  }
 
   #[wasm_bindgen(getter = allOf)]
   pub fn all_of_get(&self) -> Option<Vec<usize>> {
@@ -593,26 +859,34 @@ impl ArenaSchemaItemContainer {
   pub fn required_get(&self) -> Option<Vec<String>> {
     Some(self.0.required.as_ref()?.iter().cloned().collect())
   }
+  #[wasm_bindgen(getter = dependentRequired)]
+  pub fn dependent_required_get(&self) -> JsValue {
+    let Some(value) = self.0.dependent_required.as_ref() else {
+      return JsValue::undefined();
+    };
+
+    JsValue::from_serde(value).unwrap_or(JsValue::undefined())
+  }
 
   #[wasm_bindgen(getter = minimumInclusive)]
   pub fn minimum_inclusive_get(&self) -> Option<f64> {
-    self.0.minimum_inclusive
+    self.0.minimum_inclusive.as_ref().map(Decimal::to_f64)
   }
   #[wasm_bindgen(getter = minimumExclusive)]
   pub fn minimum_exclusive_get(&self) -> Option<f64> {
-    self.0.minimum_exclusive
+    self.0.minimum_exclusive.as_ref().map(Decimal::to_f64)
   }
   #[wasm_bindgen(getter = maximumInclusive)]
   pub fn maximum_inclusive_get(&self) -> Option<f64> {
-    self.0.maximum_inclusive
+    self.0.maximum_inclusive.as_ref().map(Decimal::to_f64)
   }
   #[wasm_bindgen(getter = maximumExclusive)]
   pub fn maximum_exclusive_get(&self) -> Option<f64> {
-    self.0.maximum_exclusive
+    self.0.maximum_exclusive.as_ref().map(Decimal::to_f64)
   }
   #[wasm_bindgen(getter = multipleOf)]
   pub fn multiple_of_get(&self) -> Option<f64> {
-    self.0.multiple_of
+    self.0.multiple_of.as_ref().map(Decimal::to_f64)
   }
 
   #[wasm_bindgen(getter = minimumLength)]
@@ -631,6 +905,14 @@ impl ArenaSchemaItemContainer {
   pub fn value_format_get(&self) -> Option<String> {
     Some(self.0.value_format.as_ref()?.clone())
   }
+  #[wasm_bindgen(getter = contentEncoding)]
+  pub fn content_encoding_get(&self) -> Option<String> {
+    Some(self.0.content_encoding.as_ref()?.clone())
+  }
+  #[wasm_bindgen(getter = contentMediaType)]
+  pub fn content_media_type_get(&self) -> Option<String> {
+    Some(self.0.content_media_type.as_ref()?.clone())
+  }
 
   #[wasm_bindgen(getter = minimumItems)]
   pub fn minimum_items_get(&self) -> Option<u32> {
@@ -644,6 +926,14 @@ impl ArenaSchemaItemContainer {
   pub fn unique_items_get(&self) -> Option<bool> {
     self.0.unique_items
   }
+  #[wasm_bindgen(getter = minimumContains)]
+  pub fn minimum_contains_get(&self) -> Option<u32> {
+    self.0.minimum_contains
+  }
+  #[wasm_bindgen(getter = maximumContains)]
+  pub fn maximum_contains_get(&self) -> Option<u32> {
+    self.0.maximum_contains
+  }
 
   #[wasm_bindgen(getter = minimumProperties)]
   pub fn minimum_properties_get(&self) -> Option<u32> {
@@ -21,11 +21,13 @@ pub enum SchemaTransform {
   InheritReference,
   ResolveAllOf,
   ResolveAnyOf,
+  ResolveDependent,
   ResolveIfThenElse,
   ResolveNot,
   ResolveSingleAllOf,
   ResolveSingleAnyOf,
   ResolveSingleOneOf,
+  ResolveUnevaluated,
   SingleType,
   Unalias,
 
@@ -57,6 +59,7 @@ impl From<SchemaTransform> for BoxedSchemaTransform {
 
       SchemaTransform::ResolveAllOf => schema_transforms::resolve_all_of::transform,
       SchemaTransform::ResolveAnyOf => schema_transforms::resolve_any_of::transform,
+      SchemaTransform::ResolveDependent => schema_transforms::resolve_dependent::transform,
       SchemaTransform::ResolveIfThenElse => schema_transforms::resolve_if_then_else::transform,
       SchemaTransform::ResolveNot => schema_transforms::resolve_not::transform,
 
@@ -64,6 +67,8 @@ impl From<SchemaTransform> for BoxedSchemaTransform {
       SchemaTransform::ResolveSingleAnyOf => schema_transforms::resolve_single::any_of::transform,
       SchemaTransform::ResolveSingleOneOf => schema_transforms::resolve_single::one_of::transform,
 
+      SchemaTransform::ResolveUnevaluated => schema_transforms::resolve_unevaluated::transform,
+
       SchemaTransform::SingleType => schema_transforms::single_type::transform,
 
       SchemaTransform::Unalias => schema_transforms::unalias::transform,
@@ -0,0 +1,229 @@
+use super::{BoxedSchemaTransform, SchemaArena, SchemaTransform};
+use crate::utilities::NodeLocation;
+use std::collections::BTreeSet;
+use std::fmt::Display;
+
+/// Diagnosing stops when the arena grew by this many items, or by the number of items it
+/// had if that is more.
+const MINIMUM_GROWTH: usize = 100;
+
+/// Why the transforms of an arena did not reach a fixed point.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TransformDiagnostic {
+  /// Iterations that were done before we gave up.
+  pub iterations: usize,
+  /// Passes that were done while diagnosing.
+  pub passes: usize,
+  /// If the arena returned to an earlier state, the number of passes between the states. A
+  /// period of 1 is a fixed point.
+  pub period: Option<usize>,
+  /// Items that were added while diagnosing.
+  pub added_count: usize,
+  /// The changes that were made while diagnosing.
+  pub changes: BTreeSet<TransformChange>,
+}
+
+/// A change of an item by a transform.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct TransformChange {
+  /// Key of the item that changed.
+  pub key: usize,
+  /// Location of the item that changed, or of the transformed item if the item that changed
+  /// was made by a transform.
+  pub location: Option<NodeLocation>,
+  pub transform: SchemaTransform,
+  /// Key of the item that the transform was applied to.
+  pub transformed_key: usize,
+}
+
+impl Display for TransformDiagnostic {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "no fixed point after {} iterations", self.iterations)?;
+    match self.period {
+      Some(1) => write!(
+        f,
+        ", the arena reaches a fixed point in {} more pass(es)",
+        self.passes
+      )?,
+      Some(period) => write!(f, ", the arena oscillates every {} passes", period)?,
+      None => write!(f, ", the arena keeps changing")?,
+    }
+    if self.added_count > 0 {
+      write!(f, " and grew by {} item(s)", self.added_count)?;
+    }
+
+    for change in &self.changes {
+      write!(f, "\n  item {}", change.key)?;
+      if let Some(location) = &change.location {
+        write!(f, " ({})", location)?;
+      }
+      write!(
+        f,
+        " changed by {:?} on item {}",
+        change.transform, change.transformed_key
+      )?;
+    }
+
+    Ok(())
+  }
+}
+
+impl SchemaArena {
+  /// Find out why the transforms do not reach a fixed point. Does at most `passes` more
+  /// passes over the arena, one transform at a time, and records which transform changed
+  /// what. Stops when the arena is in a state that it was in before.
+  pub fn diagnose_transforms(
+    &mut self,
+    transforms: &[SchemaTransform],
+    iterations: usize,
+    passes: usize,
+  ) -> TransformDiagnostic {
+    let transforms: Vec<(SchemaTransform, BoxedSchemaTransform)> = transforms
+      .iter()
+      .map(|transform| (*transform, (*transform).into()))
+      .collect();
+
+    let item_count = self.count();
+    let mut states = vec![self.clone()];
+    let mut changes = BTreeSet::new();
+    let mut period = None;
+    let mut pass_count = 0;
+
+    for _pass in 0..passes {
+      pass_count += 1;
+
+      let mut key = 0;
+      while key < self.count() {
+        for (transform, boxed_transform) in &transforms {
+          for changed_key in self.transform_item(key, boxed_transform) {
+            let location = self
+              .get_item(changed_key)
+              .location
+              .clone()
+              .or_else(|| self.get_item(key).location.clone());
+            changes.insert(TransformChange {
+              key: changed_key,
+              location,
+              transform: *transform,
+              transformed_key: key,
+            });
+          }
+        }
+        key += 1;
+      }
+
+      if let Some(index) = states.iter().rposition(|state| state == self) {
+        period = Some(states.len() - index);
+        break;
+      }
+
+      // the transforms keep adding items, we have seen enough
+      if self.count() - item_count > item_count.max(MINIMUM_GROWTH) {
+        break;
+      }
+
+      states.push(self.clone());
+    }
+
+    TransformDiagnostic {
+      iterations,
+      passes: pass_count,
+      period,
+      added_count: self.count() - item_count,
+      changes,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::{ArenaSchemaItem, SchemaType};
+
+  #[test]
+  fn test_diagnose_transforms_converging() {
+    let location: NodeLocation = "https://example.com/schema.json#/a".parse().unwrap();
+    let mut arena = SchemaArena::from_iter([ArenaSchemaItem {
+      types: Some([SchemaType::String, SchemaType::Number].into()),
+      location: Some(location.clone()),
+      ..Default::default()
+    }]);
+
+    let diagnostic = arena.diagnose_transforms(&[SchemaTransform::SingleType], 100, 10);
+    assert_eq!(diagnostic.period, Some(1));
+    assert_eq!(diagnostic.passes, 2);
+    assert_eq!(diagnostic.added_count, 2);
+    let changes: Vec<_> = diagnostic
+      .changes
+      .iter()
+      .map(|change| (change.key, change.transform, change.transformed_key))
+      .collect();
+    assert_eq!(
+      changes,
+      vec![
+        (0, SchemaTransform::SingleType, 0),
+        (1, SchemaTransform::SingleType, 0),
+        (2, SchemaTransform::SingleType, 0),
+      ]
+    );
+    // items made by the transform get the location of the transformed item
+    assert!(
+      diagnostic
+        .changes
+        .iter()
+        .all(|change| change.location.as_ref() == Some(&location))
+    );
+  }
+
+  #[test]
+  fn test_diagnose_transforms_not_converging() {
+    // the flips undo each other
+    let mut arena = SchemaArena::from_iter([
+      ArenaSchemaItem {
+        all_of: Some([1, 2].into()),
+        location: Some("https://example.com/schema.json#/a".parse().unwrap()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        one_of: Some([3, 4].into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some([SchemaType::Object].into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some([SchemaType::String].into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some([SchemaType::Number].into()),
+        ..Default::default()
+      },
+    ]);
+
+    let diagnostic = arena.diagnose_transforms(
+      &[
+        SchemaTransform::FlipAllOfOneOf,
+        SchemaTransform::FlipOneOfAllOf,
+      ],
+      100,
+      10,
+    );
+    assert_eq!(diagnostic.iterations, 100);
+    assert_eq!(diagnostic.period, None);
+    assert!(diagnostic.added_count > 0);
+    assert!(
+      diagnostic
+        .changes
+        .iter()
+        .any(|change| change.transform == SchemaTransform::FlipAllOfOneOf)
+    );
+    assert!(
+      diagnostic
+        .changes
+        .iter()
+        .any(|change| change.transform == SchemaTransform::FlipOneOfAllOf)
+    );
+  }
+}
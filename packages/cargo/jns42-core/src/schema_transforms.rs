@@ -5,8 +5,10 @@ pub mod inherit;
 pub mod name;
 pub mod resolve_all_of;
 pub mod resolve_any_of;
+pub mod resolve_dependent;
 pub mod resolve_if_then_else;
 pub mod resolve_not;
 pub mod resolve_single;
+pub mod resolve_unevaluated;
 pub mod single_type;
 pub mod unalias;
@@ -55,12 +55,16 @@ macro_rules! generate_mod {
           && item.array_items.is_none()
           && item.property_names.is_none()
           && item.contains.is_none()
+          && item.unevaluated_properties.is_none()
+          && item.unevaluated_items.is_none()
+          && item.content_schema.is_none()
           && item.tuple_items.is_none()
           && item.object_properties.is_none()
           && item.pattern_properties.is_none()
           && item.dependent_schemas.is_none()
           && item.options.is_none()
           && item.required.is_none()
+          && item.dependent_required.is_none()
           && item.minimum_inclusive.is_none()
           && item.minimum_exclusive.is_none()
           && item.maximum_inclusive.is_none()
@@ -70,9 +74,13 @@ macro_rules! generate_mod {
           && item.maximum_length.is_none()
           && item.value_pattern.is_none()
           && item.value_format.is_none()
+          && item.content_encoding.is_none()
+          && item.content_media_type.is_none()
           && item.minimum_items.is_none()
           && item.maximum_items.is_none()
           && item.unique_items.is_none()
+          && item.minimum_contains.is_none()
+          && item.maximum_contains.is_none()
           && item.minimum_properties.is_none()
           && item.maximum_properties.is_none()
         {
@@ -126,12 +134,16 @@ macro_rules! generate_mod {
           array_items: None,
           property_names: None,
           contains: None,
+          unevaluated_properties: None,
+          unevaluated_items: None,
+          content_schema: None,
           tuple_items: None,
           object_properties: None,
           pattern_properties: None,
           dependent_schemas: None,
           options: None,
           required: None,
+          dependent_required: None,
           minimum_inclusive: None,
           minimum_exclusive: None,
           maximum_inclusive: None,
@@ -141,9 +153,13 @@ macro_rules! generate_mod {
           maximum_length: None,
           value_pattern: None,
           value_format: None,
+          content_encoding: None,
+          content_media_type: None,
           minimum_items: None,
           maximum_items: None,
           unique_items: None,
+          minimum_contains: None,
+          maximum_contains: None,
           minimum_properties: None,
           maximum_properties: None,
 
@@ -268,12 +284,16 @@ pub mod reference {
       && item.array_items.is_none()
       && item.property_names.is_none()
       && item.contains.is_none()
+      && item.unevaluated_properties.is_none()
+      && item.unevaluated_items.is_none()
+      && item.content_schema.is_none()
       && item.tuple_items.is_none()
       && item.object_properties.is_none()
       && item.pattern_properties.is_none()
       && item.dependent_schemas.is_none()
       && item.options.is_none()
       && item.required.is_none()
+      && item.dependent_required.is_none()
       && item.minimum_inclusive.is_none()
       && item.minimum_exclusive.is_none()
       && item.maximum_inclusive.is_none()
@@ -283,9 +303,13 @@ pub mod reference {
       && item.maximum_length.is_none()
       && item.value_pattern.is_none()
       && item.value_format.is_none()
+      && item.content_encoding.is_none()
+      && item.content_media_type.is_none()
       && item.minimum_items.is_none()
       && item.maximum_items.is_none()
       && item.unique_items.is_none()
+      && item.minimum_contains.is_none()
+      && item.maximum_contains.is_none()
       && item.minimum_properties.is_none()
       && item.maximum_properties.is_none()
     {
@@ -333,12 +357,16 @@ pub mod reference {
       array_items: None,
       property_names: None,
       contains: None,
+      unevaluated_properties: None,
+      unevaluated_items: None,
+      content_schema: None,
       tuple_items: None,
       object_properties: None,
       pattern_properties: None,
       dependent_schemas: None,
       options: None,
       required: None,
+      dependent_required: None,
       minimum_inclusive: None,
       minimum_exclusive: None,
       maximum_inclusive: None,
@@ -348,9 +376,13 @@ pub mod reference {
       maximum_length: None,
       value_pattern: None,
       value_format: None,
+      content_encoding: None,
+      content_media_type: None,
       minimum_items: None,
       maximum_items: None,
       unique_items: None,
+      minimum_contains: None,
+      maximum_contains: None,
       minimum_properties: None,
       maximum_properties: None,
 
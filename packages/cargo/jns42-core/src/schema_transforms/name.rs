@@ -84,6 +84,9 @@ pub fn transform(arena: &mut SchemaArena, key: usize) {
   set_name_single!(map_properties, "mapProperties");
   set_name_single!(array_items, "arrayItems");
   set_name_single!(contains, "contains");
+  set_name_single!(unevaluated_properties, "unevaluatedProperties");
+  set_name_single!(unevaluated_items, "unevaluatedItems");
+  set_name_single!(content_schema, "contentSchema");
   set_name_single!(reference, "reference");
   set_name_single!(r#if, "if");
   set_name_single!(then, "then");
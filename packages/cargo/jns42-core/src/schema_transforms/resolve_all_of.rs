@@ -1,5 +1,9 @@
 use crate::models::{ArenaSchemaItem, SchemaArena};
-use std::{cell::RefCell, collections::BTreeMap};
+use std::{
+  cell::RefCell,
+  collections::{BTreeMap, BTreeSet},
+  iter,
+};
 
 /**
  * This transformer merges all sub schemas in allOf.
@@ -82,6 +86,16 @@ pub fn transform(arena: &mut SchemaArena, key: usize) {
     }
   }
 
+  // an array that contains one thing and contains another thing is not the same as an array
+  // that contains something that is both, so different contains are not merged
+  let contains: BTreeSet<_> = iter::once(item)
+    .chain(sub_entries.values())
+    .filter_map(|item| item.contains)
+    .collect();
+  if contains.len() > 1 {
+    return;
+  }
+
   let mut item_new = ArenaSchemaItem {
     all_of: None,
     ..item.clone()
@@ -577,4 +591,79 @@ mod tests {
 
     assert_eq!(actual, expected)
   }
+
+  #[test]
+  fn test_contains() {
+    let mut arena = SchemaArena::from_iter([
+      ArenaSchemaItem {
+        options: Some([1.into()].into()),
+        ..Default::default()
+      }, // 0
+      ArenaSchemaItem {
+        options: Some([2.into()].into()),
+        ..Default::default()
+      }, // 1
+      ArenaSchemaItem {
+        types: Some([SchemaType::Array].into()),
+        contains: Some(0),
+        ..Default::default()
+      }, // 2
+      ArenaSchemaItem {
+        types: Some([SchemaType::Array].into()),
+        contains: Some(1),
+        minimum_contains: Some(2),
+        ..Default::default()
+      }, // 3
+      ArenaSchemaItem {
+        types: Some([SchemaType::Array].into()),
+        contains: Some(0),
+        maximum_contains: Some(3),
+        ..Default::default()
+      }, // 4
+      ArenaSchemaItem {
+        all_of: Some([2, 3].into()),
+        ..Default::default()
+      }, // 5
+      ArenaSchemaItem {
+        all_of: Some([2, 4].into()),
+        ..Default::default()
+      }, // 6
+      ArenaSchemaItem {
+        types: Some([SchemaType::Array].into()),
+        contains: Some(0),
+        minimum_contains: Some(0),
+        ..Default::default()
+      }, // 7
+      ArenaSchemaItem {
+        all_of: Some([2, 7].into()),
+        ..Default::default()
+      }, // 8
+    ]);
+
+    while arena.apply_transform(transform) > 0 {
+      //
+    }
+
+    // different contains stay in the all of, the same contains are merged
+    assert_eq!(arena.get_item(5).all_of, Some([2, 3].into()));
+    assert_eq!(
+      arena.get_item(6),
+      &ArenaSchemaItem {
+        types: Some([SchemaType::Array].into()),
+        contains: Some(0),
+        maximum_contains: Some(3),
+        ..Default::default()
+      }
+    );
+    // a contains without minContains needs at least one element
+    assert_eq!(
+      arena.get_item(8),
+      &ArenaSchemaItem {
+        types: Some([SchemaType::Array].into()),
+        contains: Some(0),
+        minimum_contains: Some(1),
+        ..Default::default()
+      }
+    );
+  }
 }
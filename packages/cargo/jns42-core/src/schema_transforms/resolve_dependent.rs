@@ -0,0 +1,192 @@
+use crate::models::{ArenaSchemaItem, SchemaArena, SchemaType};
+use std::collections::{BTreeMap, BTreeSet};
+
+/**
+ * This transformer turns dependentRequired and dependentSchemas, and so the dependencies of
+ * older drafts, into an anyOf for every property. Either the property is there and the
+ * dependencies are met, or the property is not there. This is an anyOf and not a oneOf
+ * because a value that is not an object is valid against both. The anyOf is added to the
+ * allOf of the item.
+ *
+ * ```yaml
+ * - dependentRequired:
+ *     a: ["b"]
+ *   dependentSchemas:
+ *     a: 100
+ * ```
+ *
+ * will become
+ *
+ * ```yaml
+ * - allOf:
+ *   - 5
+ * - types: ["never"]
+ * - required: ["a", "b"]
+ * - allOf:
+ *   - 2
+ *   - 100
+ * - objectProperties:
+ *     a: 1
+ * - anyOf:
+ *   - 3
+ *   - 4
+ * ```
+ */
+pub fn transform(arena: &mut SchemaArena, key: usize) {
+  let item = arena.get_item(key);
+
+  if item.dependent_required.is_none() && item.dependent_schemas.is_none() {
+    return;
+  }
+
+  let item = item.clone();
+  let dependent_required = item.dependent_required.clone().unwrap_or_default();
+  let dependent_schemas = item.dependent_schemas.clone().unwrap_or_default();
+  let names: BTreeSet<_> = dependent_required
+    .keys()
+    .chain(dependent_schemas.keys())
+    .collect();
+
+  let never_key = arena.add_item(ArenaSchemaItem {
+    types: Some(vec![SchemaType::Never]),
+    ..Default::default()
+  });
+
+  let mut sub_keys = item.all_of.clone().unwrap_or_default();
+  for name in names {
+    let required = [name.clone()]
+      .into_iter()
+      .chain(dependent_required.get(name).into_iter().flatten().cloned())
+      .collect();
+    let mut present_key = arena.add_item(ArenaSchemaItem {
+      required: Some(required),
+      ..Default::default()
+    });
+    if let Some(dependent_schema) = dependent_schemas.get(name) {
+      present_key = arena.add_item(ArenaSchemaItem {
+        all_of: Some([present_key, *dependent_schema].into()),
+        ..Default::default()
+      });
+    }
+
+    let absent_key = arena.add_item(ArenaSchemaItem {
+      object_properties: Some(BTreeMap::from([(name.clone(), never_key)])),
+      ..Default::default()
+    });
+
+    let sub_key = arena.add_item(ArenaSchemaItem {
+      any_of: Some([present_key, absent_key].into()),
+      ..Default::default()
+    });
+    sub_keys.insert(sub_key);
+  }
+
+  let item_new = ArenaSchemaItem {
+    dependent_required: None,
+    dependent_schemas: None,
+    all_of: Some(sub_keys),
+    ..item
+  };
+
+  arena.replace_item(key, item_new);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    documents::{self, DocumentContext},
+    utilities::NodeLocation,
+    validation::Validator,
+  };
+  use serde_json::json;
+  use std::rc::Rc;
+
+  #[test]
+  fn test_transform() {
+    let mut arena = SchemaArena::new();
+
+    arena.add_item(ArenaSchemaItem {
+      dependent_required: Some([("a".to_owned(), ["b".to_owned()].into())].into()),
+      dependent_schemas: Some([("a".to_owned(), 100)].into()),
+      ..Default::default()
+    });
+
+    while arena.apply_transform(transform) > 0 {
+      //
+    }
+
+    let actual: Vec<_> = arena.iter().cloned().collect();
+    let expected: Vec<_> = [
+      ArenaSchemaItem {
+        all_of: Some([5].into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Never]),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        required: Some(["a", "b"].map(str::to_owned).into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        all_of: Some([2, 100].into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        object_properties: Some([("a".to_owned(), 1)].into()),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        any_of: Some([3, 4].into()),
+        ..Default::default()
+      },
+    ]
+    .into();
+
+    assert_eq!(actual, expected)
+  }
+
+  #[tokio::test]
+  async fn test_transform_validate() {
+    let mut document_context = Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+    let location: NodeLocation = "/schema.json".parse().unwrap();
+    document_context
+      .load_from_node(
+        location.clone(),
+        location.clone(),
+        None,
+        json!({
+          "dependencies": {
+            "a": ["b"],
+            "c": { "required": ["d"] }
+          }
+        }),
+        documents::draft_07::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let mut arena = SchemaArena::from_document_context(&document_context).unwrap();
+    while arena.apply_transform(transform) > 0 {
+      //
+    }
+    assert!(
+      arena
+        .iter()
+        .all(|item| item.dependent_required.is_none() && item.dependent_schemas.is_none())
+    );
+
+    let validator = Validator::new(arena).unwrap();
+    let is_valid = |instance| validator.is_valid(&location, &instance).unwrap();
+    assert!(is_valid(json!({})));
+    assert!(is_valid(json!({ "a": 1, "b": 2 })));
+    assert!(is_valid(json!({ "b": 2, "d": 4 })));
+    assert!(is_valid(json!({ "c": 3, "d": 4 })));
+    assert!(is_valid(json!("a")));
+    assert!(!is_valid(json!({ "a": 1 })));
+    assert!(!is_valid(json!({ "c": 3, "b": 2 })));
+  }
+}
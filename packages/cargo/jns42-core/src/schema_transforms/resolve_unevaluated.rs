@@ -0,0 +1,158 @@
+use crate::models::SchemaArena;
+
+/**
+ * This transformer turns unevaluatedProperties into mapProperties and unevaluatedItems into
+ * arrayItems. This is only possible when there are no sub schemas left that could evaluate
+ * properties or items, so after allOf is resolved and after the unevaluated keywords are
+ * inherited by the oneOf and anyOf sub schemas.
+ *
+ * ```yaml
+ * - objectProperties:
+ *     a: 1
+ *   unevaluatedProperties: 2
+ * ```
+ *
+ * will become
+ *
+ * ```yaml
+ * - objectProperties:
+ *     a: 1
+ *   mapProperties: 2
+ * ```
+ *
+ * If there already are mapProperties, every property is evaluated by them, so
+ * unevaluatedProperties is dropped. The same goes for unevaluatedItems and arrayItems.
+ */
+pub fn transform(arena: &mut SchemaArena, key: usize) {
+  let item = arena.get_item(key);
+
+  if item.unevaluated_properties.is_none() && item.unevaluated_items.is_none() {
+    return;
+  }
+
+  // sub schemas that might evaluate something
+  if item.reference.is_some()
+    || item.all_of.is_some()
+    || item.any_of.is_some()
+    || item.one_of.is_some()
+    || item.r#if.is_some()
+    || item.then.is_some()
+    || item.r#else.is_some()
+    || item.dependent_schemas.is_some()
+  {
+    return;
+  }
+
+  let mut item_new = item.clone();
+
+  item_new.map_properties = item_new
+    .map_properties
+    .or(item_new.unevaluated_properties.take());
+
+  // contains evaluates the items that are valid against it, we cannot express that with
+  // arrayItems
+  if item_new.contains.is_none() {
+    item_new.array_items = item_new.array_items.or(item_new.unevaluated_items.take());
+  }
+
+  if item_new == *item {
+    return;
+  }
+
+  arena.replace_item(key, item_new);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::models::{ArenaSchemaItem, SchemaType};
+
+  #[test]
+  fn test_transform() {
+    let mut arena = SchemaArena::from_iter([
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::String]),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Never]),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Object]),
+        object_properties: Some([("a".to_owned(), 0)].into()),
+        unevaluated_properties: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Object]),
+        map_properties: Some(0),
+        unevaluated_properties: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        all_of: Some([2, 3].into()),
+        unevaluated_properties: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Array]),
+        tuple_items: Some(vec![0]),
+        unevaluated_items: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Array]),
+        contains: Some(0),
+        unevaluated_items: Some(1),
+        ..Default::default()
+      },
+    ]);
+
+    while arena.apply_transform(transform) > 0 {
+      //
+    }
+
+    let actual: Vec<_> = arena.iter().cloned().collect();
+    let expected = vec![
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::String]),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Never]),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Object]),
+        object_properties: Some([("a".to_owned(), 0)].into()),
+        map_properties: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Object]),
+        map_properties: Some(0),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        all_of: Some([2, 3].into()),
+        unevaluated_properties: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Array]),
+        tuple_items: Some(vec![0]),
+        array_items: Some(1),
+        ..Default::default()
+      },
+      ArenaSchemaItem {
+        types: Some(vec![SchemaType::Array]),
+        contains: Some(0),
+        unevaluated_items: Some(1),
+        ..Default::default()
+      },
+    ];
+
+    assert_eq!(actual, expected)
+  }
+}
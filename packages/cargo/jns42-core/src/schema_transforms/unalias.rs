@@ -47,6 +47,9 @@ pub fn transform(arena: &mut SchemaArena, key: usize) {
   resolve_single_key!(property_names);
   resolve_single_key!(array_items);
   resolve_single_key!(contains);
+  resolve_single_key!(unevaluated_properties);
+  resolve_single_key!(unevaluated_items);
+  resolve_single_key!(content_schema);
 
   resolve_array_keys!(all_of);
   resolve_array_keys!(any_of);
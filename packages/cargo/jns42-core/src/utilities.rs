@@ -1,17 +1,25 @@
 mod arena;
 mod banner;
+mod decimal;
+mod ecma_pattern;
 mod fetch_text;
+mod fetcher;
 mod json_value;
 mod merge;
 mod node_cache;
 mod node_location;
 mod product;
+mod source_position;
 
 pub use arena::*;
 pub use banner::*;
+pub use decimal::*;
+pub use ecma_pattern::*;
 pub use fetch_text::*;
+pub use fetcher::*;
 pub use json_value::*;
 pub use merge::*;
 pub use node_cache::*;
 pub use node_location::*;
 pub use product::*;
+pub use source_position::*;
@@ -1,3 +1,7 @@
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
+
 /// A generic container that stores elements in an immutable vector.
 ///
 /// # Type Parameters
@@ -8,10 +12,74 @@
 /// that can be efficiently cloned without copying the entire underlying data. It supports operations
 /// such as adding, replacing, and retrieving items by index, as well as iterating over the items.
 /// The arena ensures that elements are type-safe and can be compared for equality.
-#[derive(Clone, Debug, PartialEq, Default)]
-pub struct Arena<I>(im::Vector<I>)
+///
+/// The arena keeps track of which items are read and changed by transforms, so
+/// `apply_transform` only has to revisit the items that might change.
+#[derive(Clone, Debug, Default)]
+pub struct Arena<I>
 where
-  I: Clone + PartialEq;
+  I: Clone + PartialEq,
+{
+  items: im::Vector<I>,
+  tracking: RefCell<Tracking<I>>,
+}
+
+/// Bookkeeping of `apply_transform`.
+#[derive(Clone, Debug)]
+struct Tracking<I> {
+  /// The key of the item that is being transformed, if any.
+  transforming: Option<usize>,
+  /// Keys of the items that were read by the transform that is running.
+  reads: BTreeSet<usize>,
+  /// The transform that is running read every item.
+  reads_all: bool,
+  /// Items as they were before the transform that is running replaced them, indexed by key.
+  originals: BTreeMap<usize, I>,
+  /// Keys of the items that were replaced or added outside of a transform.
+  changes: BTreeSet<usize>,
+  /// Keys of the transformed items that read an item, indexed by the key of the item.
+  readers: BTreeMap<usize, BTreeSet<usize>>,
+  /// Keys of the transformed items that read every item.
+  all_readers: BTreeSet<usize>,
+  /// Keys of the items to transform in the next pass. Empty if the last pass reached the
+  /// fixed point.
+  pending: BTreeSet<usize>,
+}
+
+impl<I> Default for Tracking<I> {
+  fn default() -> Self {
+    Self {
+      transforming: None,
+      reads: Default::default(),
+      reads_all: false,
+      originals: Default::default(),
+      changes: Default::default(),
+      readers: Default::default(),
+      all_readers: Default::default(),
+      pending: Default::default(),
+    }
+  }
+}
+
+impl<I> Tracking<I> {
+  /// Keys of the items that need to be transformed again when the item at `key` changed.
+  fn get_dependents(&self, key: usize) -> impl Iterator<Item = usize> + '_ {
+    std::iter::once(key)
+      .chain(self.readers.get(&key).into_iter().flatten().copied())
+      .chain(self.all_readers.iter().copied())
+  }
+}
+
+impl<I> PartialEq for Arena<I>
+where
+  I: Clone + PartialEq,
+{
+  /// Arenas are equal if their items are equal.
+  fn eq(&self, other: &Self) -> bool {
+    self.items == other.items
+  }
+}
+
 impl<I> Arena<I>
 where
   I: Clone + PartialEq,
@@ -22,7 +90,10 @@ where
   ///
   /// An instance of `Arena<I>`.
   pub fn new() -> Self {
-    Self(im::Vector::new())
+    Self {
+      items: im::Vector::new(),
+      tracking: Default::default(),
+    }
   }
 
   /// Returns the number of elements in the arena.
@@ -31,7 +102,8 @@ where
   ///
   /// The number of elements as `usize`.
   pub fn count(&self) -> usize {
-    self.0.len()
+    self.read_all();
+    self.items.len()
   }
 
   /// Retrieves a reference to the item at the specified key.
@@ -48,7 +120,12 @@ where
   ///
   /// Panics if `key` is out of bounds.
   pub fn get_item(&self, key: usize) -> &I {
-    self.0.get(key).unwrap()
+    let mut tracking = self.tracking.borrow_mut();
+    if tracking.transforming.is_some() {
+      tracking.reads.insert(key);
+    }
+
+    self.items.get(key).unwrap()
   }
 
   /// Replaces the item at the specified key with a new item.
@@ -62,7 +139,20 @@ where
   ///
   /// The item that was replaced.
   pub fn replace_item(&mut self, key: usize, item: I) -> I {
-    self.0.set(key, item)
+    let item_previous = self.items.set(key, item);
+
+    let tracking = self.tracking.get_mut();
+    if tracking.transforming.is_some() {
+      // the transform might put the original back, so we compare when it is done
+      tracking
+        .originals
+        .entry(key)
+        .or_insert_with(|| item_previous.clone());
+    } else if self.items[key] != item_previous {
+      tracking.changes.insert(key);
+    }
+
+    item_previous
   }
 
   /// Adds a new item to the end of the arena and returns its key.
@@ -75,8 +165,14 @@ where
   ///
   /// The key (index) of the newly added item.
   pub fn add_item(&mut self, item: I) -> usize {
-    let key = self.0.len();
-    self.0.push_back(item);
+    let key = self.items.len();
+    self.items.push_back(item);
+
+    let tracking = self.tracking.get_mut();
+    if tracking.transforming.is_none() {
+      tracking.changes.insert(key);
+    }
+
     key
   }
 
@@ -86,10 +182,21 @@ where
   ///
   /// An iterator over references to the items.
   pub fn iter(&self) -> impl Iterator<Item = &I> {
-    self.0.iter()
+    self.read_all();
+    self.items.iter()
   }
 
-  /// Applies a transformation function to each item in the arena, tracking the number of changes.
+  /// Applies a transformation function to the items in the arena, tracking the number of changes.
+  ///
+  /// This is one pass of a loop that runs until the fixed point is reached, that is until
+  /// this function returns 0. Every item is transformed in the first pass. After that only
+  /// items that changed, or that read an item that changed, are transformed again. Items
+  /// are transformed in the order of their keys, items that need to be transformed again
+  /// and come after the item that is being transformed are transformed in the same pass,
+  /// so the result is the same as transforming every item in every pass.
+  ///
+  /// Every loop should use the same transform until the fixed point is reached, the pass
+  /// after the fixed point is a first pass again.
   ///
   /// # Parameters
   ///
@@ -99,22 +206,99 @@ where
   ///
   /// The number of items that were changed by the transformation.
   pub fn apply_transform(&mut self, transform: impl Fn(&mut Self, usize)) -> usize {
+    let item_count = self.items.len();
+    let tracking = self.tracking.get_mut();
+    let mut queue = if tracking.pending.is_empty() {
+      *tracking = Default::default();
+      (0..item_count).collect()
+    } else {
+      let mut queue = mem::take(&mut tracking.pending);
+      for key in mem::take(&mut tracking.changes) {
+        queue.extend(tracking.get_dependents(key));
+      }
+      queue
+    };
+
     let mut count = 0;
-    let mut key = 0;
-    while key < self.0.len() {
-      let self_previous = self.clone();
+    while let Some(key) = queue.pop_first() {
+      let changed_keys = self.run_transform(key, &transform);
+      if changed_keys.is_empty() {
+        continue;
+      }
 
-      transform(self, key);
+      count += 1;
 
-      if self.0 != self_previous.0 {
-        count += 1
+      let tracking = self.tracking.get_mut();
+      // this item is transformed again in the next pass, like every item that depends
+      // on a changed item and is not transformed later in this pass
+      tracking.pending.insert(key);
+      for changed_key in changed_keys {
+        let dependent_keys: Vec<_> = tracking.get_dependents(changed_key).collect();
+        for dependent_key in dependent_keys {
+          if dependent_key > key {
+            queue.insert(dependent_key);
+          } else {
+            tracking.pending.insert(dependent_key);
+          }
+        }
       }
-
-      key += 1;
     }
 
     count
   }
+
+  /// Applies a transformation function to the item at the specified key, outside of the
+  /// passes of `apply_transform`.
+  ///
+  /// # Parameters
+  ///
+  /// * `key`: The key of the item to transform.
+  /// * `transform`: A function that takes a mutable reference to the arena and an item key, and performs a transformation.
+  ///
+  /// # Returns
+  ///
+  /// The keys of the items that were changed or added by the transformation.
+  pub fn transform_item(&mut self, key: usize, transform: impl Fn(&mut Self, usize)) -> Vec<usize> {
+    let changed_keys = self.run_transform(key, &transform);
+
+    let tracking = self.tracking.get_mut();
+    tracking.changes.extend(changed_keys.iter().copied());
+
+    changed_keys
+  }
+
+  /// Transform the item at key while recording what is read, returns the keys of the
+  /// items that changed.
+  fn run_transform(&mut self, key: usize, transform: &impl Fn(&mut Self, usize)) -> Vec<usize> {
+    let item_count_previous = self.items.len();
+
+    self.tracking.get_mut().transforming = Some(key);
+    transform(self, key);
+
+    let tracking = self.tracking.get_mut();
+    tracking.transforming = None;
+
+    for read_key in mem::take(&mut tracking.reads) {
+      tracking.readers.entry(read_key).or_default().insert(key);
+    }
+    if mem::take(&mut tracking.reads_all) {
+      tracking.all_readers.insert(key);
+    }
+
+    mem::take(&mut tracking.originals)
+      .into_iter()
+      .filter(|(changed_key, original)| self.items[*changed_key] != *original)
+      .map(|(changed_key, _original)| changed_key)
+      .chain(item_count_previous..self.items.len())
+      .collect()
+  }
+
+  fn read_all(&self) {
+    let mut tracking = self.tracking.borrow_mut();
+    if tracking.transforming.is_some() {
+      tracking.reads_all = true;
+    }
+  }
 }
 
 impl<I> FromIterator<I> for Arena<I>
@@ -131,6 +315,115 @@ where
   ///
   /// An instance of `Arena<I>` containing the items from the iterator.
   fn from_iter<T: IntoIterator<Item = I>>(iter: T) -> Self {
-    Self(im::Vector::from_iter(iter))
+    Self {
+      items: im::Vector::from_iter(iter),
+      tracking: Default::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::cell::Cell;
+
+  /// Every item is the maximum of itself and the item it points to, until it reaches the
+  /// limit, then it points to a new item.
+  fn transform(arena: &mut Arena<(usize, usize)>, key: usize) {
+    let (value, target) = *arena.get_item(key);
+    let (target_value, _target) = *arena.get_item(target);
+
+    if target_value > value {
+      arena.replace_item(key, (target_value, target));
+    } else if value == 10 {
+      let new_key = arena.add_item((11, key));
+      arena.replace_item(key, (value + 1, new_key));
+    }
+  }
+
+  /// Transform every item in every pass, the way we did it before tracking.
+  fn apply_transform_naive(
+    items: &mut Vec<(usize, usize)>,
+    transform: impl Fn(&mut Arena<(usize, usize)>, usize),
+  ) -> usize {
+    let mut count = 0;
+    let mut key = 0;
+    while key < items.len() {
+      let mut arena: Arena<_> = items.iter().cloned().collect();
+      transform(&mut arena, key);
+      let items_next: Vec<_> = arena.iter().cloned().collect();
+      if items_next != *items {
+        *items = items_next;
+        count += 1;
+      }
+      key += 1;
+    }
+    count
+  }
+
+  #[test]
+  fn test_apply_transform() {
+    let initial = vec![
+      (1, 4),
+      (2, 0),
+      (3, 1),
+      (10, 2),
+      (4, 3),
+      (0, 0),
+      (5, 5),
+      (7, 1),
+    ];
+
+    let mut expected = initial.clone();
+    let mut expected_counts = Vec::new();
+    loop {
+      let count = apply_transform_naive(&mut expected, transform);
+      expected_counts.push(count);
+      if count == 0 {
+        break;
+      }
+    }
+
+    let mut arena: Arena<_> = initial.into_iter().collect();
+    let mut actual_counts = Vec::new();
+    loop {
+      let count = arena.apply_transform(transform);
+      actual_counts.push(count);
+      if count == 0 {
+        break;
+      }
+    }
+    let actual: Vec<_> = arena.iter().cloned().collect();
+
+    assert_eq!(actual, expected);
+    assert_eq!(actual_counts, expected_counts);
+  }
+
+  #[test]
+  fn test_apply_transform_revisits() {
+    // a chain where every item depends on the next one
+    let mut arena: Arena<(usize, usize)> = (0..100).map(|key| (0, (key + 1) % 100)).collect();
+    arena.replace_item(99, (1, 0));
+
+    let calls = Cell::new(0);
+    let transform = |arena: &mut Arena<(usize, usize)>, key: usize| {
+      calls.set(calls.get() + 1);
+      let (value, target) = *arena.get_item(key);
+      let (target_value, _target) = *arena.get_item(target);
+      if target_value > value {
+        arena.replace_item(key, (target_value, target));
+      }
+    };
+
+    while arena.apply_transform(transform) > 0 {}
+
+    assert!(arena.iter().all(|(value, _target)| *value == 1));
+    // the naive way would be 100 passes of 100 calls
+    assert!(calls.get() < 400, "{} calls", calls.get());
+
+    // after the fixed point every item is transformed again
+    calls.set(0);
+    assert_eq!(arena.apply_transform(transform), 0);
+    assert_eq!(calls.get(), 100);
   }
 }
@@ -0,0 +1,204 @@
+use bigdecimal::{BigDecimal, RoundingMode, ToPrimitive, Zero};
+use std::{fmt, str::FromStr};
+
+/// An exact decimal number. Numbers in schemas are kept as they are written, so `0.1` is
+/// really `0.1` and big integers do not lose precision.
+#[derive(
+  Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[serde(try_from = "serde_json::Number")]
+#[serde(into = "serde_json::Number")]
+pub struct Decimal(BigDecimal);
+
+impl Decimal {
+  /// The decimal that is written like `value`, `None` if `value` is not finite
+  pub fn from_f64(value: f64) -> Option<Self> {
+    // the display of a float is the shortest decimal that parses back to the same float
+    value.to_string().parse().ok()
+  }
+
+  /// The nearest float, this might lose precision
+  pub fn to_f64(&self) -> f64 {
+    self.0.to_f64().unwrap_or(f64::NAN)
+  }
+
+  pub fn to_i128(&self) -> Option<i128> {
+    self.0.to_i128()
+  }
+
+  pub fn to_u128(&self) -> Option<u128> {
+    self.0.to_u128()
+  }
+
+  pub fn is_integer(&self) -> bool {
+    self.0.is_integer()
+  }
+
+  pub fn is_negative(&self) -> bool {
+    self.0 < BigDecimal::zero()
+  }
+
+  /// `true` if dividing by `other` leaves no remainder, nothing is a multiple of zero
+  pub fn is_multiple_of(&self, other: &Self) -> bool {
+    !other.0.is_zero() && (&self.0 % &other.0).is_zero()
+  }
+
+  /// The smallest integer that is not less than this decimal
+  pub fn ceil(&self) -> Self {
+    Self(self.0.with_scale_round(0, RoundingMode::Ceiling))
+  }
+
+  /// The largest integer that is not greater than this decimal
+  pub fn floor(&self) -> Self {
+    Self(self.0.with_scale_round(0, RoundingMode::Floor))
+  }
+
+  /// The decimal without an exponent, like `0.01` or `100000000000000000000`
+  pub fn to_plain_string(&self) -> String {
+    self.0.normalized().to_plain_string()
+  }
+
+  pub fn to_json(&self) -> serde_json::Value {
+    serde_json::Value::Number(self.clone().into())
+  }
+}
+
+impl fmt::Display for Decimal {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.0)
+  }
+}
+
+impl FromStr for Decimal {
+  type Err = bigdecimal::ParseBigDecimalError;
+
+  fn from_str(input: &str) -> Result<Self, Self::Err> {
+    input.parse().map(Self)
+  }
+}
+
+impl std::ops::Add for &Decimal {
+  type Output = Decimal;
+
+  fn add(self, other: Self) -> Decimal {
+    Decimal(&self.0 + &other.0)
+  }
+}
+
+impl std::ops::Sub for &Decimal {
+  type Output = Decimal;
+
+  fn sub(self, other: Self) -> Decimal {
+    Decimal(&self.0 - &other.0)
+  }
+}
+
+impl From<i32> for Decimal {
+  fn from(value: i32) -> Self {
+    Self(value.into())
+  }
+}
+
+impl From<i64> for Decimal {
+  fn from(value: i64) -> Self {
+    Self(value.into())
+  }
+}
+
+impl From<i128> for Decimal {
+  fn from(value: i128) -> Self {
+    Self(value.into())
+  }
+}
+
+impl From<u128> for Decimal {
+  fn from(value: u128) -> Self {
+    Self(value.into())
+  }
+}
+
+impl TryFrom<&serde_json::Number> for Decimal {
+  type Error = bigdecimal::ParseBigDecimalError;
+
+  /// With the `arbitrary-precision` feature the decimal is exactly the text of the number,
+  /// without it the decimal is the shortest that parses back to the same float.
+  fn try_from(value: &serde_json::Number) -> Result<Self, Self::Error> {
+    value.to_string().parse()
+  }
+}
+
+impl TryFrom<serde_json::Number> for Decimal {
+  type Error = bigdecimal::ParseBigDecimalError;
+
+  fn try_from(value: serde_json::Number) -> Result<Self, Self::Error> {
+    (&value).try_into()
+  }
+}
+
+impl From<Decimal> for serde_json::Number {
+  fn from(value: Decimal) -> Self {
+    value
+      .0
+      .to_string()
+      .parse()
+      .expect("a decimal is a valid json number")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_exact() {
+    let value: serde_json::Value = serde_json::from_str("0.3").unwrap();
+    let value = Decimal::try_from(value.as_number().unwrap()).unwrap();
+    let multiple_of: Decimal = "0.1".parse().unwrap();
+    assert!(value.is_multiple_of(&multiple_of));
+    assert!(!value.is_multiple_of(&"0.2".parse().unwrap()));
+    assert!(!value.is_multiple_of(&Decimal::from(0)));
+  }
+
+  #[cfg(feature = "arbitrary-precision")]
+  #[test]
+  fn test_exact_big_integer() {
+    let value: serde_json::Value = serde_json::from_str("100000000000000000001").unwrap();
+    let value = Decimal::try_from(value.as_number().unwrap()).unwrap();
+    assert!(value > "1e20".parse().unwrap());
+    assert_eq!(value.to_plain_string(), "100000000000000000001");
+  }
+
+  #[test]
+  fn test_from_f64() {
+    assert_eq!(Decimal::from_f64(0.01).unwrap().to_plain_string(), "0.01");
+    assert_eq!(Decimal::from_f64(1e20).unwrap(), "1e20".parse().unwrap());
+    assert_eq!(Decimal::from_f64(f64::NAN), None);
+    assert_eq!(Decimal::from_f64(f64::INFINITY), None);
+  }
+
+  #[test]
+  fn test_round() {
+    let value: Decimal = "-1.5".parse().unwrap();
+    assert_eq!(value.ceil(), Decimal::from(-1));
+    assert_eq!(value.floor(), Decimal::from(-2));
+    assert_eq!(Decimal::from(3).ceil(), Decimal::from(3));
+    assert_eq!("1e2".parse::<Decimal>().unwrap().to_plain_string(), "100");
+  }
+
+  #[test]
+  fn test_json() {
+    let value: Decimal = "0.010".parse().unwrap();
+    assert_eq!(value, "0.01".parse().unwrap());
+    let value: Decimal = serde_json::from_str("1.0").unwrap();
+    assert_eq!(value, Decimal::from(1));
+  }
+
+  #[cfg(feature = "arbitrary-precision")]
+  #[test]
+  fn test_json_exact() {
+    let value: Decimal = "0.010".parse().unwrap();
+    assert_eq!(serde_json::to_string(&value).unwrap(), "0.010");
+    let value: Decimal = serde_json::from_str("12345678901234567890123").unwrap();
+    assert_eq!(value.to_json().to_string(), "12345678901234567890123");
+  }
+}
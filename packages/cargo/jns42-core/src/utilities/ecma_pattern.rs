@@ -0,0 +1,858 @@
+use std::fmt;
+
+/// The characters of ECMA-262 that `\s` matches, white space and line terminators
+const WHITE_SPACE: [(char, char); 10] = [
+  ('\t', '\r'),
+  (' ', ' '),
+  ('\u{a0}', '\u{a0}'),
+  ('\u{1680}', '\u{1680}'),
+  ('\u{2000}', '\u{200a}'),
+  ('\u{2028}', '\u{2029}'),
+  ('\u{202f}', '\u{202f}'),
+  ('\u{205f}', '\u{205f}'),
+  ('\u{3000}', '\u{3000}'),
+  ('\u{feff}', '\u{feff}'),
+];
+
+/// The characters that have to be escaped in a unicode mode ECMA-262 pattern, in a class the
+/// `-` has to be escaped too
+const SYNTAX_CHARACTERS: &str = "^$\\.*+?()[]{}|/";
+const CLASS_SYNTAX_CHARACTERS: &str = "^$\\.*+?()[]{}|/-";
+
+/// The characters that have to be escaped for the regex crate, in a class there are set
+/// operations like `&&`
+const RUST_SYNTAX_CHARACTERS: &str = "\\.+*?()|[]{}^$";
+const RUST_CLASS_SYNTAX_CHARACTERS: &str = "\\[]^-&~";
+
+/// A regular expression from a `pattern` or `patternProperties` keyword. JSON Schema uses the
+/// ECMA-262 dialect for these.
+///
+/// Patterns are read with the syntax of annex B, the one without the `u` flag, so what that
+/// syntax reads as characters is accepted: `a{,2}`, `\c` without a control letter, octal
+/// escapes like `\101`, a `\2` when there are fewer groups and class escapes in a range like
+/// `[\d-z]`. The escapes of the `u` flag are read too, `\u{1F600}` is one code point and
+/// `\p{Letter}` is a property instead of text, a surrogate pair is one character and lone
+/// surrogates are an error. Patterns match code points, like with the `u` flag.
+///
+/// The pattern is translated to the dialect of the `regex` crate, so `\d` and `\w` are ascii
+/// only and `.` does not match line terminators, like in ECMA-262. Lookarounds and
+/// backreferences can not be expressed in that dialect, those patterns are matched by the
+/// `regress` crate, an ECMA-262 engine.
+#[derive(Debug, Clone)]
+pub enum EcmaPattern {
+  Regex {
+    source: String,
+    translated: String,
+    regex: regex::Regex,
+  },
+  Regress {
+    source: String,
+    translated: String,
+    regex: regress::Regex,
+  },
+}
+
+impl EcmaPattern {
+  pub fn new(source: &str) -> Result<Self, PatternError> {
+    let characters: Vec<_> = source.chars().collect();
+    let (group_count, group_names) = scan_groups(&characters)?;
+
+    let mut translator = Translator {
+      characters: &characters,
+      index: 0,
+      group_count,
+      group_names: &group_names,
+      rust: Some(String::new()),
+      unicode: String::new(),
+    };
+    translator.parse_disjunction()?;
+    if translator.index < characters.len() {
+      return Err(translator.error("unmatched closing parenthesis"));
+    }
+    let Translator { rust, unicode, .. } = translator;
+
+    // regress is the reference for the syntax, it knows the unicode properties of ECMA-262
+    let regress = regress::Regex::with_flags(&unicode, "u")
+      .map_err(|error| PatternError::new(error.to_string()))?;
+
+    if let Some(rust) = rust
+      && let Ok(regex) = regex::Regex::new(&rust)
+    {
+      return Ok(Self::Regex {
+        source: source.to_owned(),
+        translated: rust,
+        regex,
+      });
+    }
+
+    Ok(Self::Regress {
+      source: source.to_owned(),
+      translated: unicode,
+      regex: regress,
+    })
+  }
+
+  /// The pattern as it is in the schema
+  pub fn get_source(&self) -> &str {
+    match self {
+      Self::Regex { source, .. } | Self::Regress { source, .. } => source,
+    }
+  }
+
+  /// The pattern in the dialect of the engine that matches it. For `regress` this is a
+  /// pattern for the `u` flag.
+  pub fn get_translated(&self) -> &str {
+    match self {
+      Self::Regex { translated, .. } | Self::Regress { translated, .. } => translated,
+    }
+  }
+
+  /// `true` if the pattern needs the `regress` crate
+  pub fn is_regress(&self) -> bool {
+    matches!(self, Self::Regress { .. })
+  }
+
+  /// `true` if the pattern matches anywhere in `value`, patterns are not anchored
+  pub fn is_match(&self, value: &str) -> bool {
+    match self {
+      Self::Regex { regex, .. } => regex.is_match(value),
+      Self::Regress { regex, .. } => regex.find(value).is_some(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+  message: String,
+}
+
+impl PatternError {
+  fn new(message: String) -> Self {
+    Self { message }
+  }
+}
+
+impl fmt::Display for PatternError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message)
+  }
+}
+
+impl std::error::Error for PatternError {}
+
+/// Counts the capturing groups and collects the names of the named groups, backreferences
+/// may point to groups that come after them.
+fn scan_groups(characters: &[char]) -> Result<(usize, Vec<String>), PatternError> {
+  let mut count = 0;
+  let mut names = Vec::new();
+  let mut in_class = false;
+  let mut index = 0;
+  while index < characters.len() {
+    match characters[index] {
+      '\\' => index += 1,
+      '[' => in_class = true,
+      ']' => in_class = false,
+      '(' if !in_class => match characters.get(index + 1..index + 3) {
+        Some(['?', '<']) if !matches!(characters.get(index + 3), Some('=' | '!')) => {
+          count += 1;
+          let name: String = characters[index + 3..]
+            .iter()
+            .take_while(|c| **c != '>')
+            .collect();
+          if names.contains(&name) {
+            return Err(PatternError::new(format!("duplicate group name {}", name)));
+          }
+          names.push(name);
+        }
+        Some(['?', _]) => {}
+        _ => count += 1,
+      },
+      _ => {}
+    }
+    index += 1;
+  }
+  Ok((count, names))
+}
+
+/// What may follow a term
+enum Term {
+  Quantifiable,
+  /// annex B allows quantified lookaheads, the unicode mode does not
+  Lookahead,
+  Assertion,
+}
+
+/// An atom of a character class
+enum ClassAtom {
+  Character(char),
+  /// a class escape like `\d`, in the dialect of the regex crate and in unicode mode
+  Escape(String, String),
+}
+
+/// Translates a pattern, read as described on [`EcmaPattern`], to both the dialect of the regex
+/// crate and to a pattern for the unicode mode of ECMA-262.
+struct Translator<'c> {
+  characters: &'c [char],
+  index: usize,
+  group_count: usize,
+  group_names: &'c [String],
+  /// the pattern for the regex crate, `None` if that crate can not express it
+  rust: Option<String>,
+  unicode: String,
+}
+
+impl Translator<'_> {
+  fn error(&self, message: &str) -> PatternError {
+    PatternError::new(format!("{} at position {}", message, self.index))
+  }
+
+  fn peek(&self) -> Option<char> {
+    self.characters.get(self.index).copied()
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let character = self.peek()?;
+    self.index += 1;
+    Some(character)
+  }
+
+  fn accept(&mut self, expected: &str) -> bool {
+    let expected: Vec<_> = expected.chars().collect();
+    if self.characters[self.index..].starts_with(&expected) {
+      self.index += expected.len();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn push(&mut self, rust: &str, unicode: &str) {
+    if let Some(value) = &mut self.rust {
+      value.push_str(rust);
+    }
+    self.unicode.push_str(unicode);
+  }
+
+  /// Pushes something the regex crate can not express, like a lookaround
+  fn push_unicode(&mut self, unicode: &str) {
+    self.rust = None;
+    self.unicode.push_str(unicode);
+  }
+
+  fn push_literal(&mut self, character: char) {
+    self.push(
+      &escape(character, RUST_SYNTAX_CHARACTERS, "\\x"),
+      &escape(character, SYNTAX_CHARACTERS, "\\u"),
+    );
+  }
+
+  fn parse_disjunction(&mut self) -> Result<(), PatternError> {
+    loop {
+      self.parse_alternative()?;
+      if !self.accept("|") {
+        return Ok(());
+      }
+      self.push("|", "|");
+    }
+  }
+
+  fn parse_alternative(&mut self) -> Result<(), PatternError> {
+    while let Some(character) = self.peek() {
+      let start = self.unicode.len();
+      let term = match character {
+        '|' | ')' => return Ok(()),
+        '^' | '$' => {
+          self.index += 1;
+          self.push(&character.to_string(), &character.to_string());
+          Term::Assertion
+        }
+        '\\' if matches!(self.characters.get(self.index + 1), Some('b' | 'B')) => {
+          let assertion = self.characters[self.index + 1];
+          self.index += 2;
+          // a word boundary uses the ascii word characters
+          self.push(
+            &format!("(?-u:\\{})", assertion),
+            &format!("\\{}", assertion),
+          );
+          Term::Assertion
+        }
+        '(' => self.parse_group()?,
+        '[' => {
+          self.parse_class()?;
+          Term::Quantifiable
+        }
+        '\\' => {
+          self.parse_atom_escape()?;
+          Term::Quantifiable
+        }
+        '.' => {
+          self.index += 1;
+          self.push("[^\\n\\r\\x{2028}\\x{2029}]", ".");
+          Term::Quantifiable
+        }
+        '*' | '+' | '?' => return Err(self.error("nothing to repeat")),
+        '{' if self.is_quantifier_braces() => return Err(self.error("nothing to repeat")),
+        _ => {
+          self.index += 1;
+          self.push_literal(character);
+          Term::Quantifiable
+        }
+      };
+
+      let Some(quantifier) = self.parse_quantifier()? else {
+        continue;
+      };
+      match term {
+        Term::Quantifiable => {}
+        Term::Lookahead => {
+          self.unicode.insert_str(start, "(?:");
+          self.unicode.push(')');
+        }
+        Term::Assertion => return Err(self.error("nothing to repeat")),
+      }
+      self.push(&quantifier, &quantifier);
+    }
+    Ok(())
+  }
+
+  fn parse_group(&mut self) -> Result<Term, PatternError> {
+    self.index += 1;
+    let term = if self.accept("?:") {
+      self.push("(?:", "(?:");
+      Term::Quantifiable
+    } else if self.accept("?=") {
+      self.push_unicode("(?=");
+      Term::Lookahead
+    } else if self.accept("?!") {
+      self.push_unicode("(?!");
+      Term::Lookahead
+    } else if self.accept("?<=") {
+      self.push_unicode("(?<=");
+      Term::Assertion
+    } else if self.accept("?<!") {
+      self.push_unicode("(?<!");
+      Term::Assertion
+    } else if self.accept("?<") {
+      let name = self.parse_group_name()?;
+      // the regex crate is more strict about names, the name is only needed for
+      // backreferences and those are not translated anyway
+      let is_rust_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+      let rust = if is_rust_name {
+        format!("(?<{}>", name)
+      } else {
+        "(".to_owned()
+      };
+      self.push(&rust, &format!("(?<{}>", name));
+      Term::Quantifiable
+    } else if self.peek() == Some('?') {
+      return Err(self.error("invalid group"));
+    } else {
+      self.push("(", "(");
+      Term::Quantifiable
+    };
+
+    self.parse_disjunction()?;
+    if !self.accept(")") {
+      return Err(self.error("missing closing parenthesis"));
+    }
+    self.push(")", ")");
+    Ok(term)
+  }
+
+  /// Parses the name of a group up to and including the `>`.
+  fn parse_group_name(&mut self) -> Result<String, PatternError> {
+    let mut name = String::new();
+    loop {
+      match self.next() {
+        Some('>') if !name.is_empty() => return Ok(name),
+        Some(character) if character == '$' || character == '_' || character.is_alphabetic() => {
+          name.push(character)
+        }
+        Some(character) if character.is_alphanumeric() && !name.is_empty() => name.push(character),
+        _ => return Err(self.error("invalid group name")),
+      }
+    }
+  }
+
+  /// `true` if the characters at the current position are a quantifier like `{1,2}`.
+  fn is_quantifier_braces(&self) -> bool {
+    self.get_quantifier_braces().is_some()
+  }
+
+  /// The minimum and the optional maximum of a quantifier like `{1,2}` at the current
+  /// position.
+  fn get_quantifier_braces(&self) -> Option<(String, Option<String>)> {
+    let rest = &self.characters[self.index..];
+    if rest.first() != Some(&'{') {
+      return None;
+    }
+    let end = rest.iter().position(|c| *c == '}')?;
+    let inner: String = rest[1..end].iter().collect();
+    let (minimum, maximum) = match inner.split_once(',') {
+      Some((minimum, maximum)) => (minimum.to_owned(), Some(maximum.to_owned())),
+      None => (inner.clone(), Some(inner.clone())),
+    };
+    let is_digits = |value: &str| !value.is_empty() && value.chars().all(|c| c.is_ascii_digit());
+    if !is_digits(&minimum)
+      || maximum
+        .as_deref()
+        .is_some_and(|value| !value.is_empty() && !is_digits(value))
+    {
+      return None;
+    }
+    Some((minimum, maximum.filter(|value| !value.is_empty())))
+  }
+
+  /// Parses a quantifier if there is one and returns it
+  fn parse_quantifier(&mut self) -> Result<Option<String>, PatternError> {
+    let mut quantifier = match self.peek() {
+      Some(character @ ('*' | '+' | '?')) => {
+        self.index += 1;
+        character.to_string()
+      }
+      Some('{') => {
+        let Some((minimum, maximum)) = self.get_quantifier_braces() else {
+          return Ok(None);
+        };
+        self.index += self.characters[self.index..]
+          .iter()
+          .position(|c| *c == '}')
+          .unwrap_or_default()
+          + 1;
+
+        // the numbers are compared as digits because they may be too big for any integer
+        let minimum = trim_zeros(&minimum);
+        if let Some(maximum) = &maximum {
+          let maximum = trim_zeros(maximum);
+          if (minimum.len(), minimum) > (maximum.len(), maximum) {
+            return Err(self.error("numbers out of order in quantifier"));
+          }
+        }
+        match maximum {
+          Some(maximum) if maximum == minimum => format!("{{{}}}", minimum),
+          Some(maximum) => format!("{{{},{}}}", minimum, trim_zeros(&maximum)),
+          None => format!("{{{},}}", minimum),
+        }
+      }
+      _ => return Ok(None),
+    };
+    if self.accept("?") {
+      quantifier.push('?');
+    }
+    Ok(Some(quantifier))
+  }
+
+  fn parse_atom_escape(&mut self) -> Result<(), PatternError> {
+    self.index += 1;
+    let Some(character) = self.next() else {
+      return Err(self.error("pattern ends with a backslash"));
+    };
+    match character {
+      'd' | 'D' | 's' | 'S' | 'w' | 'W' => {
+        let rust = rust_class_escape(character, false);
+        self.push(&rust, &format!("\\{}", character));
+      }
+      'p' | 'P' if self.peek() == Some('{') => {
+        let property = self.parse_property(character)?;
+        self.push(&property, &property);
+      }
+      'k' if !self.group_names.is_empty() => {
+        if !self.accept("<") {
+          return Err(self.error("invalid named reference"));
+        }
+        let name = self.parse_group_name()?;
+        if !self.group_names.contains(&name) {
+          return Err(self.error("reference to an unknown group"));
+        }
+        self.push_unicode(&format!("\\k<{}>", name));
+      }
+      '1'..='9' => {
+        let start = self.index - 1;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+          self.index += 1;
+        }
+        let digits: String = self.characters[start..self.index].iter().collect();
+        if digits
+          .parse::<usize>()
+          .is_ok_and(|number| number <= self.group_count)
+        {
+          self.push_unicode(&format!("\\{}", digits));
+        } else {
+          // annex B reads this as an octal escape or as a digit
+          self.index = start + 1;
+          let character = self.parse_character_escape(character, false)?;
+          self.push_literal(character);
+        }
+      }
+      _ => {
+        let character = self.parse_character_escape(character, false)?;
+        self.push_literal(character);
+      }
+    }
+    Ok(())
+  }
+
+  /// Parses a unicode property escape like `\p{Letter}`, the name is checked later by regress.
+  fn parse_property(&mut self, character: char) -> Result<String, PatternError> {
+    self.index += 1;
+    let mut name = String::new();
+    loop {
+      match self.next() {
+        Some('}') if !name.is_empty() => return Ok(format!("\\{}{{{}}}", character, name)),
+        Some(character) if character.is_ascii_alphanumeric() || matches!(character, '_' | '=') => {
+          name.push(character)
+        }
+        _ => return Err(self.error("invalid property name")),
+      }
+    }
+  }
+
+  /// Parses the rest of an escape that starts with `\` and `character` and returns the
+  /// character it stands for.
+  fn parse_character_escape(
+    &mut self,
+    character: char,
+    in_class: bool,
+  ) -> Result<char, PatternError> {
+    let value = match character {
+      't' => '\t',
+      'n' => '\n',
+      'v' => '\u{b}',
+      'f' => '\u{c}',
+      'r' => '\r',
+      'c' => {
+        let control = self
+          .peek()
+          .filter(|c| c.is_ascii_alphabetic() || (in_class && (c.is_ascii_digit() || *c == '_')));
+        match control {
+          Some(control) => {
+            self.index += 1;
+            char::from(control as u8 % 32)
+          }
+          None => {
+            // annex B reads a `\c` without a control letter as a backslash and a `c`
+            self.index -= 1;
+            '\\'
+          }
+        }
+      }
+      '0'..='7' => {
+        // a legacy octal escape, the value is at most 0o377
+        let mut value = character.to_digit(8).unwrap_or_default();
+        let length = if value < 4 { 3 } else { 2 };
+        for _ in 1..length {
+          match self.peek().and_then(|c| c.to_digit(8)) {
+            Some(digit) => {
+              value = value * 8 + digit;
+              self.index += 1;
+            }
+            None => break,
+          }
+        }
+        char::from_u32(value).unwrap_or_default()
+      }
+      'x' => self.parse_hex_digits(2).unwrap_or('x'),
+      'u' => {
+        if self.peek() == Some('{')
+          && let Some(end) = self.characters[self.index..].iter().position(|c| *c == '}')
+        {
+          let digits: String = self.characters[self.index + 1..self.index + end]
+            .iter()
+            .collect();
+          if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            self.index += end + 1;
+            return u32::from_str_radix(&digits, 16)
+              .ok()
+              .and_then(char::from_u32)
+              .ok_or_else(|| self.error("invalid unicode escape"));
+          }
+        }
+        let Some(value) = self.parse_hex_value(4) else {
+          return Ok('u');
+        };
+        // a surrogate pair is one character
+        if (0xd800..0xdc00).contains(&value) && self.accept("\\u") {
+          match self.parse_hex_value(4) {
+            Some(low) if (0xdc00..0xe000).contains(&low) => {
+              let value = 0x10000 + ((value - 0xd800) << 10) + (low - 0xdc00);
+              return char::from_u32(value).ok_or_else(|| self.error("invalid unicode escape"));
+            }
+            _ => self.index -= 2,
+          }
+        }
+        char::from_u32(value).ok_or_else(|| self.error("lone surrogates can not be matched"))?
+      }
+      character => character,
+    };
+    Ok(value)
+  }
+
+  /// Parses `count` hex digits if there are that many, annex B reads fewer as characters.
+  fn parse_hex_value(&mut self, count: usize) -> Option<u32> {
+    let rest = &self.characters[self.index..];
+    if rest.len() < count || !rest[..count].iter().all(|c| c.is_ascii_hexdigit()) {
+      return None;
+    }
+    let digits: String = rest[..count].iter().collect();
+    self.index += count;
+    u32::from_str_radix(&digits, 16).ok()
+  }
+
+  fn parse_hex_digits(&mut self, count: usize) -> Option<char> {
+    self.parse_hex_value(count).and_then(char::from_u32)
+  }
+
+  fn parse_class(&mut self) -> Result<(), PatternError> {
+    self.index += 1;
+    let negated = self.accept("^");
+
+    let mut rust = String::new();
+    let mut unicode = String::new();
+    loop {
+      if self.accept("]") {
+        break;
+      }
+      let atom = self.parse_class_atom()?;
+      let range = self.peek() == Some('-')
+        && self
+          .characters
+          .get(self.index + 1)
+          .is_some_and(|c| *c != ']');
+      if !range {
+        push_class_atom(&mut rust, &mut unicode, atom);
+        continue;
+      }
+
+      self.index += 1;
+      let end = self.parse_class_atom()?;
+      match (atom, end) {
+        (ClassAtom::Character(start), ClassAtom::Character(end)) => {
+          if start > end {
+            return Err(self.error("range out of order in character class"));
+          }
+          push_class_atom(&mut rust, &mut unicode, ClassAtom::Character(start));
+          rust.push('-');
+          unicode.push('-');
+          push_class_atom(&mut rust, &mut unicode, ClassAtom::Character(end));
+        }
+        // annex B allows class escapes in a range, the `-` is then a character
+        (start, end) => {
+          push_class_atom(&mut rust, &mut unicode, start);
+          push_class_atom(&mut rust, &mut unicode, ClassAtom::Character('-'));
+          push_class_atom(&mut rust, &mut unicode, end);
+        }
+      }
+    }
+
+    // the regex crate has no empty classes
+    let rust = match (negated, rust.is_empty()) {
+      (false, true) => "[^\\x{0}-\\x{10FFFF}]".to_owned(),
+      (true, true) => "[\\x{0}-\\x{10FFFF}]".to_owned(),
+      (false, false) => format!("[{}]", rust),
+      (true, false) => format!("[^{}]", rust),
+    };
+    let unicode = format!("[{}{}]", if negated { "^" } else { "" }, unicode);
+    self.push(&rust, &unicode);
+    Ok(())
+  }
+
+  fn parse_class_atom(&mut self) -> Result<ClassAtom, PatternError> {
+    let Some(character) = self.next() else {
+      return Err(self.error("missing closing bracket"));
+    };
+    if character != '\\' {
+      return Ok(ClassAtom::Character(character));
+    }
+
+    let Some(character) = self.next() else {
+      return Err(self.error("pattern ends with a backslash"));
+    };
+    let atom = match character {
+      'd' | 'D' | 's' | 'S' | 'w' | 'W' => ClassAtom::Escape(
+        rust_class_escape(character, true),
+        format!("\\{}", character),
+      ),
+      'p' | 'P' if self.peek() == Some('{') => {
+        let property = self.parse_property(character)?;
+        ClassAtom::Escape(property.clone(), property)
+      }
+      'b' => ClassAtom::Character('\u{8}'),
+      // a digit that is not octal is a character in a class, backreferences are not allowed
+      '8' | '9' => ClassAtom::Character(character),
+      character => ClassAtom::Character(self.parse_character_escape(character, true)?),
+    };
+    Ok(atom)
+  }
+}
+
+fn push_class_atom(rust: &mut String, unicode: &mut String, atom: ClassAtom) {
+  match atom {
+    ClassAtom::Character(character) => {
+      rust.push_str(&escape(character, RUST_CLASS_SYNTAX_CHARACTERS, "\\x"));
+      unicode.push_str(&escape(character, CLASS_SYNTAX_CHARACTERS, "\\u"));
+    }
+    ClassAtom::Escape(rust_escape, unicode_escape) => {
+      rust.push_str(&rust_escape);
+      unicode.push_str(&unicode_escape);
+    }
+  }
+}
+
+/// The class escapes `\d`, `\s` and `\w` and their negations as the regex crate knows them,
+/// in the regex crate these are not ascii only.
+fn rust_class_escape(character: char, in_class: bool) -> String {
+  let items = match character.to_ascii_lowercase() {
+    'd' => "0-9".to_owned(),
+    'w' => "0-9A-Za-z_".to_owned(),
+    _ => WHITE_SPACE
+      .iter()
+      .map(|(start, end)| {
+        let start = escape(*start, RUST_CLASS_SYNTAX_CHARACTERS, "\\x");
+        let end = escape(*end, RUST_CLASS_SYNTAX_CHARACTERS, "\\x");
+        if start == end {
+          start
+        } else {
+          format!("{}-{}", start, end)
+        }
+      })
+      .collect(),
+  };
+  match (character.is_ascii_uppercase(), in_class) {
+    (true, _) => format!("[^{}]", items),
+    (false, true) => items,
+    (false, false) => format!("[{}]", items),
+  }
+}
+
+/// Escapes a character if it is one of the `special` characters. Control characters are
+/// written as code points with the `prefix`, `\x` for the regex crate and `\u` for ECMA-262.
+fn escape(character: char, special: &str, prefix: &str) -> String {
+  if special.contains(character) {
+    format!("\\{}", character)
+  } else if character.is_control() {
+    format!("{}{{{:X}}}", prefix, character as u32)
+  } else {
+    character.to_string()
+  }
+}
+
+fn trim_zeros(value: &str) -> &str {
+  let value = value.trim_start_matches('0');
+  if value.is_empty() { "0" } else { value }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_matches(pattern: &str, matching: &[&str], not_matching: &[&str]) {
+    let pattern = EcmaPattern::new(pattern).unwrap();
+    for value in matching {
+      assert!(pattern.is_match(value), "{:?} {:?}", pattern, value);
+    }
+    for value in not_matching {
+      assert!(!pattern.is_match(value), "{:?} {:?}", pattern, value);
+    }
+  }
+
+  #[test]
+  fn test_translate() {
+    let translated = |pattern| {
+      EcmaPattern::new(pattern)
+        .unwrap()
+        .get_translated()
+        .to_owned()
+    };
+
+    assert_eq!(translated("^[a-z]+$"), "^[a-z]+$");
+    assert_eq!(translated("^\\d{2,3}$"), "^[0-9]{2,3}$");
+    assert_eq!(translated("a.b"), "a[^\\n\\r\\x{2028}\\x{2029}]b");
+    assert_eq!(translated("[\\w\\-]"), "[0-9A-Za-z_\\-]");
+    assert_eq!(translated("^x\\-[a&&b]"), "^x-[a\\&\\&b]");
+    assert_eq!(translated("\\/\\_{ "), "/_\\{ ");
+    assert_eq!(translated("(?<year>\\d+)"), "(?<year>[0-9]+)");
+    assert_eq!(translated("\\u00e9\\x41\\cJ"), "éA\\x{A}");
+    assert_eq!(translated("[]"), "[^\\x{0}-\\x{10FFFF}]");
+    assert_eq!(translated("<>"), "<>");
+
+    assert_eq!(translated("(a)\\1"), "(a)\\1");
+    assert_eq!(translated("(?=a)*b"), "(?:(?=a))*b");
+    assert_eq!(translated("(?<a>x)\\k<a>[\\-]"), "(?<a>x)\\k<a>[\\-]");
+  }
+
+  #[test]
+  fn test_engine() {
+    assert!(!EcmaPattern::new("^[a-z]+$").unwrap().is_regress());
+    assert!(!EcmaPattern::new("\\p{Letter}").unwrap().is_regress());
+    assert!(EcmaPattern::new("^(?!foo)").unwrap().is_regress());
+    assert!(EcmaPattern::new("(?<=a)b").unwrap().is_regress());
+    assert!(EcmaPattern::new("(a)\\1").unwrap().is_regress());
+  }
+
+  #[test]
+  fn test_ecma_semantics() {
+    assert_matches("^\\d$", &["0", "9"], &["৪", "a"]);
+    assert_matches("^\\w$", &["a", "_", "Z"], &["é", "-"]);
+    assert_matches(
+      "^\\s$",
+      &[" ", "\t", "\u{feff}", "\u{2028}", "\u{a0}"],
+      &["a"],
+    );
+    assert_matches("^\\S$", &["a"], &[" ", "\u{feff}"]);
+    assert_matches("^.$", &["a", "é", "😀"], &["\n", "\r", "\u{2028}"]);
+    assert_matches("^[^]$", &["\n"], &["", "ab"]);
+    assert_matches("[]", &[], &["", "a"]);
+    assert_matches("\\bfoo", &["a foo", "éfoo"], &["_foo", "afoo"]);
+    assert_matches("^\\p{Letter}+$", &["héllo"], &["1"]);
+    assert_matches("^\\u{1F600}$", &["😀"], &["a"]);
+    assert_matches("^\\u{41}$", &["A"], &["u"]);
+    assert_matches("^\\uD83D\\uDE00$", &["😀"], &["a"]);
+    assert_matches("^\\101\\0$", &["A\0"], &["A"]);
+    assert_matches("^a{2}$", &["aa"], &["a", "aaa"]);
+    assert_matches("^a{,2}$", &["a{,2}"], &["aa"]);
+    assert_matches("^]}$", &["]}"], &[]);
+    assert_matches("^[\\d-z]+$", &["1-z"], &["y"]);
+    assert_matches("^\\$$", &["$"], &[""]);
+    assert_matches("^\\cj$", &["\n"], &["cj"]);
+    assert_matches("^\\c$", &["\\c"], &[]);
+  }
+
+  #[test]
+  fn test_regress_semantics() {
+    assert_matches("^(?!foo)\\w+$", &["bar"], &["foobar"]);
+    assert_matches("(?<=\\$)\\d+", &["$10"], &["10"]);
+    assert_matches("^(\\w)\\1$", &["aa"], &["ab"]);
+    assert_matches("^(?<char>\\w)\\k<char>$", &["bb"], &["ba"]);
+    assert_matches("^(?:(?=a)[a-z])+$", &["aaa"], &["ab"]);
+    assert_matches("^(?=a)*b", &["b"], &["a"]);
+    // no backreference, there is only one group so this is an octal escape
+    assert_matches("^(a)\\2$", &["a\u{2}"], &["aa"]);
+  }
+
+  #[test]
+  fn test_invalid() {
+    for pattern in [
+      "(",
+      ")",
+      "[a",
+      "*a",
+      "a**",
+      "{1}",
+      "^*",
+      "a{2,1}",
+      "[z-a]",
+      "(?<=a)*",
+      "(?x)",
+      "(?<1>a)",
+      "(?<a>a)(?<a>b)",
+      "(?<a>a)\\k<b>",
+      "\\",
+      "\\uD800",
+      "\\u{110000}",
+      "\\p{Unknown}",
+    ] {
+      assert!(EcmaPattern::new(pattern).is_err(), "{}", pattern);
+    }
+  }
+}
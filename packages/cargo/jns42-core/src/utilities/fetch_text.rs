@@ -1,26 +1,53 @@
+use std::fmt::Display;
 use wasm_bindgen::prelude::*;
 
+/// Why a fetch failed, io and http errors carry the message of the underlying error.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub enum FetchTextError {
-  IoError,
-  HttpError,
+  IoError(String),
+  HttpError(String),
+  NotFound,
+  Refused,
+}
+
+impl std::error::Error for FetchTextError {}
+
+impl Display for FetchTextError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::IoError(message) => write!(f, "IoError: {}", message),
+      Self::HttpError(message) => write!(f, "HttpError: {}", message),
+      Self::NotFound => write!(f, "NotFound"),
+      Self::Refused => write!(f, "Refused"),
+    }
+  }
 }
 
 impl From<std::io::Error> for FetchTextError {
-  fn from(_value: std::io::Error) -> Self {
-    Self::IoError
+  fn from(value: std::io::Error) -> Self {
+    Self::IoError(value.to_string())
   }
 }
 
 impl From<JsValue> for FetchTextError {
-  fn from(_value: JsValue) -> Self {
-    Self::HttpError
+  fn from(value: JsValue) -> Self {
+    Self::HttpError(
+      value
+        .as_string()
+        .or_else(|| {
+          value
+            .dyn_ref::<js_sys::Error>()
+            .map(|error| error.message().into())
+        })
+        .unwrap_or_default(),
+    )
   }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
 impl From<surf::Error> for FetchTextError {
-  fn from(_value: surf::Error) -> Self {
-    Self::HttpError
+  fn from(value: surf::Error) -> Self {
+    Self::HttpError(value.to_string())
   }
 }
 
@@ -30,23 +57,47 @@ extern "C" {
   async fn fetch_text_js(location: &str) -> Result<JsValue, JsValue>;
 }
 
-#[cfg(target_arch = "wasm32")]
 pub async fn fetch_text(location: &str) -> Result<String, FetchTextError> {
+  fetch_text_with_headers(location, &[]).await
+}
+
+/// Like `fetch_text`, but sends the headers with http(s) requests. Headers are not supported
+/// when running as webassembly, that is an error.
+///
+#[cfg(target_arch = "wasm32")]
+pub async fn fetch_text_with_headers(
+  location: &str,
+  headers: &[(String, String)],
+) -> Result<String, FetchTextError> {
+  if !headers.is_empty() {
+    return Err(FetchTextError::HttpError(
+      "headers are not supported in webassembly".to_owned(),
+    ));
+  }
+
   let text = fetch_text_js(location).await?;
   let text = text.as_string().unwrap_or_default();
 
   Ok(text)
 }
 
+/// Like `fetch_text`, but sends the headers with http(s) requests. Headers are not supported
+/// when running as webassembly, that is an error.
+///
 #[cfg(not(target_arch = "wasm32"))]
-pub async fn fetch_text(location: &str) -> Result<String, FetchTextError> {
+pub async fn fetch_text_with_headers(
+  location: &str,
+  headers: &[(String, String)],
+) -> Result<String, FetchTextError> {
   use tokio::fs::File;
   use tokio::io::AsyncReadExt;
 
   if location.starts_with("http://") || location.starts_with("https://") {
-    let mut response = surf::get(location)
-      .middleware(surf::middleware::Redirect::new(5))
-      .await?;
+    let mut request = surf::get(location).middleware(surf::middleware::Redirect::new(5));
+    for (name, value) in headers {
+      request = request.header(name.as_str(), value.as_str());
+    }
+    let mut response = request.await?;
     let data = response.body_string().await?;
     Ok(data)
   } else {
@@ -0,0 +1,247 @@
+use super::{FetchTextError, fetch_text, fetch_text_with_headers};
+use futures::future::LocalBoxFuture;
+use std::collections::BTreeMap;
+
+/// Retrieves the text of documents, the node cache uses a fetcher to load every
+/// document that is not in the cache yet.
+///
+pub trait Fetcher {
+  /// Fetch the text at the location, the location is a fetch string (see
+  /// `NodeLocation::to_fetch_string`)
+  ///
+  fn fetch_text<'a>(
+    &'a self,
+    location: &'a str,
+  ) -> LocalBoxFuture<'a, Result<String, FetchTextError>>;
+}
+
+/// Fetches documents from the filesystem or via http(s).
+///
+#[derive(Clone, Debug)]
+pub struct DefaultFetcher {
+  headers: Vec<(String, String)>,
+  network: bool,
+}
+
+impl Default for DefaultFetcher {
+  fn default() -> Self {
+    Self {
+      headers: Default::default(),
+      network: true,
+    }
+  }
+}
+
+impl DefaultFetcher {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Send a header with every http(s) request, for example for authorization. Headers
+  /// are not supported when running as webassembly, http(s) requests fail if there are any.
+  ///
+  pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+    self.headers.push((name.into(), value.into()));
+    self
+  }
+
+  /// Refuse to fetch anything over the network, only the filesystem is used.
+  ///
+  pub fn without_network(mut self) -> Self {
+    self.network = false;
+    self
+  }
+
+  fn is_network_location(location: &str) -> bool {
+    let location = location.to_lowercase();
+    location.starts_with("http://") || location.starts_with("https://")
+  }
+}
+
+impl Fetcher for DefaultFetcher {
+  fn fetch_text<'a>(
+    &'a self,
+    location: &'a str,
+  ) -> LocalBoxFuture<'a, Result<String, FetchTextError>> {
+    Box::pin(async move {
+      if !Self::is_network_location(location) {
+        return fetch_text(location).await;
+      }
+
+      if !self.network {
+        return Err(FetchTextError::Refused);
+      }
+
+      fetch_text_with_headers(location, &self.headers).await
+    })
+  }
+}
+
+/// Serves documents from memory, useful for testing.
+///
+#[derive(Clone, Debug, Default)]
+pub struct MemoryFetcher {
+  documents: BTreeMap<String, String>,
+}
+
+impl MemoryFetcher {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn with_document(mut self, location: impl Into<String>, data: impl Into<String>) -> Self {
+    self.documents.insert(location.into(), data.into());
+    self
+  }
+}
+
+impl Fetcher for MemoryFetcher {
+  fn fetch_text<'a>(
+    &'a self,
+    location: &'a str,
+  ) -> LocalBoxFuture<'a, Result<String, FetchTextError>> {
+    Box::pin(async move {
+      self
+        .documents
+        .get(location)
+        .cloned()
+        .ok_or(FetchTextError::NotFound)
+    })
+  }
+}
+
+/// Rewrites location prefixes before passing the location to another fetcher. This makes it
+/// possible to serve, for instance, `https://schemas.example.com/` from a local directory.
+///
+pub struct PrefixFetcher {
+  prefixes: Vec<(String, String)>,
+  fetcher: Box<dyn Fetcher>,
+}
+
+impl PrefixFetcher {
+  pub fn new(fetcher: impl Fetcher + 'static) -> Self {
+    Self {
+      prefixes: Default::default(),
+      fetcher: Box::new(fetcher),
+    }
+  }
+
+  /// Map locations starting with `prefix` to `replacement`. The first matching prefix
+  /// is used.
+  ///
+  pub fn with_prefix(mut self, prefix: impl Into<String>, replacement: impl Into<String>) -> Self {
+    self.prefixes.push((prefix.into(), replacement.into()));
+    self
+  }
+}
+
+impl Fetcher for PrefixFetcher {
+  fn fetch_text<'a>(
+    &'a self,
+    location: &'a str,
+  ) -> LocalBoxFuture<'a, Result<String, FetchTextError>> {
+    Box::pin(async move {
+      let location = self
+        .prefixes
+        .iter()
+        .find_map(|(prefix, replacement)| {
+          location
+            .strip_prefix(prefix.as_str())
+            .map(|rest| replacement.clone() + rest)
+        })
+        .unwrap_or_else(|| location.to_owned());
+
+      self.fetcher.fetch_text(&location).await
+    })
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[tokio::test]
+  async fn test_prefix_fetcher() {
+    let fetcher = PrefixFetcher::new(MemoryFetcher::new().with_document("/local/a.json", "{}"))
+      .with_prefix("https://schemas.example.com/", "/local/");
+
+    assert_eq!(
+      fetcher
+        .fetch_text("https://schemas.example.com/a.json")
+        .await
+        .ok(),
+      Some("{}".to_owned())
+    );
+    assert!(
+      fetcher
+        .fetch_text("https://schemas.example.com/b.json")
+        .await
+        .is_err()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_default_fetcher_without_network() {
+    let fetcher = DefaultFetcher::new().without_network();
+
+    assert!(matches!(
+      fetcher
+        .fetch_text("https://schemas.example.com/a.json")
+        .await,
+      Err(FetchTextError::Refused)
+    ));
+    assert!(
+      fetcher
+        .fetch_text("../../../fixtures/specifications/string.json")
+        .await
+        .is_ok()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_default_fetcher_with_header() {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+    // the redirect middleware might send more than one request
+    std::thread::spawn(move || {
+      for stream in listener.incoming() {
+        let mut stream = stream.unwrap();
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+        while !request.ends_with(b"\r\n\r\n") {
+          let length = stream.read(&mut buffer).unwrap();
+          request.extend_from_slice(&buffer[..length]);
+        }
+        stream
+          .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}")
+          .unwrap();
+        sender
+          .send(String::from_utf8(request).unwrap().to_lowercase())
+          .unwrap();
+      }
+    });
+
+    let fetcher = DefaultFetcher::new().with_header("Authorization", "Bearer secret");
+    assert_eq!(
+      fetcher
+        .fetch_text(&format!("http://{}/a.json", address))
+        .await
+        .ok(),
+      Some("{}".to_owned())
+    );
+
+    let requests: Vec<_> = receiver.try_iter().collect();
+    assert!(!requests.is_empty());
+    assert!(requests.iter().all(|request| {
+      request.lines().any(|line| {
+        line
+          .split_once(':')
+          .is_some_and(|(name, value)| name == "authorization" && value.trim() == "bearer secret")
+      })
+    }));
+  }
+}
@@ -1,4 +1,4 @@
-use super::NodeLocation;
+use super::{Decimal, NodeLocation};
 
 #[derive(Clone, Debug)]
 pub struct JsonValue(serde_json::Value);
@@ -19,6 +19,10 @@ impl From<serde_json::Value> for JsonValue {
 helpers
 */
 impl JsonValue {
+  pub fn as_value(&self) -> &serde_json::Value {
+    &self.0
+  }
+
   pub fn as_bool(&self) -> Option<bool> {
     self.0.as_bool()
   }
@@ -36,12 +40,35 @@ impl JsonValue {
       .map(|value| value.iter().filter_map(|value| value.as_str()))
   }
 
+  /// The members of an object field that are lists of strings, other members are skipped.
+  pub fn string_list_object(
+    &self,
+    field: &str,
+  ) -> Option<impl Iterator<Item = (&str, impl Iterator<Item = &str>)>> {
+    Some(
+      self
+        .0
+        .as_object()?
+        .get(field)?
+        .as_object()?
+        .iter()
+        .filter_map(|(name, value)| {
+          let value = value.as_array()?;
+          Some((
+            name.as_str(),
+            value.iter().filter_map(|value| value.as_str()),
+          ))
+        }),
+    )
+  }
+
   pub fn unsigned_integer(&self, field: &str) -> Option<u32> {
     self.0.as_object()?.get(field)?.as_u64()?.try_into().ok()
   }
 
-  pub fn float(&self, field: &str) -> Option<f64> {
-    self.0.as_object()?.get(field)?.as_f64()
+  /// The exact value of a number field
+  pub fn decimal(&self, field: &str) -> Option<Decimal> {
+    self.0.as_object()?.get(field)?.as_number()?.try_into().ok()
   }
 
   pub fn bool(&self, field: &str) -> Option<bool> {
@@ -75,7 +102,6 @@ impl JsonValue {
     let selected = self.0.as_object()?.get(field)?;
     let pointer: Vec<_> = pointer
       .iter()
-      .cloned()
       .map(|part| part.to_string())
       .chain([field.to_string()])
       .collect();
@@ -102,7 +128,6 @@ impl JsonValue {
     let selected = self.0.as_object()?.get(field)?;
     let pointer: Vec<_> = pointer
       .iter()
-      .cloned()
       .map(|part| part.to_string())
       .chain([field.to_string()])
       .collect();
@@ -1,4 +1,6 @@
-use super::{FetchTextError, NodeLocation, fetch_text};
+use super::{
+  DefaultFetcher, FetchTextError, Fetcher, NodeLocation, SourcePosition, read_source_positions,
+};
 use std::collections::{BTreeMap, btree_map};
 use std::iter;
 
@@ -6,9 +8,18 @@ use std::iter;
 /// Nodes have a retrieval location that is the physical (possibly globally
 /// unique) location of the node.
 ///
-#[derive(Default)]
 pub struct NodeCache {
   root_nodes: BTreeMap<NodeLocation, serde_json::Value>,
+  /// line and column of the nodes in the source text, indexed by the retrieval location
+  /// of the document and then by the pointer of the node
+  positions: BTreeMap<NodeLocation, BTreeMap<Vec<String>, (usize, usize)>>,
+  fetcher: Box<dyn Fetcher>,
+}
+
+impl Default for NodeCache {
+  fn default() -> Self {
+    Self::with_fetcher(DefaultFetcher::new())
+  }
 }
 
 impl NodeCache {
@@ -16,6 +27,22 @@ impl NodeCache {
     Default::default()
   }
 
+  /// Create a cache that retrieves documents via `fetcher`
+  ///
+  pub fn with_fetcher(fetcher: impl Fetcher + 'static) -> Self {
+    Self {
+      root_nodes: Default::default(),
+      positions: Default::default(),
+      fetcher: Box::new(fetcher),
+    }
+  }
+
+  /// Replace the fetcher, nodes that are already in the cache are kept
+  ///
+  pub fn set_fetcher(&mut self, fetcher: impl Fetcher + 'static) {
+    self.fetcher = Box::new(fetcher);
+  }
+
   /// Retrieves all locations in the cache
   ///
   pub fn get_locations(&self) -> impl Iterator<Item = NodeLocation> + '_ {
@@ -34,19 +61,14 @@ impl NodeCache {
     &self,
     retrieval_location: &NodeLocation,
   ) -> Vec<(NodeLocation, &serde_json::Value)> {
-    let mut location = retrieval_location.set_root();
+    let location = retrieval_location.set_root();
     let pointer = retrieval_location.get_pointer().unwrap_or_default();
     self
       .get_node_path_with_member(&location, pointer.clone())
       .unwrap_or_default()
       .into_iter()
       .enumerate()
-      .map(|(index, node)| {
-        if index > 0 {
-          location = location.push_pointer(pointer[0..index - 1].to_vec());
-        }
-        (location.clone(), node)
-      })
+      .map(|(index, node)| (location.set_pointer(pointer[0..index].to_vec()), node))
       .collect()
   }
 
@@ -60,6 +82,21 @@ impl NodeCache {
     nodes.pop()
   }
 
+  /// Retrieves where the node starts in the source text of its document. Only nodes
+  /// that are loaded from a location have a position.
+  ///
+  pub fn get_source_position(&self, retrieval_location: &NodeLocation) -> Option<SourcePosition> {
+    let root_location = retrieval_location.set_root();
+    let pointer = retrieval_location.get_pointer().unwrap_or_default();
+    let (line, column) = *self.positions.get(&root_location)?.get(&pointer)?;
+
+    Some(SourcePosition {
+      location: root_location,
+      line,
+      column,
+    })
+  }
+
   /// Load nodes from a location. The retrieval location is the physical location of
   /// the node, it should be a root location
   ///
@@ -76,8 +113,27 @@ impl NodeCache {
       /*
       retrieve the document
       */
-      let data = fetch_text(&entry.key().to_fetch_string()).await?;
-      let root_node = serde_yaml::from_str(&data)?;
+      let data = self
+        .fetcher
+        .fetch_text(&entry.key().to_fetch_string())
+        .await
+        .map_err(|error| NodeCacheError::FetchError(entry.key().clone(), error))?;
+      let root_node = serde_yaml::from_str(&data).map_err(|error| {
+        let position = error.location();
+        NodeCacheError::SerializationError {
+          location: entry.key().clone(),
+          message: error.to_string(),
+          line: position.as_ref().map(|position| position.line()),
+          column: position.as_ref().map(|position| position.column()),
+        }
+      })?;
+
+      /*
+      remember where every node came from
+      */
+      self
+        .positions
+        .insert(entry.key().clone(), read_source_positions(&data));
 
       /*
       populate the cache with this document
@@ -103,7 +159,7 @@ impl NodeCache {
       entry.insert(node);
       Ok(())
     } else {
-      Err(NodeCacheError::Conflict)
+      Err(NodeCacheError::Conflict(retrieval_location.clone()))
     }
   }
 
@@ -165,24 +221,15 @@ impl NodeCache {
 
 #[derive(Debug)]
 pub enum NodeCacheError {
-  SerializationError,
-  Conflict,
-  FetchError,
-}
-
-impl From<FetchTextError> for NodeCacheError {
-  fn from(value: FetchTextError) -> Self {
-    match value {
-      FetchTextError::IoError => Self::FetchError,
-      FetchTextError::HttpError => Self::FetchError,
-    }
-  }
-}
-
-impl From<serde_yaml::Error> for NodeCacheError {
-  fn from(_value: serde_yaml::Error) -> Self {
-    Self::SerializationError
-  }
+  /// The document could not be parsed, line and column are one based
+  SerializationError {
+    location: NodeLocation,
+    message: String,
+    line: Option<usize>,
+    column: Option<usize>,
+  },
+  Conflict(NodeLocation),
+  FetchError(NodeLocation, FetchTextError),
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -216,4 +263,33 @@ mod tests {
       serde_json::Value::String("Full main category entity".into())
     );
   }
+
+  #[tokio::test]
+  async fn test_get_source_position() {
+    let mut cache = NodeCache::new();
+
+    let location: NodeLocation = "../../../fixtures/specifications/nwd.yaml".parse().unwrap();
+
+    cache.load_from_location(&location).await.unwrap();
+
+    let position = cache
+      .get_source_position(&location.set_pointer(vec![
+        "definitions".into(),
+        "main-category".into(),
+        "description".into(),
+      ]))
+      .unwrap();
+    assert_eq!(position.location, location);
+
+    let text = std::fs::read_to_string("../../../fixtures/specifications/nwd.yaml").unwrap();
+    let line = text.lines().nth(position.line - 1).unwrap();
+    // a block scalar starts at its indicator
+    assert_eq!(&line[position.column - 1..], "|-");
+
+    assert!(
+      cache
+        .get_source_position(&location.set_pointer(vec!["not-there".into()]))
+        .is_none()
+    );
+  }
 }
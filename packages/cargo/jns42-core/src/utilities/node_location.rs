@@ -180,6 +180,39 @@ impl NodeLocation {
       other.hash.clone(),
     )
   }
+
+  /**
+  The location that, joined with the base location, is this location. This is the reverse of
+  join. None if the location cannot be made relative to the base, because the origins differ or
+  only one of the paths is absolute.
+  */
+  pub fn make_relative(&self, base: &NodeLocation) -> Option<Self> {
+    if self.origin != base.origin || self.path.is_empty() || base.path.is_empty() {
+      return None;
+    }
+    if self.path.first().unwrap().is_empty() != base.path.first().unwrap().is_empty() {
+      return None;
+    }
+
+    let directory = &self.path[..self.path.len() - 1];
+    let base_directory = &base.path[..base.path.len() - 1];
+    let common = directory
+      .iter()
+      .zip(base_directory)
+      .take_while(|(part, base_part)| part == base_part)
+      .count();
+
+    let path = iter::repeat_n("..".to_owned(), base_directory.len() - common)
+      .chain(self.path[common..].iter().cloned())
+      .collect();
+
+    Some(NodeLocation::new(
+      Default::default(),
+      path,
+      self.query.clone(),
+      self.hash.clone(),
+    ))
+  }
 }
 
 impl TryFrom<&str> for NodeLocation {
@@ -405,6 +438,42 @@ mod tests {
     }
   }
 
+  #[test]
+  fn test_make_relative() {
+    do_test("/a/b/c.json", "/a/b/d.json", "c.json#");
+    do_test("/a/b/c.json#/x", "/a/b/c.json", "c.json#/x");
+    do_test("/a/e/c.json", "/a/b/d.json", "../e/c.json#");
+    do_test("/a/b/e/c.json", "/a/b/d.json", "e/c.json#");
+    do_test(
+      "http://www.example.com/a/c.json",
+      "http://www.example.com/b/d.json",
+      "../a/c.json#",
+    );
+
+    assert_eq!(
+      "http://www.example.com/a/c.json"
+        .parse::<NodeLocation>()
+        .unwrap()
+        .make_relative(&"/a/d.json".parse().unwrap()),
+      None
+    );
+    assert_eq!(
+      "a/c.json"
+        .parse::<NodeLocation>()
+        .unwrap()
+        .make_relative(&"/a/d.json".parse().unwrap()),
+      None
+    );
+
+    fn do_test(location: &str, base: &str, expected: &str) {
+      let location: NodeLocation = location.parse().unwrap();
+      let base: NodeLocation = base.parse().unwrap();
+      let actual = location.make_relative(&base).unwrap();
+      assert_eq!(actual.to_string(), expected);
+      assert_eq!(base.join(&actual), location);
+    }
+  }
+
   #[test]
   fn test_url_regex() {
     do_test(
@@ -0,0 +1,183 @@
+use super::NodeLocation;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use yaml_rust2::{Event, parser::Parser, scanner::TScalarStyle};
+
+/// Where a node starts in the source text of the document it was retrieved from.
+/// Line and column are one based.
+///
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct SourcePosition {
+  /// retrieval location of the document (the file)
+  pub location: NodeLocation,
+  pub line: usize,
+  pub column: usize,
+}
+
+impl Display for SourcePosition {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{}:{}:{}",
+      self.location.to_fetch_string(),
+      self.line,
+      self.column
+    )
+  }
+}
+
+enum Frame {
+  Mapping {
+    pointer: Vec<String>,
+    key: Option<String>,
+  },
+  Sequence {
+    pointer: Vec<String>,
+    index: usize,
+  },
+}
+
+/// Read the (one based) line and column of every node in the first document of the
+/// yaml (or json, json is yaml) text, indexed by the pointer of the node.
+///
+/// Nodes that come from an alias are not in the result, neither are nodes after the text
+/// stopped being valid yaml, parsing the text will report that.
+///
+pub fn read_source_positions(data: &str) -> BTreeMap<Vec<String>, (usize, usize)> {
+  let mut positions: BTreeMap<Vec<String>, (usize, usize)> = BTreeMap::new();
+  let mut frames: Vec<Frame> = Vec::new();
+  let mut parser = Parser::new_from_str(data);
+
+  while let Ok((event, marker)) = parser.next_token() {
+    let (line, column) = match event {
+      Event::Scalar(_, TScalarStyle::Literal | TScalarStyle::Folded, ..) => {
+        find_block_scalar_indicator(data, marker.line())
+          .unwrap_or((marker.line(), marker.col() + 1))
+      }
+      _ => (marker.line(), marker.col() + 1),
+    };
+
+    match event {
+      Event::SequenceEnd | Event::MappingEnd => {
+        frames.pop();
+        continue;
+      }
+      Event::DocumentEnd | Event::StreamEnd => break,
+      Event::Nothing | Event::StreamStart | Event::DocumentStart => continue,
+      _ => {}
+    }
+
+    let pointer = match frames.last_mut() {
+      None => Vec::new(),
+      Some(Frame::Mapping { pointer, key }) => match key.take() {
+        Some(member) => pointer.iter().cloned().chain([member]).collect(),
+        None => {
+          // this is the key of a member, keys that are not a string are not json
+          let Event::Scalar(scalar, ..) = event else {
+            break;
+          };
+          // the mark of a block mapping is at the colon of the first key, we want the start
+          // of that key
+          if let Some(position) = positions.get_mut(pointer) {
+            *position = (*position).min((line, column));
+          }
+          *key = Some(scalar);
+          continue;
+        }
+      },
+      Some(Frame::Sequence { pointer, index }) => {
+        let member = index.to_string();
+        *index += 1;
+        pointer.iter().cloned().chain([member]).collect()
+      }
+    };
+
+    match event {
+      Event::SequenceStart(..) => frames.push(Frame::Sequence {
+        pointer: pointer.clone(),
+        index: 0,
+      }),
+      Event::MappingStart(..) => frames.push(Frame::Mapping {
+        pointer: pointer.clone(),
+        key: None,
+      }),
+      _ => {}
+    }
+
+    positions.insert(pointer, (line, column));
+  }
+
+  positions
+}
+
+/// The mark of a block scalar is where the content starts, we want the position of the
+/// indicator (`|` or `>`). That is on the last line before the content that is not blank.
+fn find_block_scalar_indicator(data: &str, content_line: usize) -> Option<(usize, usize)> {
+  let (line_index, line) = data
+    .lines()
+    .enumerate()
+    .take(content_line.saturating_sub(1))
+    .filter(|(_, line)| !line.trim().is_empty())
+    .last()?;
+  let line = match line.find(" #") {
+    Some(comment) => &line[..comment],
+    None => line,
+  };
+  let indicator = line.rfind(['|', '>'])?;
+  Some((line_index + 1, line[..indicator].chars().count() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pointer(pointer: &[&str]) -> Vec<String> {
+    pointer.iter().map(|member| member.to_string()).collect()
+  }
+
+  #[test]
+  fn test_read_source_positions_yaml() {
+    let positions = read_source_positions(
+      "type: object\nproperties:\n  a:\n    type: string\n  b:\n    enum:\n      - 1\n      - [2, 3]\n",
+    );
+
+    assert_eq!(positions.get(&pointer(&[])), Some(&(1, 1)));
+    assert_eq!(positions.get(&pointer(&["type"])), Some(&(1, 7)));
+    assert_eq!(positions.get(&pointer(&["properties"])), Some(&(3, 3)));
+    assert_eq!(positions.get(&pointer(&["properties", "a"])), Some(&(4, 5)));
+    assert_eq!(
+      positions.get(&pointer(&["properties", "a", "type"])),
+      Some(&(4, 11))
+    );
+    assert_eq!(
+      positions.get(&pointer(&["properties", "b", "enum", "0"])),
+      Some(&(7, 9))
+    );
+    assert_eq!(
+      positions.get(&pointer(&["properties", "b", "enum", "1", "1"])),
+      Some(&(8, 13))
+    );
+  }
+
+  #[test]
+  fn test_read_source_positions_json() {
+    let positions = read_source_positions(
+      "{\n  \"$defs\": {\n    \"a/b\": { \"type\": [\"string\", null] }\n  }\n}\n",
+    );
+
+    assert_eq!(positions.get(&pointer(&[])), Some(&(1, 1)));
+    assert_eq!(positions.get(&pointer(&["$defs"])), Some(&(2, 12)));
+    assert_eq!(positions.get(&pointer(&["$defs", "a/b"])), Some(&(3, 12)));
+    assert_eq!(
+      positions.get(&pointer(&["$defs", "a/b", "type", "1"])),
+      Some(&(3, 33))
+    );
+  }
+
+  #[test]
+  fn test_read_source_positions_invalid() {
+    let positions = read_source_positions("a: 1\nb: [\n");
+
+    assert_eq!(positions.get(&pointer(&["a"])), Some(&(1, 4)));
+  }
+}
@@ -0,0 +1,10 @@
+mod formats;
+mod output;
+mod validator;
+
+pub use formats::*;
+pub use output::*;
+pub use validator::*;
+
+/// The source of the format checks, generated packages get a copy of it.
+pub const FORMATS_SOURCE: &str = include_str!("validation/formats.rs");
@@ -0,0 +1,1930 @@
+//! Checks for the formats of JSON Schema 2020-12.
+//!
+//! This module only uses the standard library. Generated packages get a copy of it, so
+//! generated code and the validator agree on what a valid format is.
+
+/// The formats that we can check, all formats of JSON Schema 2020-12.
+pub const FORMATS: [&str; 19] = [
+  "date-time",
+  "date",
+  "time",
+  "duration",
+  "email",
+  "idn-email",
+  "hostname",
+  "idn-hostname",
+  "ipv4",
+  "ipv6",
+  "uri",
+  "uri-reference",
+  "iri",
+  "iri-reference",
+  "uri-template",
+  "uuid",
+  "json-pointer",
+  "relative-json-pointer",
+  "regex",
+];
+
+/// `true` if we can check the `format`.
+pub fn is_known_format(format: &str) -> bool {
+  FORMATS.contains(&format)
+}
+
+/// Checks `value` against `format`. Formats that we do not know are only annotations, every
+/// value is valid for them.
+pub fn is_valid_format(format: &str, value: &str) -> bool {
+  match format {
+    "date-time" => is_date_time(value),
+    "date" => is_date(value),
+    "time" => is_time(value),
+    "duration" => is_duration(value),
+    "email" => is_email(value),
+    "idn-email" => is_idn_email(value),
+    "hostname" => is_hostname(value),
+    "idn-hostname" => is_idn_hostname(value),
+    "ipv4" => is_ipv4(value),
+    "ipv6" => is_ipv6(value),
+    "uri" => is_uri(value),
+    "uri-reference" => is_uri_reference(value),
+    "iri" => is_iri(value),
+    "iri-reference" => is_iri_reference(value),
+    "uri-template" => is_uri_template(value),
+    "uuid" => is_uuid(value),
+    "json-pointer" => is_json_pointer(value),
+    "relative-json-pointer" => is_relative_json_pointer(value),
+    "regex" => is_regex(value),
+    _ => true,
+  }
+}
+
+// dates and times, RFC 3339
+
+/// A `date-time` like `1963-06-19T08:30:06.283185Z`.
+pub fn is_date_time(value: &str) -> bool {
+  let bytes = value.as_bytes();
+  bytes.len() > 11
+    && is_full_date(&bytes[..10])
+    && matches!(bytes[10], b'T' | b't')
+    && is_full_time(&bytes[11..])
+}
+
+/// A `date` like `1963-06-19`.
+pub fn is_date(value: &str) -> bool {
+  is_full_date(value.as_bytes())
+}
+
+/// A `time` with an offset like `08:30:06Z`.
+pub fn is_time(value: &str) -> bool {
+  is_full_time(value.as_bytes())
+}
+
+fn is_full_date(bytes: &[u8]) -> bool {
+  let [y1, y2, y3, y4, b'-', m1, m2, b'-', d1, d2] = *bytes else {
+    return false;
+  };
+  let (Some(year), Some(month), Some(day)) = (
+    to_number(&[y1, y2, y3, y4]),
+    to_number(&[m1, m2]),
+    to_number(&[d1, d2]),
+  ) else {
+    return false;
+  };
+
+  let leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+  let days = match month {
+    1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+    4 | 6 | 9 | 11 => 30,
+    2 if leap_year => 29,
+    2 => 28,
+    _ => return false,
+  };
+
+  (1..=days).contains(&day)
+}
+
+fn is_full_time(bytes: &[u8]) -> bool {
+  let [h1, h2, b':', m1, m2, b':', s1, s2, ref rest @ ..] = *bytes else {
+    return false;
+  };
+  let (Some(hour), Some(minute), Some(second)) = (
+    to_number(&[h1, h2]),
+    to_number(&[m1, m2]),
+    to_number(&[s1, s2]),
+  ) else {
+    return false;
+  };
+  if hour > 23 || minute > 59 || second > 60 {
+    return false;
+  }
+
+  let rest = match rest {
+    [b'.', rest @ ..] => {
+      let count = rest.iter().take_while(|byte| byte.is_ascii_digit()).count();
+      if count == 0 {
+        return false;
+      }
+      &rest[count..]
+    }
+    rest => rest,
+  };
+
+  let offset = match *rest {
+    [b'Z' | b'z'] => 0,
+    [sign @ (b'+' | b'-'), h1, h2, b':', m1, m2] => {
+      let (Some(hour), Some(minute)) = (to_number(&[h1, h2]), to_number(&[m1, m2])) else {
+        return false;
+      };
+      if hour > 23 || minute > 59 {
+        return false;
+      }
+      let offset = (hour * 60 + minute) as i64;
+      if sign == b'+' { offset } else { -offset }
+    }
+    _ => return false,
+  };
+
+  // a leap second is always the last second of the day in UTC
+  if second == 60 {
+    let minutes = (hour * 60 + minute) as i64 - offset;
+    return minutes.rem_euclid(24 * 60) == 23 * 60 + 59;
+  }
+
+  true
+}
+
+/// A `duration` like `P4DT12H30M5S`, as in appendix A of RFC 3339.
+pub fn is_duration(value: &str) -> bool {
+  let Some(rest) = value.strip_prefix('P') else {
+    return false;
+  };
+  let (date, time) = match rest.split_once('T') {
+    Some((date, time)) => (date, Some(time)),
+    None => (rest, None),
+  };
+
+  let Some(date_units) = to_duration_units(date) else {
+    return false;
+  };
+  let Some(time_units) = time.map(to_duration_units).unwrap_or(Some(String::new())) else {
+    return false;
+  };
+
+  // weeks can not be combined with anything
+  if date_units == "W" {
+    return time.is_none();
+  }
+
+  // there is at least one element and a time has at least one element
+  if (date_units.is_empty() || time.is_some()) && time_units.is_empty() {
+    return false;
+  }
+
+  // units come in order and may not skip a unit
+  "YMD".contains(date_units.as_str()) && "HMS".contains(time_units.as_str())
+}
+
+/// The units of the elements in `value`, every element is a number and a unit.
+fn to_duration_units(value: &str) -> Option<String> {
+  let mut units = String::new();
+  let mut digits = 0;
+  for character in value.chars() {
+    if character.is_ascii_digit() {
+      digits += 1;
+      continue;
+    }
+    if digits == 0 || !character.is_ascii_uppercase() {
+      return None;
+    }
+    digits = 0;
+    units.push(character);
+  }
+  if digits > 0 {
+    return None;
+  }
+  Some(units)
+}
+
+/// The number in `bytes` if they are all ascii digits.
+fn to_number(bytes: &[u8]) -> Option<u32> {
+  if bytes.is_empty() || !bytes.iter().all(u8::is_ascii_digit) {
+    return None;
+  }
+  bytes.iter().try_fold(0u32, |number, byte| {
+    number.checked_mul(10)?.checked_add((byte - b'0') as u32)
+  })
+}
+
+/// `true` if `value` is one or more ascii digits.
+fn is_digits(value: &str) -> bool {
+  !value.is_empty() && value.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+// email addresses, RFC 5321 and RFC 6531
+
+/// An `email` address like `joe.bloggs@example.com`.
+pub fn is_email(value: &str) -> bool {
+  is_mailbox(value, false)
+}
+
+/// An `idn-email` address, like `email` but with unicode like `실례@실례.테스트`.
+pub fn is_idn_email(value: &str) -> bool {
+  is_mailbox(value, true)
+}
+
+fn is_mailbox(value: &str, international: bool) -> bool {
+  // the local part may be quoted and hold a @, the domain never does
+  let Some((local, domain)) = value.rsplit_once('@') else {
+    return false;
+  };
+
+  let local = if local.starts_with('"') {
+    is_quoted_string(local, international)
+  } else {
+    is_dot_string(local, international)
+  };
+  if !local {
+    return false;
+  }
+
+  if let Some(literal) = domain
+    .strip_prefix('[')
+    .and_then(|domain| domain.strip_suffix(']'))
+  {
+    return is_ipv4(literal) || literal.strip_prefix("IPv6:").is_some_and(is_ipv6);
+  }
+
+  if international {
+    is_idn_hostname(domain)
+  } else {
+    is_hostname(domain)
+  }
+}
+
+fn is_dot_string(value: &str, international: bool) -> bool {
+  value.split('.').all(|atom| {
+    !atom.is_empty()
+      && atom.chars().all(|character| {
+        character.is_ascii_alphanumeric()
+          || "!#$%&'*+-/=?^_`{|}~".contains(character)
+          || (international && !character.is_ascii())
+      })
+  })
+}
+
+fn is_quoted_string(value: &str, international: bool) -> bool {
+  let Some(value) = value
+    .strip_prefix('"')
+    .and_then(|value| value.strip_suffix('"'))
+  else {
+    return false;
+  };
+
+  let mut characters = value.chars();
+  while let Some(character) = characters.next() {
+    let character = if character == '\\' {
+      let Some(character) = characters.next() else {
+        return false;
+      };
+      character
+    } else if character == '"' {
+      return false;
+    } else {
+      character
+    };
+    if !((' '..='~').contains(&character) || (international && !character.is_ascii())) {
+      return false;
+    }
+  }
+
+  true
+}
+
+// host names, RFC 1123 and IDNA2008
+
+/// A `hostname` like `www.example.com`, labels may be punycode.
+pub fn is_hostname(value: &str) -> bool {
+  // a trailing dot is the root of a fully qualified name
+  let value = value.strip_suffix('.').unwrap_or(value);
+  if value.is_empty() || value.len() > 253 {
+    return false;
+  }
+
+  value.split('.').all(is_ascii_label)
+}
+
+/// An `idn-hostname` like `실례.테스트`.
+pub fn is_idn_hostname(value: &str) -> bool {
+  let mut length = 0;
+  let mut labels = value
+    .split(['.', '\u{3002}', '\u{ff0e}', '\u{ff61}'])
+    .peekable();
+  while let Some(label) = labels.next() {
+    // a trailing dot is the root of a fully qualified name
+    if label.is_empty() && labels.peek().is_none() && length > 0 {
+      break;
+    }
+
+    if label.is_ascii() {
+      if !is_ascii_label(label) {
+        return false;
+      }
+      length += label.len() + 1;
+    } else {
+      let characters: Vec<_> = label.chars().collect();
+      if !is_unicode_label(&characters) {
+        return false;
+      }
+      let Some(encoded) = encode_punycode(&characters) else {
+        return false;
+      };
+      if encoded.len() + 4 > 63 {
+        return false;
+      }
+      length += encoded.len() + 5;
+    }
+  }
+
+  length > 0 && length - 1 <= 253
+}
+
+/// A label of ascii characters, the label may be punycode.
+fn is_ascii_label(label: &str) -> bool {
+  if label.is_empty() || label.len() > 63 {
+    return false;
+  }
+  if label.starts_with('-') || label.ends_with('-') {
+    return false;
+  }
+  if !label
+    .bytes()
+    .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-')
+  {
+    return false;
+  }
+
+  // only punycode may have hyphens in the third and fourth position
+  if label.get(2..4) != Some("--") {
+    return true;
+  }
+  if !label[..2].eq_ignore_ascii_case("xn") {
+    return false;
+  }
+  let Some(characters) = decode_punycode(&label[4..]) else {
+    return false;
+  };
+  !characters.is_ascii() && is_unicode_label(&characters.chars().collect::<Vec<_>>())
+}
+
+/// A label with unicode characters, as in RFC 5891 and RFC 5892. The categories of the
+/// characters are approximated, there are no unicode tables in the standard library.
+fn is_unicode_label(label: &[char]) -> bool {
+  let Some(first) = label.first() else {
+    return false;
+  };
+  if *first == '-' || label.last() == Some(&'-') {
+    return false;
+  }
+  if label.get(2) == Some(&'-') && label.get(3) == Some(&'-') {
+    return false;
+  }
+  if is_combining_mark(*first) {
+    return false;
+  }
+
+  let arabic_indic = label.iter().any(|c| ('\u{0660}'..='\u{0669}').contains(c));
+  let extended_arabic_indic = label.iter().any(|c| ('\u{06f0}'..='\u{06f9}').contains(c));
+  if arabic_indic && extended_arabic_indic {
+    return false;
+  }
+
+  for (index, character) in label.iter().copied().enumerate() {
+    let before = index.checked_sub(1).map(|index| label[index]);
+    let after = label.get(index + 1).copied();
+
+    let valid = match character {
+      'a'..='z' | 'A'..='Z' | '0'..='9' | '-' => true,
+      // MIDDLE DOT, between two l's
+      '\u{00b7}' => before == Some('l') && after == Some('l'),
+      // GREEK LOWER NUMERAL SIGN, before a greek character
+      '\u{0375}' => after.is_some_and(is_greek),
+      // HEBREW PUNCTUATION GERESH and GERSHAYIM, after a hebrew character
+      '\u{05f3}' | '\u{05f4}' => before.is_some_and(is_hebrew),
+      // KATAKANA MIDDLE DOT, in a label with japanese characters
+      '\u{30fb}' => label
+        .iter()
+        .any(|c| *c != '\u{30fb}' && (is_hiragana(*c) || is_katakana(*c) || is_han(*c))),
+      // ZERO WIDTH JOINER, after a virama
+      '\u{200d}' => before.is_some_and(is_virama),
+      // ZERO WIDTH NON-JOINER, after a virama or between joining characters
+      '\u{200c}' => {
+        before.is_some_and(is_virama) || {
+          let before = label[..index]
+            .iter()
+            .rev()
+            .find(|c| !is_combining_mark(**c));
+          let after = label[index + 1..].iter().find(|c| !is_combining_mark(**c));
+          before.is_some_and(|c| is_joining(*c, false))
+            && after.is_some_and(|c| is_joining(*c, true))
+        }
+      }
+      // exceptions that are valid
+      '\u{00df}' | '\u{03c2}' | '\u{06fd}' | '\u{06fe}' | '\u{0f0b}' | '\u{3007}' => true,
+      // exceptions that are not valid
+      '\u{0640}' | '\u{07fa}' | '\u{302e}' | '\u{302f}' | '\u{3031}'..='\u{3035}' | '\u{303b}' => {
+        false
+      }
+      character if character.is_ascii() => false,
+      character => character.is_alphanumeric() || is_combining_mark(character),
+    };
+    if !valid {
+      return false;
+    }
+  }
+
+  true
+}
+
+fn is_greek(character: char) -> bool {
+  matches!(character, '\u{0370}'..='\u{0373}' | '\u{0376}'..='\u{03ff}' | '\u{1f00}'..='\u{1fff}')
+}
+
+fn is_hebrew(character: char) -> bool {
+  matches!(character, '\u{0591}'..='\u{05f4}' | '\u{fb1d}'..='\u{fb4f}')
+}
+
+fn is_hiragana(character: char) -> bool {
+  matches!(character, '\u{3041}'..='\u{309f}' | '\u{1b001}'..='\u{1b11f}')
+}
+
+fn is_katakana(character: char) -> bool {
+  matches!(character, '\u{30a0}'..='\u{30ff}' | '\u{31f0}'..='\u{31ff}' | '\u{ff66}'..='\u{ff9d}')
+}
+
+fn is_han(character: char) -> bool {
+  matches!(
+    character,
+    '\u{2e80}'..='\u{2fdf}'
+      | '\u{3005}'
+      | '\u{3007}'
+      | '\u{3021}'..='\u{3029}'
+      | '\u{3038}'..='\u{303b}'
+      | '\u{3400}'..='\u{4dbf}'
+      | '\u{4e00}'..='\u{9fff}'
+      | '\u{f900}'..='\u{faff}'
+      | '\u{20000}'..='\u{3134f}'
+  )
+}
+
+/// Characters with a canonical combining class of 9.
+fn is_virama(character: char) -> bool {
+  matches!(
+    character,
+    '\u{094d}'
+      | '\u{09cd}'
+      | '\u{0a4d}'
+      | '\u{0acd}'
+      | '\u{0b4d}'
+      | '\u{0bcd}'
+      | '\u{0c4d}'
+      | '\u{0ccd}'
+      | '\u{0d3b}'
+      | '\u{0d3c}'
+      | '\u{0d4d}'
+      | '\u{0dca}'
+      | '\u{0e3a}'
+      | '\u{0eba}'
+      | '\u{0f84}'
+      | '\u{1039}'
+      | '\u{103a}'
+      | '\u{1714}'
+      | '\u{1734}'
+      | '\u{17d2}'
+      | '\u{1a60}'
+      | '\u{1b44}'
+      | '\u{1baa}'
+      | '\u{1bab}'
+      | '\u{1bf2}'
+      | '\u{1bf3}'
+      | '\u{2d7f}'
+      | '\u{a806}'
+      | '\u{a8c4}'
+      | '\u{a953}'
+      | '\u{a9c0}'
+      | '\u{aaf6}'
+      | '\u{abed}'
+  )
+}
+
+/// Marks that combine with the character before them, the common scripts only.
+fn is_combining_mark(character: char) -> bool {
+  matches!(
+    character,
+    '\u{0300}'..='\u{036f}'
+      | '\u{0483}'..='\u{0489}'
+      | '\u{0591}'..='\u{05bd}'
+      | '\u{05bf}'
+      | '\u{05c1}'..='\u{05c2}'
+      | '\u{05c4}'..='\u{05c5}'
+      | '\u{05c7}'
+      | '\u{0610}'..='\u{061a}'
+      | '\u{064b}'..='\u{065f}'
+      | '\u{0670}'
+      | '\u{06d6}'..='\u{06dc}'
+      | '\u{06df}'..='\u{06e4}'
+      | '\u{06e7}'..='\u{06e8}'
+      | '\u{06ea}'..='\u{06ed}'
+      | '\u{0711}'
+      | '\u{0730}'..='\u{074a}'
+      | '\u{07a6}'..='\u{07b0}'
+      | '\u{07eb}'..='\u{07f3}'
+      | '\u{0900}'..='\u{0903}'
+      | '\u{093a}'..='\u{093c}'
+      | '\u{093e}'..='\u{094f}'
+      | '\u{0951}'..='\u{0957}'
+      | '\u{0962}'..='\u{0963}'
+      | '\u{0981}'..='\u{0983}'
+      | '\u{09bc}'
+      | '\u{09be}'..='\u{09cd}'
+      | '\u{09d7}'
+      | '\u{09e2}'..='\u{09e3}'
+      | '\u{0e31}'
+      | '\u{0e34}'..='\u{0e3a}'
+      | '\u{0e47}'..='\u{0e4e}'
+      | '\u{1ab0}'..='\u{1aff}'
+      | '\u{1dc0}'..='\u{1dff}'
+      | '\u{20d0}'..='\u{20ff}'
+      | '\u{302a}'..='\u{302f}'
+      | '\u{3099}'..='\u{309a}'
+      | '\u{fe20}'..='\u{fe2f}'
+  )
+}
+
+/// Characters that join with a character after them (`after` is false) or before them
+/// (`after` is true), the arabic and syriac scripts only.
+fn is_joining(character: char, after: bool) -> bool {
+  let right_joining = matches!(
+    character,
+    '\u{0622}'..='\u{0625}'
+      | '\u{0627}'
+      | '\u{0629}'
+      | '\u{062f}'..='\u{0632}'
+      | '\u{0648}'
+      | '\u{0671}'..='\u{0673}'
+      | '\u{0675}'..='\u{0677}'
+      | '\u{0688}'..='\u{0699}'
+      | '\u{06c0}'
+      | '\u{06c3}'..='\u{06cb}'
+      | '\u{06cd}'
+      | '\u{06cf}'
+      | '\u{06d2}'..='\u{06d3}'
+      | '\u{06d5}'
+      | '\u{06ee}'..='\u{06ef}'
+  );
+  let joining = matches!(
+    character,
+    '\u{0620}'..='\u{064a}'
+      | '\u{066e}'..='\u{066f}'
+      | '\u{0671}'..='\u{06d3}'
+      | '\u{06d5}'
+      | '\u{06ee}'..='\u{06ef}'
+      | '\u{06fa}'..='\u{06fc}'
+      | '\u{06ff}'
+      | '\u{0710}'..='\u{072f}'
+      | '\u{074d}'..='\u{077f}'
+  ) && character != '\u{0640}';
+
+  joining && (after || !right_joining)
+}
+
+const PUNYCODE_BASE: u32 = 36;
+const PUNYCODE_MINIMUM: u32 = 1;
+const PUNYCODE_MAXIMUM: u32 = 26;
+
+/// Decodes punycode as in RFC 3492, without the `xn--` prefix.
+fn decode_punycode(value: &str) -> Option<String> {
+  let (basic, extended) = match value.rfind('-') {
+    Some(index) => (&value[..index], &value[index + 1..]),
+    None => ("", value),
+  };
+  if !basic.is_ascii() {
+    return None;
+  }
+
+  let mut output: Vec<char> = basic.chars().collect();
+  let mut code = 128u32;
+  let mut index = 0u32;
+  let mut bias = 72;
+  let mut digits = extended.bytes().peekable();
+  while digits.peek().is_some() {
+    let previous_index = index;
+    let mut weight = 1u32;
+    let mut k = PUNYCODE_BASE;
+    loop {
+      let digit = match digits.next()? {
+        byte @ b'a'..=b'z' => byte - b'a',
+        byte @ b'A'..=b'Z' => byte - b'A',
+        byte @ b'0'..=b'9' => byte - b'0' + 26,
+        _ => return None,
+      } as u32;
+      index = index.checked_add(digit.checked_mul(weight)?)?;
+      let threshold = punycode_threshold(k, bias);
+      if digit < threshold {
+        break;
+      }
+      weight = weight.checked_mul(PUNYCODE_BASE - threshold)?;
+      k += PUNYCODE_BASE;
+    }
+
+    let length = output.len() as u32 + 1;
+    bias = adapt_punycode_bias(index - previous_index, length, previous_index == 0);
+    code = code.checked_add(index / length)?;
+    index %= length;
+    output.insert(index as usize, char::from_u32(code)?);
+    index += 1;
+  }
+
+  Some(output.into_iter().collect())
+}
+
+/// Encodes a label as punycode as in RFC 3492, without the `xn--` prefix.
+fn encode_punycode(label: &[char]) -> Option<String> {
+  let mut output: String = label.iter().filter(|c| c.is_ascii()).collect();
+  let basic_count = output.len() as u32;
+  if basic_count > 0 {
+    output.push('-');
+  }
+
+  let mut code = 128u32;
+  let mut delta = 0u32;
+  let mut bias = 72;
+  let mut handled_count = basic_count;
+  while (handled_count as usize) < label.len() {
+    let minimum = label
+      .iter()
+      .map(|c| *c as u32)
+      .filter(|c| *c >= code)
+      .min()?;
+    delta = delta.checked_add((minimum - code).checked_mul(handled_count + 1)?)?;
+    code = minimum;
+    for character in label {
+      let character = *character as u32;
+      if character < code {
+        delta = delta.checked_add(1)?;
+      }
+      if character != code {
+        continue;
+      }
+
+      let mut q = delta;
+      let mut k = PUNYCODE_BASE;
+      loop {
+        let threshold = punycode_threshold(k, bias);
+        if q < threshold {
+          break;
+        }
+        let digit = threshold + (q - threshold) % (PUNYCODE_BASE - threshold);
+        output.push(punycode_digit(digit));
+        q = (q - threshold) / (PUNYCODE_BASE - threshold);
+        k += PUNYCODE_BASE;
+      }
+      output.push(punycode_digit(q));
+      bias = adapt_punycode_bias(delta, handled_count + 1, handled_count == basic_count);
+      delta = 0;
+      handled_count += 1;
+    }
+    delta = delta.checked_add(1)?;
+    code += 1;
+  }
+
+  Some(output)
+}
+
+fn punycode_threshold(k: u32, bias: u32) -> u32 {
+  k.saturating_sub(bias)
+    .clamp(PUNYCODE_MINIMUM, PUNYCODE_MAXIMUM)
+}
+
+fn punycode_digit(digit: u32) -> char {
+  if digit < 26 {
+    (b'a' + digit as u8) as char
+  } else {
+    (b'0' + (digit - 26) as u8) as char
+  }
+}
+
+fn adapt_punycode_bias(delta: u32, length: u32, first: bool) -> u32 {
+  let mut delta = if first { delta / 700 } else { delta / 2 };
+  delta += delta / length;
+  let mut k = 0;
+  while delta > ((PUNYCODE_BASE - PUNYCODE_MINIMUM) * PUNYCODE_MAXIMUM) / 2 {
+    delta /= PUNYCODE_BASE - PUNYCODE_MINIMUM;
+    k += PUNYCODE_BASE;
+  }
+  k + ((PUNYCODE_BASE - PUNYCODE_MINIMUM + 1) * delta) / (delta + 38)
+}
+
+// ip addresses, RFC 2673 and RFC 4291
+
+/// An `ipv4` address like `192.168.0.1`, without leading zeros.
+pub fn is_ipv4(value: &str) -> bool {
+  value.parse::<std::net::Ipv4Addr>().is_ok()
+}
+
+/// An `ipv6` address like `::ffff:192.168.0.1`, without a zone.
+pub fn is_ipv6(value: &str) -> bool {
+  value.parse::<std::net::Ipv6Addr>().is_ok()
+}
+
+// resource identifiers, RFC 3986, RFC 3987 and RFC 6570
+
+/// An absolute `uri` like `http://example.com/?query#fragment`.
+pub fn is_uri(value: &str) -> bool {
+  is_resource_identifier(value, false, false)
+}
+
+/// A `uri-reference`, an absolute uri or a relative reference like `../path`.
+pub fn is_uri_reference(value: &str) -> bool {
+  is_resource_identifier(value, false, true)
+}
+
+/// An absolute `iri`, like a uri but with unicode.
+pub fn is_iri(value: &str) -> bool {
+  is_resource_identifier(value, true, false)
+}
+
+/// An `iri-reference`, like a uri reference but with unicode.
+pub fn is_iri_reference(value: &str) -> bool {
+  is_resource_identifier(value, true, true)
+}
+
+fn is_resource_identifier(value: &str, international: bool, relative: bool) -> bool {
+  let (value, fragment) = match value.split_once('#') {
+    Some((value, fragment)) => (value, Some(fragment)),
+    None => (value, None),
+  };
+  let (value, query) = match value.split_once('?') {
+    Some((value, query)) => (value, Some(query)),
+    None => (value, None),
+  };
+
+  let is_query_character = |character: char| {
+    character == '/'
+      || character == '?'
+      || is_path_character(character, international)
+      || (international && is_private_character(character))
+  };
+  if !fragment.is_none_or(|fragment| is_encoded(fragment, |c| is_query_character(c) && c != '#'))
+    || !query.is_none_or(|query| is_encoded(query, is_query_character))
+  {
+    return false;
+  }
+
+  // the scheme ends at the first colon, if there is no slash before it
+  let scheme = value
+    .split_once(':')
+    .map(|(scheme, _)| scheme)
+    .filter(|scheme| !scheme.contains('/'));
+  let value = match scheme {
+    Some(scheme) => {
+      let mut characters = scheme.chars();
+      if !characters.next().is_some_and(|c| c.is_ascii_alphabetic())
+        || !characters.all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+      {
+        return false;
+      }
+      &value[scheme.len() + 1..]
+    }
+    None if relative => value,
+    None => return false,
+  };
+
+  let path = match value.strip_prefix("//") {
+    Some(value) => {
+      let (authority, path) = match value.find('/') {
+        Some(index) => value.split_at(index),
+        None => (value, ""),
+      };
+      if !is_authority(authority, international) {
+        return false;
+      }
+      path
+    }
+    None => value,
+  };
+
+  is_encoded(path, |character| {
+    character == '/' || is_path_character(character, international)
+  })
+}
+
+fn is_authority(value: &str, international: bool) -> bool {
+  let (user, value) = match value.rsplit_once('@') {
+    Some((user, value)) => (Some(user), value),
+    None => (None, value),
+  };
+  if !user.is_none_or(|user| {
+    is_encoded(user, |character| {
+      character == ':'
+        || is_unreserved_character(character, international)
+        || is_sub_delimiter(character)
+    })
+  }) {
+    return false;
+  }
+
+  let (host, port) = if value.starts_with('[') {
+    let Some(index) = value.find(']') else {
+      return false;
+    };
+    let (host, port) = value.split_at(index + 1);
+    if !(port.is_empty() || port.starts_with(':')) {
+      return false;
+    }
+    let literal = &host[1..host.len() - 1];
+    if !(is_ipv6(literal) || is_future_ip_literal(literal)) {
+      return false;
+    }
+    (None, port.strip_prefix(':'))
+  } else {
+    match value.rsplit_once(':') {
+      Some((host, port)) => (Some(host), Some(port)),
+      None => (Some(value), None),
+    }
+  };
+
+  host.is_none_or(|host| {
+    is_encoded(host, |character| {
+      is_unreserved_character(character, international) || is_sub_delimiter(character)
+    })
+  }) && port.is_none_or(|port| port.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+fn is_future_ip_literal(value: &str) -> bool {
+  let Some((version, address)) = value
+    .strip_prefix(['v', 'V'])
+    .and_then(|value| value.split_once('.'))
+  else {
+    return false;
+  };
+  !version.is_empty()
+    && version.bytes().all(|byte| byte.is_ascii_hexdigit())
+    && !address.is_empty()
+    && address.chars().all(|character| {
+      character == ':' || is_unreserved_character(character, false) || is_sub_delimiter(character)
+    })
+}
+
+/// `true` if every character of `value` is a percent encoded octet or is allowed by
+/// `is_allowed`.
+fn is_encoded(value: &str, is_allowed: impl Fn(char) -> bool) -> bool {
+  let mut characters = value.chars();
+  while let Some(character) = characters.next() {
+    if character == '%' {
+      let first = characters.next();
+      let second = characters.next();
+      if !(first.is_some_and(|c| c.is_ascii_hexdigit())
+        && second.is_some_and(|c| c.is_ascii_hexdigit()))
+      {
+        return false;
+      }
+    } else if !is_allowed(character) {
+      return false;
+    }
+  }
+  true
+}
+
+fn is_path_character(character: char, international: bool) -> bool {
+  character == ':'
+    || character == '@'
+    || is_unreserved_character(character, international)
+    || is_sub_delimiter(character)
+}
+
+fn is_unreserved_character(character: char, international: bool) -> bool {
+  character.is_ascii_alphanumeric()
+    || "-._~".contains(character)
+    || (international && is_unicode_character(character))
+}
+
+fn is_sub_delimiter(character: char) -> bool {
+  "!$&'()*+,;=".contains(character)
+}
+
+/// The `ucschar` of RFC 3987.
+fn is_unicode_character(character: char) -> bool {
+  let code = character as u32;
+  matches!(
+    code,
+    0xa0..=0xd7ff | 0xf900..=0xfdcf | 0xfdf0..=0xffef | 0x10000..=0xefffd
+  ) && (code < 0x10000 || code & 0xfffe != 0xfffe)
+}
+
+/// The `iprivate` of RFC 3987.
+fn is_private_character(character: char) -> bool {
+  matches!(
+    character as u32,
+    0xe000..=0xf8ff | 0xf0000..=0xffffd | 0x100000..=0x10fffd
+  )
+}
+
+/// A `uri-template` like `http://example.com/dictionary/{term:1}/{term}`.
+pub fn is_uri_template(value: &str) -> bool {
+  let mut rest = value;
+  while !rest.is_empty() {
+    let index = rest.find(['{', '}']).unwrap_or(rest.len());
+    let literal = &rest[..index];
+    if !is_encoded(literal, |character| {
+      !(character.is_control() || " \"'%<>\\^`{|}".contains(character))
+    }) {
+      return false;
+    }
+    rest = &rest[index..];
+
+    if rest.is_empty() {
+      break;
+    }
+    let Some(index) = rest.find('}').filter(|_| rest.starts_with('{')) else {
+      return false;
+    };
+    if !is_uri_template_expression(&rest[1..index]) {
+      return false;
+    }
+    rest = &rest[index + 1..];
+  }
+
+  true
+}
+
+fn is_uri_template_expression(value: &str) -> bool {
+  let value = value
+    .strip_prefix(|c| "+#./;?&=,!@|".contains(c))
+    .unwrap_or(value);
+
+  value.split(',').all(|variable| {
+    let (name, length) = match variable.split_once(':') {
+      Some((name, length)) => (name, Some(length)),
+      None => (variable.strip_suffix('*').unwrap_or(variable), None),
+    };
+    let length = length
+      .is_none_or(|length| !length.starts_with('0') && length.len() <= 4 && is_digits(length));
+    length
+      && !name.is_empty()
+      && !name.starts_with('.')
+      && !name.ends_with('.')
+      && !name.contains("..")
+      && is_encoded(name, |c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+  })
+}
+
+// other formats
+
+/// A `uuid` like `2eb8aa08-aa98-11ea-b4aa-73b441d16380`, as in RFC 4122.
+pub fn is_uuid(value: &str) -> bool {
+  value.len() == 36
+    && value.bytes().enumerate().all(|(index, byte)| match index {
+      8 | 13 | 18 | 23 => byte == b'-',
+      _ => byte.is_ascii_hexdigit(),
+    })
+}
+
+/// A `json-pointer` like `/foo/bar~0/baz~1/%a`, as in RFC 6901.
+pub fn is_json_pointer(value: &str) -> bool {
+  if value.is_empty() {
+    return true;
+  }
+  let Some(value) = value.strip_prefix('/') else {
+    return false;
+  };
+
+  let mut characters = value.chars();
+  while let Some(character) = characters.next() {
+    if character == '~' && !matches!(characters.next(), Some('0' | '1')) {
+      return false;
+    }
+  }
+  true
+}
+
+/// A `relative-json-pointer` like `1/foo` or `0#`.
+pub fn is_relative_json_pointer(value: &str) -> bool {
+  let count = value.bytes().take_while(u8::is_ascii_digit).count();
+  let (number, rest) = value.split_at(count);
+  if number.is_empty() || (number.len() > 1 && number.starts_with('0')) {
+    return false;
+  }
+
+  rest == "#" || is_json_pointer(rest)
+}
+
+/// A `regex` in the ECMA-262 dialect, like `([abc])+\s+$`. This checks the syntax of the
+/// expression, as in annex B of ECMA-262, without the `u` flag.
+pub fn is_regex(value: &str) -> bool {
+  let characters: Vec<_> = value.chars().collect();
+  let group_names: Vec<String> = characters
+    .windows(3)
+    .enumerate()
+    .filter(|(_, window)| window == &['(', '?', '<'])
+    .filter_map(|(index, _)| {
+      let name: String = characters[index + 3..]
+        .iter()
+        .take_while(|c| **c != '>')
+        .collect();
+      (!name.starts_with(['=', '!'])).then_some(name)
+    })
+    .collect();
+
+  let mut parser = RegexParser {
+    characters: &characters,
+    index: 0,
+    group_names: &group_names,
+  };
+  parser.parse_disjunction() && parser.index == characters.len()
+}
+
+struct RegexParser<'c> {
+  characters: &'c [char],
+  index: usize,
+  group_names: &'c [String],
+}
+
+impl RegexParser<'_> {
+  fn peek(&self) -> Option<char> {
+    self.characters.get(self.index).copied()
+  }
+
+  fn next(&mut self) -> Option<char> {
+    let character = self.peek()?;
+    self.index += 1;
+    Some(character)
+  }
+
+  fn accept(&mut self, expected: &str) -> bool {
+    let expected: Vec<_> = expected.chars().collect();
+    if self.characters[self.index..].starts_with(&expected) {
+      self.index += expected.len();
+      true
+    } else {
+      false
+    }
+  }
+
+  fn parse_disjunction(&mut self) -> bool {
+    loop {
+      if !self.parse_alternative() {
+        return false;
+      }
+      if !self.accept("|") {
+        return true;
+      }
+    }
+  }
+
+  fn parse_alternative(&mut self) -> bool {
+    while let Some(character) = self.peek() {
+      let quantifiable = match character {
+        '|' | ')' => return true,
+        '^' | '$' => {
+          self.index += 1;
+          false
+        }
+        '\\' if matches!(self.characters.get(self.index + 1), Some('b' | 'B')) => {
+          self.index += 2;
+          false
+        }
+        '(' => {
+          let Some(quantifiable) = self.parse_group() else {
+            return false;
+          };
+          quantifiable
+        }
+        '[' => {
+          if !self.parse_class() {
+            return false;
+          }
+          true
+        }
+        '\\' => {
+          if !self.parse_atom_escape() {
+            return false;
+          }
+          true
+        }
+        '*' | '+' | '?' => return false,
+        '{' if self.is_quantifier_braces() => return false,
+        _ => {
+          self.index += 1;
+          true
+        }
+      };
+
+      match self.parse_quantifier() {
+        None => return false,
+        Some(true) if !quantifiable => return false,
+        Some(_) => {}
+      }
+    }
+    true
+  }
+
+  /// Parses a group, returns whether the group can be quantified.
+  fn parse_group(&mut self) -> Option<bool> {
+    self.index += 1;
+    let quantifiable = if self.accept("?:") {
+      true
+    } else if self.accept("?=") || self.accept("?!") {
+      // annex B allows quantified lookaheads
+      true
+    } else if self.accept("?<=") || self.accept("?<!") {
+      false
+    } else if self.accept("?<") {
+      let name = self.parse_group_name()?;
+      if self
+        .group_names
+        .iter()
+        .filter(|other| **other == name)
+        .count()
+        > 1
+      {
+        return None;
+      }
+      true
+    } else if self.peek() == Some('?') {
+      return None;
+    } else {
+      true
+    };
+
+    if !self.parse_disjunction() || !self.accept(")") {
+      return None;
+    }
+    Some(quantifiable)
+  }
+
+  /// Parses the name of a group up to and including the `>`.
+  fn parse_group_name(&mut self) -> Option<String> {
+    let mut name = String::new();
+    loop {
+      match self.next()? {
+        '>' => break,
+        character if character == '$' || character == '_' || character.is_alphabetic() => {
+          name.push(character)
+        }
+        character if character.is_alphanumeric() && !name.is_empty() => name.push(character),
+        _ => return None,
+      }
+    }
+    if name.is_empty() {
+      return None;
+    }
+    Some(name)
+  }
+
+  /// `true` if the characters at the current position are a quantifier like `{1,2}`.
+  fn is_quantifier_braces(&self) -> bool {
+    let rest = &self.characters[self.index..];
+    let Some(end) = rest.iter().position(|c| *c == '}') else {
+      return false;
+    };
+    let inner: String = rest[1..end].iter().collect();
+    let (minimum, maximum) = match inner.split_once(',') {
+      Some((minimum, maximum)) => (minimum, Some(maximum)),
+      None => (inner.as_str(), None),
+    };
+    is_digits(minimum) && maximum.is_none_or(|maximum| maximum.is_empty() || is_digits(maximum))
+  }
+
+  /// Parses a quantifier if there is one, returns whether there was one or `None` if the
+  /// quantifier is not valid.
+  fn parse_quantifier(&mut self) -> Option<bool> {
+    match self.peek() {
+      Some('*' | '+' | '?') => {
+        self.index += 1;
+      }
+      Some('{') if self.is_quantifier_braces() => {
+        let end = self.index
+          + self.characters[self.index..]
+            .iter()
+            .position(|c| *c == '}')
+            .unwrap_or_default();
+        let inner: String = self.characters[self.index + 1..end].iter().collect();
+        self.index = end + 1;
+
+        // the minimum may not be more than the maximum, the numbers are compared as digits
+        // because they may be too big for any integer
+        if let Some((minimum, maximum)) = inner
+          .split_once(',')
+          .filter(|(_, maximum)| !maximum.is_empty())
+        {
+          let minimum = minimum.trim_start_matches('0');
+          let maximum = maximum.trim_start_matches('0');
+          if (minimum.len(), minimum) > (maximum.len(), maximum) {
+            return None;
+          }
+        }
+      }
+      _ => return Some(false),
+    }
+    self.accept("?");
+    Some(true)
+  }
+
+  fn parse_atom_escape(&mut self) -> bool {
+    self.index += 1;
+    match self.next() {
+      None => false,
+      Some('k') if !self.group_names.is_empty() => {
+        if !self.accept("<") {
+          return false;
+        }
+        self
+          .parse_group_name()
+          .is_some_and(|name| self.group_names.contains(&name))
+      }
+      Some(character) => self.parse_character_escape(character),
+    }
+  }
+
+  /// Parses the rest of an escape that starts with `\` and `character`.
+  fn parse_character_escape(&mut self, character: char) -> bool {
+    match character {
+      'c' => {
+        // annex B treats a `\c` without a control letter as characters
+        if self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+          self.index += 1;
+        }
+        true
+      }
+      'x' => self.parse_hex_digits(2),
+      'u' => self.parse_hex_digits(4),
+      _ => true,
+    }
+  }
+
+  /// Parses `count` hex digits if there are that many, annex B allows fewer.
+  fn parse_hex_digits(&mut self, count: usize) -> bool {
+    let rest = &self.characters[self.index..];
+    if rest.len() >= count && rest[..count].iter().all(|c| c.is_ascii_hexdigit()) {
+      self.index += count;
+    }
+    true
+  }
+
+  fn parse_class(&mut self) -> bool {
+    self.index += 1;
+    self.accept("^");
+
+    let mut previous: Option<Option<char>> = None;
+    let mut range = false;
+    loop {
+      let Some(character) = self.next() else {
+        return false;
+      };
+      // the value of the atom, or `None` for a class like `\d`
+      let value = match character {
+        ']' => return true,
+        '\\' => {
+          let Some(character) = self.next() else {
+            return false;
+          };
+          match character {
+            'd' | 'D' | 's' | 'S' | 'w' | 'W' => None,
+            'b' => Some('\u{8}'),
+            'x' | 'u' => {
+              let count = if character == 'x' { 2 } else { 4 };
+              let start = self.index;
+              self.parse_hex_digits(count);
+              let digits: String = self.characters[start..self.index].iter().collect();
+              u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(char::from_u32)
+                .or(Some(character))
+            }
+            character => {
+              self.parse_character_escape(character);
+              Some(character)
+            }
+          }
+        }
+        '-' if previous.is_some() && !range && self.peek() != Some(']') => {
+          range = true;
+          continue;
+        }
+        character => Some(character),
+      };
+
+      if range {
+        let start = previous.take().flatten();
+        range = false;
+        // annex B allows classes in a range, the range is then characters
+        if start.zip(value).is_some_and(|(start, end)| start > end) {
+          return false;
+        }
+      } else {
+        previous = Some(value);
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn assert_format(format: &str, valid: &[&str], invalid: &[&str]) {
+    for value in valid {
+      assert!(is_valid_format(format, value), "{} {:?}", format, value);
+    }
+    for value in invalid {
+      assert!(!is_valid_format(format, value), "{} {:?}", format, value);
+    }
+  }
+
+  #[test]
+  fn test_unknown_format() {
+    assert!(is_known_format("date-time"));
+    assert!(!is_known_format("int32"));
+    assert!(is_valid_format("int32", "anything"));
+  }
+
+  #[test]
+  fn test_date_time() {
+    assert_format(
+      "date-time",
+      &[
+        "1963-06-19T08:30:06.283185Z",
+        "1963-06-19T08:30:06Z",
+        "1937-01-01T12:00:27.87+00:20",
+        "1990-12-31T15:59:50.123-08:00",
+        "1998-12-31T23:59:60Z",
+        "1998-12-31T15:59:60.123-08:00",
+        "1963-06-19t08:30:06.283185z",
+      ],
+      &[
+        "1998-12-31T23:59:61Z",
+        "1998-12-31T23:58:60Z",
+        "1998-12-31T22:59:60Z",
+        "1990-02-31T15:59:59.123-08:00",
+        "1990-12-31T15:59:59-24:00",
+        "1963-06-19T08:30:06.28123+01:00Z",
+        "06/19/1963 08:30:06 PST",
+        "2013-350T01:01:01",
+        "1963-6-19T08:30:06.283185Z",
+        "1963-06-1T08:30:06.283185Z",
+        "1963-06-1৪T00:00:00Z",
+        "1963-06-11T0৪:00:00Z",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_date() {
+    assert_format(
+      "date",
+      &[
+        "1963-06-19",
+        "2020-01-31",
+        "2021-02-28",
+        "2020-02-29",
+        "2020-03-31",
+        "2020-04-30",
+        "2020-12-31",
+      ],
+      &[
+        "2020-01-32",
+        "2021-02-29",
+        "2020-02-30",
+        "2020-04-31",
+        "2020-13-01",
+        "06/19/1963",
+        "2013-350",
+        "1998-1-20",
+        "1998-01-1",
+        "1963-06-1৪",
+        "20230328",
+        "2023-W01",
+        "2023-W13-2",
+        "2022W527",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_time() {
+    assert_format(
+      "time",
+      &[
+        "08:30:06Z",
+        "23:59:60Z",
+        "23:59:60+00:00",
+        "01:29:60+01:30",
+        "23:29:60+23:30",
+        "15:59:60-08:00",
+        "00:29:60-23:30",
+        "23:20:50.52Z",
+        "08:30:06.283185Z",
+        "08:30:06+00:20",
+        "08:30:06-08:00",
+        "08:30:06z",
+      ],
+      &[
+        "22:59:60Z",
+        "23:58:60Z",
+        "22:59:60+00:00",
+        "23:58:60+00:00",
+        "23:59:60+01:00",
+        "23:59:60+00:30",
+        "23:59:60-01:00",
+        "23:59:60-00:30",
+        "24:00:00Z",
+        "00:60:00Z",
+        "00:00:61Z",
+        "01:02:03+24:00",
+        "01:02:03+00:60",
+        "01:02:03Z+00:30",
+        "08:30:06 PST",
+        "01:01:01,1111",
+        "12:00:00",
+        "12:00:00.52",
+        "1২:00:00Z",
+        "08:30:06#00:20",
+        "ab:cd:ef",
+        "08:30:06.Z",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_duration() {
+    assert_format(
+      "duration",
+      &[
+        "P4DT12H30M5S",
+        "P4Y",
+        "PT0S",
+        "P0D",
+        "P1M",
+        "PT1M",
+        "PT36H",
+        "P1DT12H",
+        "P2W",
+        "P1Y2M3DT4H5M6S",
+      ],
+      &[
+        "PT1D",
+        "4DT12H30M5S",
+        "P",
+        "P1YT",
+        "PT",
+        "P2D1Y",
+        "P1D2H",
+        "P2S",
+        "P1Y2W",
+        "P২Y",
+        "P1",
+        "P1Y1D",
+        "PT1H1S",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_email() {
+    assert_format(
+      "email",
+      &[
+        "joe.bloggs@example.com",
+        "te~st@example.com",
+        "~test@example.com",
+        "test~@example.com",
+        "\"joe bloggs\"@example.com",
+        "\"joe..bloggs\"@example.com",
+        "\"joe@bloggs\"@example.com",
+        "joe.bloggs@[127.0.0.1]",
+        "joe.bloggs@[IPv6:::1]",
+        "te.s.t@example.com",
+      ],
+      &[
+        "2962",
+        ".test@example.com",
+        "test.@example.com",
+        "te..st@example.com",
+        "joe.bloggs@invalid=domain.com",
+        "joe.bloggs@[127.0.0.300]",
+        "실례@실례.테스트",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_idn_email() {
+    assert_format(
+      "idn-email",
+      &["실례@실례.테스트", "joe.bloggs@example.com"],
+      &["2962", "te..st@example.com"],
+    );
+  }
+
+  #[test]
+  fn test_hostname() {
+    assert_format(
+      "hostname",
+      &[
+        "www.example.com",
+        "xn--4gbwdl.xn--wgbh1c",
+        "hostname",
+        "host-name",
+        "h0stn4me",
+        "1host",
+        "hostnam3",
+        "example.com.",
+        "abcdefghijklmnopqrstuvwxyzabcdefghijklmnopqrstuvwxyzabcdefghijk.com",
+      ],
+      &[
+        "-a-host-name-that-starts-with--",
+        "not_a_valid_host_name",
+        "a-vvvvvvvvvvvvvvvveeeeeeeeeeeeeeeerrrrrrrrrrrrrrrryyyyyyyyyyyyyyyy-long-host-name-component",
+        "-hostname",
+        "hostname-",
+        "_hostname",
+        "hostname_",
+        "host_name",
+        "",
+        ".",
+        ".example",
+        "xn--X",
+        "XN--aa---o47jg78q",
+        "실례.테스트",
+        &["a"; 128].join("."),
+      ],
+    );
+  }
+
+  #[test]
+  fn test_idn_hostname() {
+    assert_format(
+      "idn-hostname",
+      &[
+        "실례.테스트",
+        "xn--ihqwcrb4cv8a8dqg056pqjye",
+        "l\u{00b7}l",
+        "\u{03b1}\u{0375}\u{03b2}",
+        "\u{05d0}\u{05f3}\u{05d1}",
+        "\u{05d0}\u{05f4}\u{05d1}",
+        "\u{30fb}\u{3041}",
+        "\u{30fb}\u{30a1}",
+        "\u{30fb}\u{4e08}",
+        "\u{0628}\u{0660}\u{0628}",
+        "\u{06f0}\u{06f0}",
+        "\u{0915}\u{094d}\u{200d}\u{0937}",
+        "\u{0915}\u{094d}\u{200c}\u{0937}",
+        "\u{0628}\u{064a}\u{200c}\u{0628}\u{064a}",
+        "\u{00df}\u{03c2}\u{0f0b}\u{3007}",
+        "\u{06fd}\u{06fe}",
+        "hostname",
+        "a.b",
+        "a\u{3002}b",
+        "a\u{ff0e}b",
+        "a\u{ff61}b",
+      ],
+      &[
+        "\u{302e}실례.테스트",
+        "실\u{302e}례.테스트",
+        "실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실실례례테스트례례례례례례례례례례례례례례례례례테스트례례례례례례례례례례례례례례례례례례례테스트례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례례.테스트",
+        "-> $1.00 <-",
+        "xn--X",
+        "XN--aa---o47jg78q",
+        "-hello",
+        "hello-",
+        "-hello-",
+        "\u{0903}hello",
+        "\u{0300}hello",
+        "\u{0488}hello",
+        "\u{0640}\u{07fa}",
+        "\u{3031}\u{3032}\u{3033}\u{3034}\u{3035}\u{302e}\u{302f}\u{303b}",
+        "a\u{00b7}l",
+        "\u{00b7}l",
+        "l\u{00b7}a",
+        "l\u{00b7}",
+        "\u{03b1}\u{0375}S",
+        "\u{03b1}\u{0375}",
+        "A\u{05f3}\u{05d1}",
+        "\u{05f3}\u{05d1}",
+        "A\u{05f4}\u{05d1}",
+        "def\u{30fb}abc",
+        "\u{30fb}",
+        "\u{0628}\u{0660}\u{06f0}",
+        "\u{0915}\u{200d}\u{0937}",
+        "\u{200d}\u{0937}",
+        "\u{0915}\u{200c}\u{0937}",
+        "",
+        ".",
+        "\u{3002}",
+        "\u{ff0e}",
+        "\u{ff61}",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_punycode() {
+    let label: Vec<_> = "他们为什么不说中文".chars().collect();
+    assert_eq!(
+      encode_punycode(&label).as_deref(),
+      Some("ihqwcrb4cv8a8dqg056pqjye")
+    );
+    assert_eq!(
+      decode_punycode("ihqwcrb4cv8a8dqg056pqjye").as_deref(),
+      Some("他们为什么不说中文")
+    );
+    assert_eq!(decode_punycode("X"), None);
+  }
+
+  #[test]
+  fn test_ipv4() {
+    assert_format(
+      "ipv4",
+      &["192.168.0.1", "87.10.0.1"],
+      &[
+        "127.0.0.0.1",
+        "256.256.256.256",
+        "127.0",
+        "0x7f000001",
+        "2130706433",
+        "087.10.0.1",
+        "1২7.0.0.1",
+        "192.168.1.0/24",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_ipv6() {
+    assert_format(
+      "ipv6",
+      &[
+        "::1",
+        "::abef",
+        "::",
+        "1:d6::42",
+        "::42:ff:1",
+        "::ffff:192.168.0.1",
+        "1:2:3:4:5:6:7:8",
+        "1000:1000:1000:1000:1000:1000:255.255.255.255",
+      ],
+      &[
+        "12345::",
+        "1:1:1:1:1:1:1:1:1:1:1:1:1:1:1:1",
+        "::laptop",
+        ":2:3:4:5:6:7:8",
+        "1:2:3:4:5:6:7:",
+        ":2:3:4::8",
+        "1::d6::42",
+        "1::2:192.168.256.1",
+        "1::2:192.168.ff.1",
+        "1:2:3:4:5:::8",
+        "1:2:3:4:5:6:7",
+        "1",
+        "127.0.0.1",
+        "1:2:3:4:1.2.3",
+        "  ::1",
+        "::1  ",
+        "fe80::/64",
+        "fe80::a%eth1",
+        "100:100:100:100:100:100:255.255.255.255.255",
+        "100:100:100:100:100:100:100:255.255.255.255",
+        "1:2:3:4:5:6:7:৪",
+        "1:2::192.16৪.0.1",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_uri() {
+    assert_format(
+      "uri",
+      &[
+        "http://foo.bar/?baz=qux#quux",
+        "http://foo.com/blah_(wikipedia)_blah#cite-1",
+        "http://foo.bar/?q=Test%20URL-encoded%20stuff",
+        "http://xn--nw2a.xn--j6w193g/",
+        "http://-.~_!$&'()*+,;=:%40:80%2f::::::@example.com",
+        "http://223.255.255.254",
+        "ftp://ftp.is.co.za/rfc/rfc1808.txt",
+        "http://www.ietf.org/rfc/rfc2396.txt",
+        "ldap://[2001:db8::7]/c=GB?objectClass?one",
+        "mailto:John.Doe@example.com",
+        "news:comp.infosystems.www.servers.unix",
+        "tel:+1-816-555-1212",
+        "urn:oasis:names:specification:docbook:dtd:xml:4.1.2",
+      ],
+      &[
+        "//foo.bar/?baz=qux#quux",
+        "/abc",
+        "\\\\WINDOWS\\fileshare",
+        "abc",
+        "http:// shouldfail.com",
+        ":// should fail",
+        "bar,baz:foo",
+        "http://foo.com/unicode_(✪)_in_parens",
+        "https://[@example.org/test.txt",
+        "https://example.org:80a/",
+        "http://[2001:db8::7",
+        "http://foo.bar/?baz=qux#qu#ux",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_uri_reference() {
+    assert_format(
+      "uri-reference",
+      &[
+        "http://foo.bar/?baz=qux#quux",
+        "//foo.bar/?baz=qux#quux",
+        "/abc",
+        "abc",
+        "#fragment",
+        "",
+        "../a/b?c",
+      ],
+      &["\\\\WINDOWS\\fileshare", "#frag\\ment", "/âππ"],
+    );
+  }
+
+  #[test]
+  fn test_iri() {
+    assert_format(
+      "iri",
+      &[
+        "http://ƒøø.ßår/?∂éœ=πîx#πîüx",
+        "http://ƒøø.com/blah_(wîkïpédiå)_blah#ßité-1",
+        "http://ƒøø.ßår/?q=Test%20URL-encoded%20stuff",
+        "http://-.~_!$&'()*+,;=:%40:80%2f::::::@example.com",
+        "http://[2001:0db8:85a3:0000:0000:8a2e:0370:7334]",
+      ],
+      &[
+        "http://2001:0db8:85a3:0000:0000:8a2e:0370:7334",
+        "/abc",
+        "\\\\WINDOWS\\filëßåré",
+        "âππ",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_iri_reference() {
+    assert_format(
+      "iri-reference",
+      &[
+        "http://ƒøø.ßår/?∂éœ=πîx#πîüx",
+        "//ƒøø.ßår/?∂éœ=πîx#πîüx",
+        "/âππ",
+        "âππ",
+        "#ƒrägmênt",
+      ],
+      &["\\\\WINDOWS\\filëßåré", "#ƒräg\\mênt"],
+    );
+  }
+
+  #[test]
+  fn test_uri_template() {
+    assert_format(
+      "uri-template",
+      &[
+        "http://example.com/dictionary/{term:1}/{term}",
+        "http://example.com/dictionary",
+        "dictionary/{term:1}/{term}",
+        "{+path}/here{?x,y*}",
+      ],
+      &[
+        "http://example.com/dictionary/{term:1}/{term",
+        "http://example.com/dictionary/term}",
+        "{term:0}",
+        "{}",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_uuid() {
+    assert_format(
+      "uuid",
+      &[
+        "2EB8AA08-AA98-11EA-B4AA-73B441D16380",
+        "2eb8aa08-aa98-11ea-b4aa-73b441d16380",
+        "2eb8aa08-AA98-11ea-B4Aa-73B441D16380",
+        "00000000-0000-0000-0000-000000000000",
+        "98d80576-482e-427f-8434-7f86890ab222",
+        "99c17cbb-656f-564a-940f-1a4568f03487",
+        "99c17cbb-656f-664a-940f-1a4568f03487",
+        "99c17cbb-656f-f64a-940f-1a4568f03487",
+      ],
+      &[
+        "2eb8aa08-aa98-11ea-b4aa-73b441d1638",
+        "2eb8aa08-aa98-11ea-73b441d16380",
+        "2eb8aa08-aa98-11ea-b4ga-73b441d16380",
+        "2eb8aa08aa9811eab4aa73b441d16380",
+        "2eb8aa08aa98-11ea-b4aa73b441d16380",
+        "2eb8-aa08-aa98-11ea-b4aa73b44-1d16380",
+        "2eb8aa08aa9811eab4aa73b441d16380----",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_json_pointer() {
+    assert_format(
+      "json-pointer",
+      &[
+        "/foo/bar~0/baz~1/%a",
+        "/foo//bar",
+        "/foo/bar/",
+        "",
+        "/foo",
+        "/foo/0",
+        "/",
+        "/a~1b",
+        "/c%d",
+        "/e^f",
+        "/g|h",
+        "/i\\j",
+        "/k\"l",
+        "/ ",
+        "/m~0n",
+        "/foo/-",
+        "/foo/-/bar",
+        "/~1~0~0~1~1",
+      ],
+      &[
+        "/foo/bar~",
+        "#",
+        "#/",
+        "#a",
+        "/~0~",
+        "/~0/~",
+        "/~2",
+        "/~-1",
+        "/~~",
+        "a",
+        "0",
+        "a/a",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_relative_json_pointer() {
+    assert_format(
+      "relative-json-pointer",
+      &["1", "0/foo/bar", "2/0/baz/1/zip", "0#", "120/foo/bar"],
+      &[
+        "/foo/bar",
+        "-1/foo/bar",
+        "+1/foo/bar",
+        "0##",
+        "01/a",
+        "01#",
+        "",
+      ],
+    );
+  }
+
+  #[test]
+  fn test_regex() {
+    assert_format(
+      "regex",
+      &[
+        "([abc])+\\s+$",
+        "^[a-z0-9]+$",
+        "^(?:\\d{3}-)?\\d{4}$",
+        "(?<year>\\d{4})-\\k<year>",
+        "(?=a)(?!b)(?<=c)(?<!d)",
+        "a{,2}",
+        "a]",
+        "[\\d-z]",
+        "\\u00e9\\x41\\cA",
+        "a+?b*?c??d{1,2}?",
+        "",
+      ],
+      &[
+        "^(abc]",
+        "(abc",
+        "abc)",
+        "[abc",
+        "*a",
+        "a**",
+        "a{2,1}",
+        "[z-a]",
+        "\\",
+        "(?<=a)+",
+        "(?x)",
+        "(?<1a>b)",
+        "(?<a>b)(?<a>c)",
+        "(?<a>b)\\k<b>",
+        "^*",
+      ],
+    );
+  }
+}
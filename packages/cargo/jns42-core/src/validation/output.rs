@@ -0,0 +1,90 @@
+/// The output formats from the JSON Schema specification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+  /// Only whether the instance is valid
+  Flag,
+  /// A flat list of errors
+  #[default]
+  Basic,
+  /// The errors as a tree that follows the structure of the schema
+  Detailed,
+}
+
+/// A single unit of output, the root unit is the result of the validation.
+#[derive(Clone, Debug, PartialEq, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputUnit {
+  pub valid: bool,
+  /// JSON pointer to the keyword, following references
+  pub keyword_location: String,
+  /// Location of the keyword in the document that it is defined in
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub absolute_keyword_location: Option<String>,
+  /// JSON pointer to the value in the instance
+  pub instance_location: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub error: Option<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub errors: Vec<OutputUnit>,
+}
+
+impl OutputUnit {
+  /// Turns the full tree of errors into the requested format.
+  pub(super) fn into_format(self, format: OutputFormat) -> Self {
+    match format {
+      OutputFormat::Flag => Self {
+        valid: self.valid,
+        ..Default::default()
+      },
+      OutputFormat::Basic => {
+        let mut errors = Vec::new();
+        self
+          .errors
+          .into_iter()
+          .for_each(|unit| unit.flatten(&mut errors));
+        Self { errors, ..self }
+      }
+      OutputFormat::Detailed => Self {
+        errors: self.errors.into_iter().map(Self::condense).collect(),
+        ..self
+      },
+    }
+  }
+
+  /// Collect every unit that has an error, without nested units.
+  fn flatten(self, result: &mut Vec<Self>) {
+    let Self {
+      valid,
+      keyword_location,
+      absolute_keyword_location,
+      instance_location,
+      error,
+      errors,
+    } = self;
+
+    if error.is_some() {
+      result.push(Self {
+        valid,
+        keyword_location,
+        absolute_keyword_location,
+        instance_location,
+        error,
+        errors: Vec::new(),
+      });
+    }
+
+    for unit in errors {
+      unit.flatten(result);
+    }
+  }
+
+  /// Units without an error message and with only one nested unit are replaced by that unit.
+  fn condense(self) -> Self {
+    let mut errors: Vec<_> = self.errors.into_iter().map(Self::condense).collect();
+    if self.error.is_none() && errors.len() == 1 {
+      return errors.pop().unwrap();
+    }
+
+    Self { errors, ..self }
+  }
+}
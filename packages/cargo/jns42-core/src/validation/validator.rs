@@ -0,0 +1,1309 @@
+use super::{OutputFormat, OutputUnit, is_valid_format};
+use crate::{
+  documents::DocumentContext,
+  error::Error,
+  models::{ArenaSchemaItem, SchemaArena, SchemaType},
+  utilities::{Decimal, EcmaPattern, NodeLocation},
+};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  iter,
+  rc::Rc,
+};
+
+/// Validates JSON values against schemas that are loaded at runtime, no code generation
+/// needed. The validator works on the schemas as they were loaded, without any transforms.
+/// References, including dynamic references, are resolved when the documents are loaded.
+pub struct Validator {
+  arena: SchemaArena,
+  /// maps the identity location of every schema to it's key in the arena
+  keys: BTreeMap<NodeLocation, usize>,
+  /// compiled regular expressions, indexed by their source
+  patterns: BTreeMap<String, EcmaPattern>,
+  /// `format` is an annotation unless this is set
+  format_assertion: bool,
+}
+
+impl Validator {
+  pub fn new(arena: SchemaArena) -> Result<Self, Error> {
+    let mut keys = BTreeMap::new();
+    let mut patterns = BTreeMap::new();
+
+    for (key, item) in arena.iter().enumerate() {
+      if let Some(location) = &item.location {
+        keys.insert(location.clone(), key);
+      }
+
+      for pattern in iter::empty().chain(item.value_pattern.iter()).chain(
+        item
+          .pattern_properties
+          .iter()
+          .flat_map(|value| value.keys()),
+      ) {
+        if patterns.contains_key(pattern) {
+          continue;
+        }
+
+        let regex = EcmaPattern::new(pattern).map_err(|error| match &item.location {
+          Some(location) => {
+            Error::InvalidPattern(format!("{} in {}: {}", pattern, location, error))
+          }
+          None => Error::InvalidPattern(format!("{}: {}", pattern, error)),
+        })?;
+        patterns.insert(pattern.clone(), regex);
+      }
+    }
+
+    Ok(Self {
+      arena,
+      keys,
+      patterns,
+      format_assertion: false,
+    })
+  }
+
+  /// Assert `format`, like the format assertion vocabulary does. Without this `format` is
+  /// only an annotation, like it is in the format annotation vocabulary.
+  pub fn with_format_assertion(mut self) -> Self {
+    self.format_assertion = true;
+    self
+  }
+
+  pub fn from_document_context(document_context: &Rc<DocumentContext>) -> Result<Self, Error> {
+    Self::new(SchemaArena::from_document_context(document_context)?)
+  }
+
+  /// Validates the `instance` against the schema at `location`, the location is the
+  /// identity location of the schema.
+  pub fn validate(
+    &self,
+    location: &NodeLocation,
+    instance: &serde_json::Value,
+    format: OutputFormat,
+  ) -> Result<OutputUnit, Error> {
+    let key = *self
+      .keys
+      .get(location)
+      .ok_or_else(|| Error::IdentityLocationNotFound(location.clone()))?;
+
+    let mut evaluation = Evaluation {
+      validator: self,
+      stack: Vec::new(),
+    };
+    let errors = evaluation.evaluate_schema(key, instance, &[], &[]);
+
+    let unit = OutputUnit {
+      valid: errors.is_empty(),
+      keyword_location: String::new(),
+      absolute_keyword_location: None,
+      instance_location: String::new(),
+      error: None,
+      errors,
+    };
+
+    Ok(unit.into_format(format))
+  }
+
+  pub fn is_valid(
+    &self,
+    location: &NodeLocation,
+    instance: &serde_json::Value,
+  ) -> Result<bool, Error> {
+    Ok(self.validate(location, instance, OutputFormat::Flag)?.valid)
+  }
+}
+
+/// The state of a single validation.
+struct Evaluation<'v> {
+  validator: &'v Validator,
+  /// schemas that are being evaluated and the instance location they are evaluated for,
+  /// this is how we detect references that loop without ever reaching a value.
+  stack: Vec<(usize, Vec<String>)>,
+}
+
+impl Evaluation<'_> {
+  /// Evaluates a schema, returns the units of the keywords that failed.
+  fn evaluate_schema(
+    &mut self,
+    key: usize,
+    instance: &serde_json::Value,
+    keyword_path: &[String],
+    instance_path: &[String],
+  ) -> Vec<OutputUnit> {
+    self
+      .evaluate_schema_annotated(key, instance, keyword_path, instance_path)
+      .0
+  }
+
+  /// Evaluates a schema, returns the units of the keywords that failed and the properties and
+  /// items of the instance that were evaluated.
+  fn evaluate_schema_annotated(
+    &mut self,
+    key: usize,
+    instance: &serde_json::Value,
+    keyword_path: &[String],
+    instance_path: &[String],
+  ) -> (Vec<OutputUnit>, Evaluated) {
+    let frame = (key, instance_path.to_vec());
+    if self.stack.contains(&frame) {
+      return Default::default();
+    }
+    self.stack.push(frame);
+
+    let validator = self.validator;
+    let item = validator.arena.get_item(key);
+    let mut scope = Scope {
+      item,
+      keyword_path,
+      instance_path,
+      errors: Vec::new(),
+      evaluated: Default::default(),
+    };
+
+    self.evaluate_assertions(&mut scope, instance);
+    self.evaluate_applicators(&mut scope, instance);
+
+    self.stack.pop();
+    (scope.errors, scope.evaluated)
+  }
+
+  /// Evaluates a sub schema against the same instance as the scope. If the sub schema is
+  /// valid the properties and items it evaluated are evaluated by the scope too.
+  fn evaluate_in_place(
+    &mut self,
+    scope: &mut Scope,
+    key: usize,
+    instance: &serde_json::Value,
+    keyword_path: &[String],
+  ) -> Vec<OutputUnit> {
+    let (errors, evaluated) =
+      self.evaluate_schema_annotated(key, instance, keyword_path, scope.instance_path);
+    if errors.is_empty() {
+      scope.evaluated.extend(evaluated);
+    }
+    errors
+  }
+
+  /// The sub schemas of an applicator like `allOf` with their index in the source. The arena
+  /// keeps them ordered by location, where `10` comes before `2`, so the index is taken from
+  /// the location of the sub schema. Sub schemas that have an identifier of their own go last.
+  fn indexed_sub_keys(&self, keyword: &str, keys: &BTreeSet<usize>) -> Vec<(String, usize)> {
+    let mut indexed_keys: Vec<_> = keys
+      .iter()
+      .map(|key| {
+        let item = self.validator.arena.get_item(*key);
+        let index = item
+          .location
+          .as_ref()
+          .and_then(|location| location.get_pointer())
+          .and_then(|pointer| match pointer.as_slice() {
+            [.., parent, index] if parent == keyword => index.parse::<usize>().ok(),
+            _ => None,
+          });
+        (index, *key)
+      })
+      .collect();
+    indexed_keys.sort_by_key(|(index, _)| index.unwrap_or(usize::MAX));
+
+    indexed_keys
+      .into_iter()
+      .enumerate()
+      .map(|(position, (index, key))| (index.unwrap_or(position).to_string(), key))
+      .collect()
+  }
+
+  fn evaluate_assertions(&mut self, scope: &mut Scope, instance: &serde_json::Value) {
+    let item = scope.item;
+
+    if let Some(types) = &item.types {
+      if types.contains(&SchemaType::Never) && types.len() == 1 {
+        scope.fail_schema("no value is valid against this schema".to_owned());
+      } else if !types.iter().any(|r#type| is_type(r#type, instance)) {
+        let types: Vec<_> = types.iter().map(|r#type| r#type.to_string()).collect();
+        scope.fail(
+          "type",
+          format!("the value is not of type {}", types.join(" or ")),
+        );
+      }
+    }
+
+    if item
+      .options
+      .as_ref()
+      .is_some_and(|options| !options.iter().any(|option| json_equals(option, instance)))
+    {
+      scope.fail(
+        "enum",
+        "the value is not one of the allowed values".to_owned(),
+      );
+    }
+
+    // numbers are exact, so 0.3 is a multiple of 0.1
+    if let Some(value) = instance
+      .as_number()
+      .and_then(|value| Decimal::try_from(value).ok())
+    {
+      if let Some(minimum) = item
+        .minimum_inclusive
+        .as_ref()
+        .filter(|minimum| value < **minimum)
+      {
+        scope.fail("minimum", format!("the value is less than {}", minimum));
+      }
+      if let Some(minimum) = item
+        .minimum_exclusive
+        .as_ref()
+        .filter(|minimum| value <= **minimum)
+      {
+        scope.fail(
+          "exclusiveMinimum",
+          format!("the value is less than or equal to {}", minimum),
+        );
+      }
+      if let Some(maximum) = item
+        .maximum_inclusive
+        .as_ref()
+        .filter(|maximum| value > **maximum)
+      {
+        scope.fail("maximum", format!("the value is greater than {}", maximum));
+      }
+      if let Some(maximum) = item
+        .maximum_exclusive
+        .as_ref()
+        .filter(|maximum| value >= **maximum)
+      {
+        scope.fail(
+          "exclusiveMaximum",
+          format!("the value is greater than or equal to {}", maximum),
+        );
+      }
+      if let Some(multiple_of) = item
+        .multiple_of
+        .as_ref()
+        .filter(|multiple_of| !value.is_multiple_of(multiple_of))
+      {
+        scope.fail(
+          "multipleOf",
+          format!("the value is not a multiple of {}", multiple_of),
+        );
+      }
+    }
+
+    if let Some(value) = instance.as_str() {
+      let length = value.chars().count();
+      if let Some(minimum_length) = item
+        .minimum_length
+        .filter(|minimum_length| length < *minimum_length as usize)
+      {
+        scope.fail(
+          "minLength",
+          format!("the value is shorter than {} characters", minimum_length),
+        );
+      }
+      if let Some(maximum_length) = item
+        .maximum_length
+        .filter(|maximum_length| length > *maximum_length as usize)
+      {
+        scope.fail(
+          "maxLength",
+          format!("the value is longer than {} characters", maximum_length),
+        );
+      }
+      if let Some(pattern) = item
+        .value_pattern
+        .as_ref()
+        .filter(|pattern| !self.validator.patterns[*pattern].is_match(value))
+      {
+        scope.fail(
+          "pattern",
+          format!("the value does not match the pattern {}", pattern),
+        );
+      }
+      if let Some(format) = item
+        .value_format
+        .as_ref()
+        .filter(|_format| self.validator.format_assertion)
+        .filter(|format| !is_valid_format(format, value))
+      {
+        scope.fail("format", format!("the value is not a valid {}", format));
+      }
+    }
+
+    if let Some(elements) = instance.as_array() {
+      if let Some(minimum_items) = item
+        .minimum_items
+        .filter(|minimum_items| elements.len() < *minimum_items as usize)
+      {
+        scope.fail(
+          "minItems",
+          format!("the array has less than {} items", minimum_items),
+        );
+      }
+      if let Some(maximum_items) = item
+        .maximum_items
+        .filter(|maximum_items| elements.len() > *maximum_items as usize)
+      {
+        scope.fail(
+          "maxItems",
+          format!("the array has more than {} items", maximum_items),
+        );
+      }
+      if item.unique_items.unwrap_or_default() {
+        let unique = elements.iter().enumerate().all(|(index, element)| {
+          elements[index + 1..]
+            .iter()
+            .all(|other| !json_equals(element, other))
+        });
+        if !unique {
+          scope.fail("uniqueItems", "the array has duplicate items".to_owned());
+        }
+      }
+    }
+
+    if let Some(members) = instance.as_object() {
+      if let Some(minimum_properties) = item
+        .minimum_properties
+        .filter(|minimum_properties| members.len() < *minimum_properties as usize)
+      {
+        scope.fail(
+          "minProperties",
+          format!("the object has less than {} properties", minimum_properties),
+        );
+      }
+      if let Some(maximum_properties) = item
+        .maximum_properties
+        .filter(|maximum_properties| members.len() > *maximum_properties as usize)
+      {
+        scope.fail(
+          "maxProperties",
+          format!("the object has more than {} properties", maximum_properties),
+        );
+      }
+      let missing: Vec<_> = item
+        .required
+        .iter()
+        .flatten()
+        .filter(|name| !members.contains_key(name.as_str()))
+        .map(|name| format!("{:?}", name))
+        .collect();
+      if !missing.is_empty() {
+        scope.fail(
+          "required",
+          format!(
+            "the object is missing the properties {}",
+            missing.join(", ")
+          ),
+        );
+      }
+      for (name, required) in item.dependent_required.iter().flatten() {
+        if !members.contains_key(name) {
+          continue;
+        }
+        let missing: Vec<_> = required
+          .iter()
+          .filter(|name| !members.contains_key(name.as_str()))
+          .map(|name| format!("{:?}", name))
+          .collect();
+        if !missing.is_empty() {
+          scope.fail(
+            "dependentRequired",
+            format!(
+              "the object has the property {:?} but is missing the properties {}",
+              name,
+              missing.join(", ")
+            ),
+          );
+        }
+      }
+    }
+  }
+
+  fn evaluate_applicators(&mut self, scope: &mut Scope, instance: &serde_json::Value) {
+    let item = scope.item;
+
+    if let Some(reference) = item.reference {
+      let keyword = item.reference_keyword.as_deref().unwrap_or("$ref");
+      let keyword_path = scope.keyword_path(&[keyword]);
+      let errors = self.evaluate_in_place(scope, reference, instance, &keyword_path);
+      scope.fail_with(
+        keyword,
+        "the value is not valid against the referenced schema",
+        errors,
+      );
+    }
+
+    if let Some(r#if) = item.r#if {
+      let keyword_path = scope.keyword_path(&["if"]);
+      let valid = self
+        .evaluate_in_place(scope, r#if, instance, &keyword_path)
+        .is_empty();
+      let (keyword, sub_key) = if valid {
+        ("then", item.then)
+      } else {
+        ("else", item.r#else)
+      };
+      if let Some(sub_key) = sub_key {
+        let keyword_path = scope.keyword_path(&[keyword]);
+        let errors = self.evaluate_in_place(scope, sub_key, instance, &keyword_path);
+        scope.fail_with(
+          keyword,
+          &format!("the value is not valid against the {} schema", keyword),
+          errors,
+        );
+      }
+    }
+
+    if let Some(not) = item.not {
+      let keyword_path = scope.keyword_path(&["not"]);
+      let valid = self
+        .evaluate_schema(not, instance, &keyword_path, scope.instance_path)
+        .is_empty();
+      if valid {
+        scope.fail(
+          "not",
+          "the value should not be valid against the schema".to_owned(),
+        );
+      }
+    }
+
+    if let Some(all_of) = &item.all_of {
+      let mut errors = Vec::new();
+      for (index, sub_key) in self.indexed_sub_keys("allOf", all_of) {
+        let keyword_path = scope.keyword_path(&["allOf", &index]);
+        errors.extend(self.evaluate_in_place(scope, sub_key, instance, &keyword_path));
+      }
+      scope.fail_with(
+        "allOf",
+        "the value is not valid against all of the schemas",
+        errors,
+      );
+    }
+
+    if let Some(any_of) = &item.any_of {
+      let mut errors = Vec::new();
+      let mut valid = false;
+      for (index, sub_key) in self.indexed_sub_keys("anyOf", any_of) {
+        let keyword_path = scope.keyword_path(&["anyOf", &index]);
+        let sub_errors = self.evaluate_in_place(scope, sub_key, instance, &keyword_path);
+        valid = valid || sub_errors.is_empty();
+        errors.extend(sub_errors);
+      }
+      if !valid {
+        scope.fail_with(
+          "anyOf",
+          "the value is not valid against any of the schemas",
+          errors,
+        );
+      }
+    }
+
+    if let Some(one_of) = &item.one_of {
+      let mut errors = Vec::new();
+      let mut valid_count = 0;
+      for (index, sub_key) in self.indexed_sub_keys("oneOf", one_of) {
+        let keyword_path = scope.keyword_path(&["oneOf", &index]);
+        let sub_errors = self.evaluate_in_place(scope, sub_key, instance, &keyword_path);
+        if sub_errors.is_empty() {
+          valid_count += 1;
+        }
+        errors.extend(sub_errors);
+      }
+      if valid_count == 0 {
+        scope.fail_with(
+          "oneOf",
+          "the value is not valid against any of the schemas",
+          errors,
+        );
+      } else if valid_count > 1 {
+        scope.fail(
+          "oneOf",
+          format!(
+            "the value is valid against {} of the schemas, it should be valid against one",
+            valid_count
+          ),
+        );
+      }
+    }
+
+    if let Some(elements) = instance.as_array() {
+      self.evaluate_array_applicators(scope, elements);
+    }
+
+    if let Some(members) = instance.as_object() {
+      self.evaluate_object_applicators(scope, members);
+    }
+
+    // these go last, they depend on what all the other applicators evaluated
+    if let Some(elements) = instance.as_array() {
+      self.evaluate_unevaluated_items(scope, elements);
+    }
+
+    if let Some(members) = instance.as_object() {
+      self.evaluate_unevaluated_properties(scope, members);
+    }
+  }
+
+  fn evaluate_array_applicators(&mut self, scope: &mut Scope, elements: &[serde_json::Value]) {
+    let item = scope.item;
+    let tuple_items = item.tuple_items.as_deref().unwrap_or_default();
+
+    let mut errors = Vec::new();
+    for (index, (element, sub_key)) in elements.iter().zip(tuple_items).enumerate() {
+      let keyword_path = scope.keyword_path(&["prefixItems", &index.to_string()]);
+      let instance_path = scope.instance_path(&index.to_string());
+      errors.extend(self.evaluate_schema(*sub_key, element, &keyword_path, &instance_path));
+      scope.evaluated.items.insert(index);
+    }
+    scope.fail_with("prefixItems", "some items are not valid", errors);
+
+    if let Some(array_items) = item.array_items {
+      let mut errors = Vec::new();
+      let keyword_path = scope.keyword_path(&["items"]);
+      for (index, element) in elements.iter().enumerate().skip(tuple_items.len()) {
+        let instance_path = scope.instance_path(&index.to_string());
+        errors.extend(self.evaluate_schema(array_items, element, &keyword_path, &instance_path));
+        scope.evaluated.items.insert(index);
+      }
+      scope.fail_with("items", "some items are not valid", errors);
+    }
+
+    if let Some(contains) = item.contains {
+      let keyword_path = scope.keyword_path(&["contains"]);
+      let mut errors = Vec::new();
+      let mut count = 0;
+      for (index, element) in elements.iter().enumerate() {
+        let instance_path = scope.instance_path(&index.to_string());
+        let sub_errors = self.evaluate_schema(contains, element, &keyword_path, &instance_path);
+        if sub_errors.is_empty() {
+          count += 1;
+          scope.evaluated.items.insert(index);
+        }
+        errors.extend(sub_errors);
+      }
+
+      let minimum_contains = item.minimum_contains.unwrap_or(1) as usize;
+      if count < minimum_contains {
+        // an empty array has no errors for the items, but still fails
+        let (keyword, error) = match item.minimum_contains {
+          Some(minimum_contains) => (
+            "minContains",
+            format!(
+              "the array contains less than {} valid items",
+              minimum_contains
+            ),
+          ),
+          None => (
+            "contains",
+            "the array does not contain a valid item".to_owned(),
+          ),
+        };
+        let unit = scope.unit(Some(keyword), error, errors);
+        scope.errors.push(unit);
+      }
+      if let Some(maximum_contains) = item
+        .maximum_contains
+        .filter(|maximum_contains| count > *maximum_contains as usize)
+      {
+        scope.fail(
+          "maxContains",
+          format!(
+            "the array contains more than {} valid items",
+            maximum_contains
+          ),
+        );
+      }
+    }
+  }
+
+  fn evaluate_object_applicators(
+    &mut self,
+    scope: &mut Scope,
+    members: &serde_json::Map<String, serde_json::Value>,
+  ) {
+    let item = scope.item;
+    let validator = self.validator;
+
+    if let Some(property_names) = item.property_names {
+      let mut errors = Vec::new();
+      let keyword_path = scope.keyword_path(&["propertyNames"]);
+      for name in members.keys() {
+        let instance_path = scope.instance_path(name);
+        let name = serde_json::Value::String(name.clone());
+        errors.extend(self.evaluate_schema(property_names, &name, &keyword_path, &instance_path));
+      }
+      scope.fail_with("propertyNames", "some property names are not valid", errors);
+    }
+
+    let mut errors = Vec::new();
+    for (name, sub_key) in item.object_properties.iter().flatten() {
+      let Some(member) = members.get(name) else {
+        continue;
+      };
+      let keyword_path = scope.keyword_path(&["properties", name]);
+      let instance_path = scope.instance_path(name);
+      errors.extend(self.evaluate_schema(*sub_key, member, &keyword_path, &instance_path));
+      scope.evaluated.properties.insert(name.clone());
+    }
+    scope.fail_with("properties", "some properties are not valid", errors);
+
+    let mut errors = Vec::new();
+    for (pattern, sub_key) in item.pattern_properties.iter().flatten() {
+      let regex = &validator.patterns[pattern];
+      let keyword_path = scope.keyword_path(&["patternProperties", pattern]);
+      for (name, member) in members {
+        if !regex.is_match(name) {
+          continue;
+        }
+        let instance_path = scope.instance_path(name);
+        errors.extend(self.evaluate_schema(*sub_key, member, &keyword_path, &instance_path));
+        scope.evaluated.properties.insert(name.clone());
+      }
+    }
+    scope.fail_with("patternProperties", "some properties are not valid", errors);
+
+    if let Some(map_properties) = item.map_properties {
+      let mut errors = Vec::new();
+      let keyword_path = scope.keyword_path(&["additionalProperties"]);
+      for (name, member) in members {
+        if item
+          .object_properties
+          .as_ref()
+          .is_some_and(|object_properties| object_properties.contains_key(name))
+        {
+          continue;
+        }
+        if item
+          .pattern_properties
+          .iter()
+          .flatten()
+          .any(|(pattern, _)| validator.patterns[pattern].is_match(name))
+        {
+          continue;
+        }
+        let instance_path = scope.instance_path(name);
+        errors.extend(self.evaluate_schema(map_properties, member, &keyword_path, &instance_path));
+        scope.evaluated.properties.insert(name.clone());
+      }
+      scope.fail_with(
+        "additionalProperties",
+        "some additional properties are not valid",
+        errors,
+      );
+    }
+
+    let mut errors = Vec::new();
+    for (name, sub_key) in item.dependent_schemas.iter().flatten() {
+      if !members.contains_key(name) {
+        continue;
+      }
+      let keyword_path = scope.keyword_path(&["dependentSchemas", name]);
+      let instance = serde_json::Value::Object(members.clone());
+      errors.extend(self.evaluate_in_place(scope, *sub_key, &instance, &keyword_path));
+    }
+    scope.fail_with(
+      "dependentSchemas",
+      "the object is not valid against some dependent schemas",
+      errors,
+    );
+  }
+
+  fn evaluate_unevaluated_items(&mut self, scope: &mut Scope, elements: &[serde_json::Value]) {
+    let Some(unevaluated_items) = scope.item.unevaluated_items else {
+      return;
+    };
+
+    let mut errors = Vec::new();
+    let keyword_path = scope.keyword_path(&["unevaluatedItems"]);
+    for (index, element) in elements.iter().enumerate() {
+      if scope.evaluated.items.contains(&index) {
+        continue;
+      }
+      let instance_path = scope.instance_path(&index.to_string());
+      errors.extend(self.evaluate_schema(
+        unevaluated_items,
+        element,
+        &keyword_path,
+        &instance_path,
+      ));
+    }
+    scope.evaluated.items.extend(0..elements.len());
+    scope.fail_with(
+      "unevaluatedItems",
+      "some unevaluated items are not valid",
+      errors,
+    );
+  }
+
+  fn evaluate_unevaluated_properties(
+    &mut self,
+    scope: &mut Scope,
+    members: &serde_json::Map<String, serde_json::Value>,
+  ) {
+    let Some(unevaluated_properties) = scope.item.unevaluated_properties else {
+      return;
+    };
+
+    let mut errors = Vec::new();
+    let keyword_path = scope.keyword_path(&["unevaluatedProperties"]);
+    for (name, member) in members {
+      if scope.evaluated.properties.contains(name) {
+        continue;
+      }
+      let instance_path = scope.instance_path(name);
+      errors.extend(self.evaluate_schema(
+        unevaluated_properties,
+        member,
+        &keyword_path,
+        &instance_path,
+      ));
+    }
+    scope.evaluated.properties.extend(members.keys().cloned());
+    scope.fail_with(
+      "unevaluatedProperties",
+      "some unevaluated properties are not valid",
+      errors,
+    );
+  }
+}
+
+/// The properties and items of an instance that were evaluated by a schema, this is what
+/// `unevaluatedProperties` and `unevaluatedItems` need.
+#[derive(Default)]
+struct Evaluated {
+  properties: BTreeSet<String>,
+  items: BTreeSet<usize>,
+}
+
+impl Evaluated {
+  fn extend(&mut self, other: Self) {
+    self.properties.extend(other.properties);
+    self.items.extend(other.items);
+  }
+}
+
+/// The schema that is being evaluated and the errors that were found.
+struct Scope<'s> {
+  item: &'s ArenaSchemaItem,
+  keyword_path: &'s [String],
+  instance_path: &'s [String],
+  errors: Vec<OutputUnit>,
+  evaluated: Evaluated,
+}
+
+impl Scope<'_> {
+  fn keyword_path(&self, parts: &[&str]) -> Vec<String> {
+    self
+      .keyword_path
+      .iter()
+      .cloned()
+      .chain(parts.iter().map(|part| (*part).to_owned()))
+      .collect()
+  }
+
+  fn instance_path(&self, part: &str) -> Vec<String> {
+    self
+      .instance_path
+      .iter()
+      .cloned()
+      .chain(iter::once(part.to_owned()))
+      .collect()
+  }
+
+  fn unit(&self, keyword: Option<&str>, error: String, errors: Vec<OutputUnit>) -> OutputUnit {
+    let keyword_path: Vec<_> = match keyword {
+      Some(keyword) => self.keyword_path(&[keyword]),
+      None => self.keyword_path.to_vec(),
+    };
+    let absolute_keyword_location = self.item.location.as_ref().map(|location| match keyword {
+      Some(keyword) => location.push_pointer(vec![keyword.to_owned()]),
+      None => location.clone(),
+    });
+
+    OutputUnit {
+      valid: false,
+      keyword_location: to_pointer(&keyword_path),
+      absolute_keyword_location: absolute_keyword_location.map(|location| location.to_string()),
+      instance_location: to_pointer(self.instance_path),
+      error: Some(error),
+      errors,
+    }
+  }
+
+  /// Records a failed assertion of `keyword`.
+  fn fail(&mut self, keyword: &str, error: String) {
+    let unit = self.unit(Some(keyword), error, Vec::new());
+    self.errors.push(unit);
+  }
+
+  /// Records that the schema itself fails, this is the case for the `false` schema.
+  fn fail_schema(&mut self, error: String) {
+    let unit = self.unit(None, error, Vec::new());
+    self.errors.push(unit);
+  }
+
+  /// Records a failed applicator if there are errors in the schemas it applies.
+  fn fail_with(&mut self, keyword: &str, error: &str, errors: Vec<OutputUnit>) {
+    if errors.is_empty() {
+      return;
+    }
+    let unit = self.unit(Some(keyword), error.to_owned(), errors);
+    self.errors.push(unit);
+  }
+}
+
+fn to_pointer(parts: &[String]) -> String {
+  parts
+    .iter()
+    .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+    .collect()
+}
+
+fn is_type(r#type: &SchemaType, instance: &serde_json::Value) -> bool {
+  match r#type {
+    SchemaType::Never => false,
+    SchemaType::Any => true,
+    SchemaType::Null => instance.is_null(),
+    SchemaType::Boolean => instance.is_boolean(),
+    // 1.0 is an integer too
+    SchemaType::Integer => instance
+      .as_number()
+      .and_then(|value| Decimal::try_from(value).ok())
+      .is_some_and(|value| value.is_integer()),
+    SchemaType::Number => instance.is_number(),
+    SchemaType::String => instance.is_string(),
+    SchemaType::Array => instance.is_array(),
+    SchemaType::Object => instance.is_object(),
+  }
+}
+
+/// Json equality where numbers are equal if their value is equal, so 1 equals 1.0
+fn json_equals(left: &serde_json::Value, right: &serde_json::Value) -> bool {
+  match (left, right) {
+    (serde_json::Value::Number(left), serde_json::Value::Number(right)) => {
+      Decimal::try_from(left).ok() == Decimal::try_from(right).ok()
+    }
+    (serde_json::Value::Array(left), serde_json::Value::Array(right)) => {
+      left.len() == right.len()
+        && left
+          .iter()
+          .zip(right)
+          .all(|(left, right)| json_equals(left, right))
+    }
+    (serde_json::Value::Object(left), serde_json::Value::Object(right)) => {
+      left.len() == right.len()
+        && left.iter().all(|(name, left)| {
+          right
+            .get(name)
+            .is_some_and(|right| json_equals(left, right))
+        })
+    }
+    (left, right) => left == right,
+  }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::documents;
+  use serde_json::json;
+
+  async fn load_validator(schema: serde_json::Value) -> (Validator, NodeLocation) {
+    let mut document_context = Rc::new(DocumentContext::default());
+    document_context.register_well_known_factories().unwrap();
+
+    let location: NodeLocation = "/schema.json".parse().unwrap();
+    document_context
+      .load_from_node(
+        location.clone(),
+        location.clone(),
+        None,
+        schema,
+        documents::draft_2020_12::META_SCHEMA_ID,
+      )
+      .await
+      .unwrap();
+
+    let validator = Validator::from_document_context(&document_context).unwrap();
+    (validator, location)
+  }
+
+  #[tokio::test]
+  async fn test_validate_assertions() {
+    let (validator, location) = load_validator(json!({
+      "type": "object",
+      "required": ["a"],
+      "properties": {
+        "a": { "type": "integer", "minimum": 10 },
+        "b": { "type": "string", "pattern": "^x" }
+      },
+      "additionalProperties": false
+    }))
+    .await;
+
+    assert!(
+      validator
+        .is_valid(&location, &json!({ "a": 10, "b": "xyz" }))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!({ "a": 9 })).unwrap());
+    assert!(!validator.is_valid(&location, &json!({ "b": "x" })).unwrap());
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "a": 10, "b": "y" }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "a": 10, "c": 1 }))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!([])).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_references() {
+    let (validator, location) = load_validator(json!({
+      "$defs": {
+        "node": {
+          "type": "object",
+          "properties": {
+            "children": { "type": "array", "items": { "$ref": "#/$defs/node" } }
+          }
+        }
+      },
+      "$ref": "#/$defs/node"
+    }))
+    .await;
+
+    assert!(
+      validator
+        .is_valid(&location, &json!({ "children": [{ "children": [] }] }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "children": [{ "children": [1] }] }))
+        .unwrap()
+    );
+  }
+
+  #[tokio::test]
+  async fn test_validate_one_of() {
+    let (validator, location) = load_validator(json!({
+      "oneOf": [{ "type": "integer" }, { "type": "number" }]
+    }))
+    .await;
+
+    assert!(validator.is_valid(&location, &json!(1.5)).unwrap());
+    assert!(!validator.is_valid(&location, &json!(1)).unwrap());
+    assert!(!validator.is_valid(&location, &json!("1")).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_applicator_index() {
+    let sub_schemas: Vec<_> = (0..12)
+      .map(|index| match index {
+        10 => json!({ "maximum": 3 }),
+        _ => json!({ "minimum": -index }),
+      })
+      .collect();
+    let (validator, location) = load_validator(json!({ "allOf": sub_schemas })).await;
+
+    let basic = validator
+      .validate(&location, &json!(5), OutputFormat::Basic)
+      .unwrap();
+    assert_eq!(
+      basic
+        .errors
+        .iter()
+        .map(|unit| unit.keyword_location.as_str())
+        .collect::<Vec<_>>(),
+      vec!["/allOf", "/allOf/10/maximum"]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_validate_unevaluated() {
+    let (validator, location) = load_validator(json!({
+      "allOf": [
+        { "properties": { "a": { "type": "integer" } } },
+        { "properties": { "b": { "type": "string" } } }
+      ],
+      "properties": {
+        "list": {
+          "prefixItems": [{ "type": "integer" }],
+          "contains": { "type": "string" },
+          "unevaluatedItems": false
+        }
+      },
+      "unevaluatedProperties": false
+    }))
+    .await;
+
+    assert!(
+      validator
+        .is_valid(
+          &location,
+          &json!({ "a": 1, "b": "x", "list": [1, "x", "y"] })
+        )
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "a": 1, "c": 1 }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "list": [1, "x", 2] }))
+        .unwrap()
+    );
+    // properties of a failing schema are not evaluated
+    assert!(!validator.is_valid(&location, &json!({ "a": "x" })).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_dependent() {
+    let (validator, location) = load_validator(json!({
+      "dependentRequired": { "a": ["b"] },
+      "dependentSchemas": { "c": { "required": ["d"] } }
+    }))
+    .await;
+
+    assert!(validator.is_valid(&location, &json!({ "b": 1 })).unwrap());
+    assert!(
+      validator
+        .is_valid(&location, &json!({ "a": 1, "b": 1, "c": 1, "d": 1 }))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!({ "a": 1 })).unwrap());
+    assert!(!validator.is_valid(&location, &json!({ "c": 1 })).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_contains() {
+    let (validator, location) = load_validator(json!({
+      "type": "array",
+      "contains": { "type": "string" },
+      "minContains": 2,
+      "maxContains": 3
+    }))
+    .await;
+
+    assert!(
+      validator
+        .is_valid(&location, &json!(["a", 1, "b"]))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!(["a", 1])).unwrap());
+    assert!(
+      !validator
+        .is_valid(&location, &json!(["a", "b", "c", "d"]))
+        .unwrap()
+    );
+
+    let (validator, location) = load_validator(json!({
+      "contains": { "type": "string" },
+      "minContains": 0
+    }))
+    .await;
+
+    assert!(validator.is_valid(&location, &json!([])).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_format() {
+    let (validator, location) = load_validator(json!({
+      "oneOf": [
+        { "type": "string", "format": "uuid" },
+        { "type": "string", "pattern": "^[a-z0-9]+$", "not": { "format": "uuid" } }
+      ]
+    }))
+    .await;
+    // without format assertion format is an annotation
+    assert!(
+      validator
+        .is_valid(&location, &json!("3e4666bf-d5e5-4aa7-b8ce-cefe41c7568x"))
+        .unwrap()
+    );
+
+    let validator = validator.with_format_assertion();
+
+    assert!(
+      validator
+        .is_valid(&location, &json!("3e4666bf-d5e5-4aa7-b8ce-cefe41c7568a"))
+        .unwrap()
+    );
+    assert!(validator.is_valid(&location, &json!("handle123")).unwrap());
+    assert!(
+      !validator
+        .is_valid(&location, &json!("3e4666bf-d5e5-4aa7-b8ce-cefe41c7568x"))
+        .unwrap()
+    );
+    // formats only apply to strings, unknown formats are annotations
+    let (validator, location) = load_validator(json!({
+      "prefixItems": [{ "format": "date" }, { "format": "int32" }]
+    }))
+    .await;
+    let validator = validator.with_format_assertion();
+    assert!(validator.is_valid(&location, &json!([1, "x"])).unwrap());
+    assert!(!validator.is_valid(&location, &json!(["x"])).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_pattern() {
+    let (validator, location) = load_validator(json!({
+      "type": "object",
+      "properties": {
+        "code": { "type": "string", "pattern": "^(?!000)\\d{3}$" },
+        "double": { "type": "string", "pattern": "^(\\w)\\1$" }
+      },
+      "patternProperties": { "^x-(?=\\w)": { "type": "integer" } },
+      "additionalProperties": false
+    }))
+    .await;
+
+    assert!(
+      validator
+        .is_valid(
+          &location,
+          &json!({ "code": "123", "double": "aa", "x-a": 1 })
+        )
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "code": "000" }))
+        .unwrap()
+    );
+    // \d is ascii only in ECMA-262
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "code": "12৪" }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "double": "ab" }))
+        .unwrap()
+    );
+    assert!(
+      !validator
+        .is_valid(&location, &json!({ "x-a": "a" }))
+        .unwrap()
+    );
+    assert!(!validator.is_valid(&location, &json!({ "x-": 1 })).unwrap());
+  }
+
+  #[tokio::test]
+  async fn test_validate_decimal() {
+    // json! would turn the big integers into floats
+    let (validator, location) = load_validator(
+      serde_json::from_str(
+        r#"{
+          "type": "object",
+          "properties": {
+            "amount": { "type": "number", "multipleOf": 0.01 },
+            "id": { "type": "integer", "maximum": 100000000000000000000 }
+          }
+        }"#,
+      )
+      .unwrap(),
+    )
+    .await;
+
+    let is_valid = |instance: &str| {
+      validator
+        .is_valid(&location, &serde_json::from_str(instance).unwrap())
+        .unwrap()
+    };
+    assert!(is_valid(r#"{ "amount": 19.99 }"#));
+    assert!(is_valid(r#"{ "amount": 0.3 }"#));
+    assert!(!is_valid(r#"{ "amount": 0.001 }"#));
+
+    // without arbitrary precision big integers are floats
+    #[cfg(feature = "arbitrary-precision")]
+    {
+      assert!(is_valid(r#"{ "id": 100000000000000000000 }"#));
+      assert!(!is_valid(r#"{ "id": 100000000000000000001 }"#));
+      assert!(!is_valid(r#"{ "id": 10000000000000000000.5 }"#));
+    }
+  }
+
+  #[tokio::test]
+  async fn test_validate_dynamic_reference_keyword() {
+    let (validator, location) = load_validator(json!({
+      "$dynamicAnchor": "node",
+      "type": "object",
+      "properties": {
+        "child": { "$dynamicRef": "#node" },
+        "value": { "type": "integer" }
+      }
+    }))
+    .await;
+
+    let basic = validator
+      .validate(
+        &location,
+        &json!({ "child": { "value": "x" } }),
+        OutputFormat::Basic,
+      )
+      .unwrap();
+    assert!(!basic.valid);
+    assert_eq!(
+      basic.errors[1].keyword_location,
+      "/properties/child/$dynamicRef"
+    );
+  }
+
+  #[tokio::test]
+  async fn test_validate_output() {
+    let (validator, location) = load_validator(json!({
+      "type": "array",
+      "items": { "type": "integer", "maximum": 3 }
+    }))
+    .await;
+
+    let basic = validator
+      .validate(&location, &json!([1, 5]), OutputFormat::Basic)
+      .unwrap();
+    assert!(!basic.valid);
+    assert_eq!(
+      basic
+        .errors
+        .iter()
+        .map(|unit| (
+          unit.keyword_location.as_str(),
+          unit.instance_location.as_str()
+        ))
+        .collect::<Vec<_>>(),
+      vec![("/items", ""), ("/items/maximum", "/1")]
+    );
+    assert_eq!(
+      basic.errors[1].absolute_keyword_location.as_deref(),
+      Some("/schema.json#/items/maximum")
+    );
+
+    let detailed = validator
+      .validate(&location, &json!([1, 5]), OutputFormat::Detailed)
+      .unwrap();
+    assert_eq!(detailed.errors.len(), 1);
+    assert_eq!(detailed.errors[0].keyword_location, "/items");
+    assert_eq!(
+      detailed.errors[0].errors[0].keyword_location,
+      "/items/maximum"
+    );
+
+    let flag = validator
+      .validate(&location, &json!([1, 2]), OutputFormat::Flag)
+      .unwrap();
+    assert_eq!(
+      flag,
+      OutputUnit {
+        valid: true,
+        ..Default::default()
+      }
+    );
+  }
+}
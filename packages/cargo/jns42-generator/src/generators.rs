@@ -1,10 +1,17 @@
 pub mod cargo_toml;
+pub mod client_rs;
+pub mod client_test_rs;
 pub mod errors_rs;
 pub mod examples_test_rs;
 pub mod file;
+pub mod formats_rs;
 pub mod interiors_rs;
 pub mod lib_rs;
 pub mod main_rs;
+pub mod mocks_rs;
 pub mod mocks_test_rs;
 pub mod package;
+pub mod parsers_rs;
+pub mod server_rs;
+pub mod server_test_rs;
 pub mod types_rs;
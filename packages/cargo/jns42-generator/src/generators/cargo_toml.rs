@@ -1,11 +1,13 @@
+use crate::models::{NumberType, Specification, StringFormat};
 use jns42_core::utilities::banner;
-use std::error::Error;
+use std::{collections::BTreeSet, error::Error};
 
 pub fn generate_file_content(
   package_name: &str,
   package_version: &str,
+  specification: &Specification,
 ) -> Result<String, Box<dyn Error>> {
-  let manifest = toml::toml! {
+  let mut manifest = toml::toml! {
     [package]
     name = package_name
     version = package_version
@@ -35,8 +37,143 @@ pub fn generate_file_content(
 
     [dependencies.serde_json]
     version = "1.0"
+
+    [dependencies.serde_yaml]
+    version = "0.9"
+
+    [dependencies.regex]
+    version = "1.10"
   };
 
+  // strings with base64 encoded content
+  if specification
+    .arena
+    .iter()
+    .any(|item| item.is_base64_content())
+  {
+    let content_manifest = toml::toml! {
+      [dependencies.base64]
+      version = "0.22"
+    };
+    merge_manifest(&mut manifest, content_manifest);
+  }
+
+  // patterns with lookarounds or backreferences
+  if specification.has_regress_patterns() {
+    let pattern_manifest = toml::toml! {
+      [dependencies.regress]
+      version = "0.10"
+    };
+    merge_manifest(&mut manifest, pattern_manifest);
+  }
+
+  // integers that might not fit in 64 bits and exact decimals are parsed without losing
+  // precision
+  if specification.has_wide_integers() || specification.number_type == NumberType::Decimal {
+    let integer_manifest = toml::toml! {
+      [dependencies.serde_json]
+      features = ["arbitrary_precision"]
+      version = "1.0"
+    };
+    merge_manifest(&mut manifest, integer_manifest);
+  }
+
+  if specification.has_big_integers() {
+    let integer_manifest = toml::toml! {
+      [dependencies.num-bigint]
+      version = "0.4"
+
+      [dependencies.num-traits]
+      version = "0.2"
+    };
+    merge_manifest(&mut manifest, integer_manifest);
+  }
+
+  if specification.number_type == NumberType::Decimal {
+    let number_manifest = toml::toml! {
+      [dependencies.bigdecimal]
+      version = "0.4"
+    };
+    merge_manifest(&mut manifest, number_manifest);
+  }
+
+  // strings with a format that has a type, every type is behind a feature
+  let features: BTreeSet<_> = specification
+    .arena
+    .iter()
+    .filter_map(StringFormat::from_item)
+    .map(|format| format.get_feature())
+    .collect();
+  for feature in features {
+    let format_manifest = match feature {
+      "uuid" => toml::toml! {
+        [features]
+        uuid = ["dep:uuid"]
+
+        [dependencies.uuid]
+        features = ["serde"]
+        optional = true
+        version = "1"
+      },
+      "chrono" => toml::toml! {
+        [features]
+        chrono = ["dep:chrono"]
+
+        [dependencies.chrono]
+        default-features = false
+        features = ["std", "serde"]
+        optional = true
+        version = "0.4"
+      },
+      "url" => toml::toml! {
+        [features]
+        url = ["dep:url"]
+
+        [dependencies.url]
+        features = ["serde"]
+        optional = true
+        version = "2"
+      },
+      // the types are in the standard library
+      _ => toml::toml! {
+        [features]
+        ip = []
+      },
+    };
+    merge_manifest(&mut manifest, format_manifest);
+  }
+
+  // the client, the server and the tests for them
+  if !specification.operations.is_empty() {
+    let api_manifest = toml::toml! {
+      [dependencies.reqwest]
+      version = "0.12"
+
+      [dependencies.hyper]
+      features = ["server", "http1"]
+      version = "1"
+
+      [dependencies.http-body-util]
+      version = "0.1"
+
+      [dependencies.form_urlencoded]
+      version = "1"
+
+      [dependencies.percent-encoding]
+      version = "2"
+
+      [dev-dependencies.tokio]
+      features = ["macros", "rt-multi-thread", "net"]
+      version = "1"
+
+      [dev-dependencies.hyper-util]
+      features = ["tokio"]
+      version = "0.1"
+    };
+
+    merge_manifest(&mut manifest, api_manifest);
+  }
+
   let content = toml::ser::to_string_pretty(&manifest)?;
 
   Ok(format!(
@@ -45,3 +182,18 @@ pub fn generate_file_content(
     content
   ))
 }
+
+/// Adds the tables in `other` to the tables in `manifest`
+fn merge_manifest(manifest: &mut toml::Table, other: toml::Table) {
+  for (name, table) in other {
+    let toml::Value::Table(table) = table else {
+      continue;
+    };
+    let entry = manifest
+      .entry(name)
+      .or_insert_with(|| toml::Value::Table(Default::default()));
+    if let toml::Value::Table(entry) = entry {
+      entry.extend(table);
+    }
+  }
+}
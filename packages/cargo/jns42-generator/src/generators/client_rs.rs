@@ -0,0 +1,698 @@
+use super::parsers_rs::get_parser_identifier;
+use crate::models::Specification;
+use jns42_core::models::{ApiBody, ApiHeader, ApiOperation, ApiParameterLocation, ApiResponse};
+use jns42_core::naming::Sentence;
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{TokenStreamExt, format_ident, quote};
+use std::collections::BTreeSet;
+use std::error::Error;
+
+pub fn generate_file_token_stream(
+  specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  tokens.append_all(quote! {
+    /// Errors that can happen while calling an operation
+    #[derive(core::fmt::Debug)]
+    pub enum ClientError {
+      /// The base url can not have path segments
+      Url,
+      Request(reqwest::Error),
+      Serialization(serde_json::Error),
+      /// The response did not validate
+      Validation(crate::errors::ValidationErrors),
+      /// The status of the response is not in the api description
+      UnexpectedStatus(u16),
+      /// A required header of the response is missing
+      MissingHeader(&'static str),
+    }
+
+    impl core::fmt::Display for ClientError {
+      fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+          Self::Url => write!(f, "invalid base url"),
+          Self::Request(error) => write!(f, "request failed: {}", error),
+          Self::Serialization(error) => write!(f, "serialization failed: {}", error),
+          Self::Validation(error) => write!(f, "validation failed: {}", error),
+          Self::UnexpectedStatus(status) => write!(f, "unexpected status {}", status),
+          Self::MissingHeader(name) => write!(f, "missing header {}", name),
+        }
+      }
+    }
+
+    impl std::error::Error for ClientError {}
+
+    impl core::convert::From<reqwest::Error> for ClientError {
+      fn from(value: reqwest::Error) -> Self {
+        Self::Request(value)
+      }
+    }
+
+    impl core::convert::From<serde_json::Error> for ClientError {
+      fn from(value: serde_json::Error) -> Self {
+        Self::Serialization(value)
+      }
+    }
+
+    impl core::convert::From<crate::errors::ValidationErrors> for ClientError {
+      fn from(value: crate::errors::ValidationErrors) -> Self {
+        Self::Validation(value)
+      }
+    }
+  });
+
+  tokens.append_all(quote! {
+    /// Parameters are serialized as their json value, except for strings. Arrays become
+    /// multiple values.
+    pub fn to_parameter_strings(
+      value: &impl serde::Serialize,
+    ) -> core::result::Result<std::vec::Vec<std::string::String>, ClientError> {
+      let value = serde_json::to_value(value)?;
+      let values = match value {
+        serde_json::Value::Array(values) => values.into_iter().map(to_parameter_value_string).collect(),
+        value => vec![to_parameter_value_string(value)],
+      };
+      core::result::Result::Ok(values)
+    }
+
+    /// A single parameter value, array items are separated by a comma.
+    pub fn to_parameter_string(
+      value: &impl serde::Serialize,
+    ) -> core::result::Result<std::string::String, ClientError> {
+      core::result::Result::Ok(to_parameter_strings(value)?.join(","))
+    }
+
+    fn to_parameter_value_string(value: serde_json::Value) -> std::string::String {
+      match value {
+        serde_json::Value::String(value) => value,
+        value => value.to_string(),
+      }
+    }
+
+    /// Read a body as json, or as a string if the body is not json.
+    pub fn from_body(
+      data: &[u8],
+      is_json: bool,
+    ) -> core::result::Result<serde_json::Value, ClientError> {
+      if is_json {
+        core::result::Result::Ok(serde_json::from_slice(data)?)
+      } else {
+        core::result::Result::Ok(serde_json::Value::String(std::string::String::from_utf8_lossy(data).into_owned()))
+      }
+    }
+  });
+
+  let mut operations_tokens = quote! {};
+  for operation in &specification.operations {
+    operations_tokens.append_all(generate_operation_token_stream(specification, operation)?);
+    tokens.append_all(generate_response_token_stream(specification, operation)?);
+    tokens.append_all(generate_from_headers_token_stream(specification, operation));
+  }
+
+  tokens.append_all(quote! {
+    /// Calls the operations of the api, relative to the base url.
+    #[derive(core::clone::Clone, core::fmt::Debug)]
+    pub struct Client {
+      base_url: reqwest::Url,
+      http_client: reqwest::Client,
+    }
+
+    impl Client {
+      pub fn new(base_url: reqwest::Url) -> Self {
+        Self::with_http_client(base_url, reqwest::Client::new())
+      }
+
+      /// Use a configured http client, for instance with default headers for authorization.
+      pub fn with_http_client(base_url: reqwest::Url, http_client: reqwest::Client) -> Self {
+        Self {
+          base_url,
+          http_client,
+        }
+      }
+
+      pub fn base_url(&self) -> &reqwest::Url {
+        &self.base_url
+      }
+
+      #operations_tokens
+    }
+  });
+
+  Ok(tokens)
+}
+
+pub fn get_operation_identifier(operation: &ApiOperation) -> Ident {
+  format_ident!(
+    "r#{}",
+    Sentence::new(&operation.name.join(" ")).to_snake_case()
+  )
+}
+
+pub fn get_response_identifier(operation: &ApiOperation) -> Ident {
+  format_ident!(
+    "r#{}",
+    Sentence::new(&format!("{} response", operation.name.join(" "))).to_pascal_case()
+  )
+}
+
+pub fn get_response_variant_identifier(response: &ApiResponse) -> Ident {
+  match response.get_status_range() {
+    Some(_) => format_ident!("Status{}", response.status.to_uppercase()),
+    None => format_ident!("Default"),
+  }
+}
+
+/// The identifier of the struct with the headers of a response, `None` if the response has
+/// no headers.
+pub fn get_headers_identifier(operation: &ApiOperation, response: &ApiResponse) -> Option<Ident> {
+  if response.headers.is_empty() {
+    return None;
+  }
+
+  Some(format_ident!(
+    "r#{}",
+    Sentence::new(&format!(
+      "{} response {} headers",
+      operation.name.join(" "),
+      response.status
+    ))
+    .to_pascal_case()
+  ))
+}
+
+pub fn get_header_identifier(header: &ApiHeader) -> Ident {
+  format_ident!("r#{}", Sentence::new(&header.name).to_snake_case())
+}
+
+/// Identifiers for the parameters of an operation, in the same order as the parameters.
+pub fn get_parameter_identifiers(operation: &ApiOperation) -> Vec<Ident> {
+  let mut names = BTreeSet::new();
+  names.insert("body".to_owned());
+
+  operation
+    .parameters
+    .iter()
+    .map(|parameter| {
+      let mut name = Sentence::new(&parameter.name).to_snake_case();
+      if !names.insert(name.clone()) {
+        // the same name in another location
+        name = format!("{}_{}", name, get_location_name(parameter.location));
+        names.insert(name.clone());
+      }
+      format_ident!("r#{}", name)
+    })
+    .collect()
+}
+
+pub fn get_location_name(location: ApiParameterLocation) -> &'static str {
+  match location {
+    ApiParameterLocation::Path => "path",
+    ApiParameterLocation::Query => "query",
+    ApiParameterLocation::Header => "header",
+    ApiParameterLocation::Cookie => "cookie",
+  }
+}
+
+/// The arguments of an operation, the parameters followed by the body. Arguments that are
+/// not required are optional.
+pub fn generate_arguments_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  let parameter_identifiers = get_parameter_identifiers(operation);
+  for (parameter, parameter_identifier) in operation.parameters.iter().zip(&parameter_identifiers) {
+    let value_type = get_value_type_token_stream(specification, parameter.schema.as_ref());
+    tokens.append_all(if parameter.required {
+      quote! { #parameter_identifier: #value_type, }
+    } else {
+      quote! { #parameter_identifier: core::option::Option<#value_type>, }
+    });
+  }
+
+  if let Some(request_body) = &operation.request_body {
+    let value_type = get_value_type_token_stream(specification, request_body.schema.as_ref());
+    tokens.append_all(if request_body.required {
+      quote! { body: #value_type, }
+    } else {
+      quote! { body: core::option::Option<#value_type>, }
+    });
+  }
+
+  tokens
+}
+
+/// The type of a parameter or body, without the option when it's not required.
+pub fn get_value_type_token_stream(
+  specification: &Specification,
+  schema: Option<&jns42_core::utilities::NodeLocation>,
+) -> TokenStream {
+  let Some(schema) = schema else {
+    return quote! { std::string::String };
+  };
+
+  specification
+    .get_key(schema)
+    .and_then(|key| specification.get_type_identifier(&key))
+    .unwrap_or_else(|| quote! { serde_json::Value })
+}
+
+/// The type of the value in a response, `None` when the response has no body.
+pub fn get_response_value_type_token_stream(
+  specification: &Specification,
+  body: Option<&ApiBody>,
+) -> Option<TokenStream> {
+  let schema = body?.schema.as_ref()?;
+  Some(get_value_type_token_stream(specification, Some(schema)))
+}
+
+/// The fields of a response variant are the status for ranges and the default, the value
+/// of the body and the headers, all of them are optional.
+pub fn generate_response_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+  let response_identifier = get_response_identifier(operation);
+
+  let mut variants_tokens = quote! {};
+  for response in &operation.responses {
+    let variant_identifier = get_response_variant_identifier(response);
+
+    let mut fields_tokens = Vec::new();
+    if !is_exact_status(response) {
+      fields_tokens.push(quote! { u16 });
+    }
+    if let Some(value_type) =
+      get_response_value_type_token_stream(specification, response.body.as_ref())
+    {
+      fields_tokens.push(value_type);
+    }
+    if let Some(headers_identifier) = get_headers_identifier(operation, response) {
+      tokens.append_all(generate_headers_token_stream(
+        specification,
+        response,
+        &headers_identifier,
+      ));
+      fields_tokens.push(quote! { #headers_identifier });
+    }
+
+    let doc = format!("Status {}", response.status);
+    variants_tokens.append_all(if fields_tokens.is_empty() {
+      quote! { #[doc = #doc] #variant_identifier, }
+    } else {
+      quote! { #[doc = #doc] #variant_identifier(#(#fields_tokens),*), }
+    });
+  }
+
+  tokens.append_all(quote! {
+    #[derive(core::fmt::Debug)]
+    pub enum #response_identifier {
+      #variants_tokens
+    }
+  });
+
+  Ok(tokens)
+}
+
+pub fn is_exact_status(response: &ApiResponse) -> bool {
+  matches!(response.get_status_range(), Some((minimum, maximum)) if minimum == maximum)
+}
+
+fn generate_headers_token_stream(
+  specification: &Specification,
+  response: &ApiResponse,
+  headers_identifier: &Ident,
+) -> TokenStream {
+  let mut fields_tokens = quote! {};
+  for header in &response.headers {
+    let header_identifier = get_header_identifier(header);
+    let value_type = get_value_type_token_stream(specification, header.schema.as_ref());
+    let doc = format!("The {} header", header.name);
+    fields_tokens.append_all(if header.required {
+      quote! { #[doc = #doc] pub #header_identifier: #value_type, }
+    } else {
+      quote! { #[doc = #doc] pub #header_identifier: core::option::Option<#value_type>, }
+    });
+  }
+
+  let doc = format!("Headers of the {} response", response.status);
+  quote! {
+    #[doc = #doc]
+    #[derive(core::fmt::Debug)]
+    pub struct #headers_identifier {
+      #fields_tokens
+    }
+  }
+}
+
+/// Reads the headers of the responses of the operation, header values are parsed like
+/// parameters.
+fn generate_from_headers_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  for response in &operation.responses {
+    let Some(headers_identifier) = get_headers_identifier(operation, response) else {
+      continue;
+    };
+
+    let mut fields_tokens = quote! {};
+    for header in &response.headers {
+      let name = header.name.to_lowercase();
+      let header_identifier = get_header_identifier(header);
+      let convert_tokens = match header
+        .schema
+        .as_ref()
+        .and_then(|schema| specification.get_key(schema))
+      {
+        Some(key) => {
+          let parse_tokens = match get_parser_identifier(specification, &key) {
+            Some(parser_identifier) => quote! {
+              let value = crate::parsers::#parser_identifier(&value, &crate::parsers::ParserOptions::default()).unwrap_or(value);
+            },
+            None => quote! {},
+          };
+          match specification.get_type_identifier(&key) {
+            Some(type_identifier) => quote! {
+              {
+                let value = serde_json::Value::String(value.to_owned());
+                #parse_tokens
+                #type_identifier::from_json(&value, crate::errors::ValidationMode::First)?
+              }
+            },
+            None => quote! { serde_json::Value::String(value.to_owned()) },
+          }
+        }
+        None if header.schema.is_some() => quote! { serde_json::Value::String(value.to_owned()) },
+        None => quote! { value.to_owned() },
+      };
+      let missing_tokens = if header.required {
+        quote! { return core::result::Result::Err(ClientError::MissingHeader(#name)) }
+      } else {
+        quote! { core::option::Option::None }
+      };
+      let found_tokens = if header.required {
+        convert_tokens
+      } else {
+        quote! { core::option::Option::Some(#convert_tokens) }
+      };
+
+      fields_tokens.append_all(quote! {
+        #header_identifier: match headers.get(#name).and_then(|value| value.to_str().ok()) {
+          core::option::Option::Some(value) => #found_tokens,
+          core::option::Option::None => #missing_tokens,
+        },
+      });
+    }
+
+    tokens.append_all(quote! {
+      impl #headers_identifier {
+        pub fn from_headers(headers: &reqwest::header::HeaderMap) -> core::result::Result<Self, ClientError> {
+          core::result::Result::Ok(Self {
+            #fields_tokens
+          })
+        }
+      }
+    });
+  }
+
+  tokens
+}
+
+fn generate_operation_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let operation_identifier = get_operation_identifier(operation);
+  let response_identifier = get_response_identifier(operation);
+  let method_identifier = format_ident!("{}", operation.method.to_uppercase());
+  let parameter_identifiers = get_parameter_identifiers(operation);
+
+  let arguments_tokens = generate_arguments_token_stream(specification, operation);
+  let mut path_tokens = quote! {};
+  let mut request_tokens = quote! {};
+
+  for (parameter, parameter_identifier) in operation.parameters.iter().zip(&parameter_identifiers) {
+    let name = &parameter.name;
+    let parameter_tokens = match parameter.location {
+      ApiParameterLocation::Path => {
+        path_tokens.append_all(quote! {
+          let #parameter_identifier = to_parameter_string(&#parameter_identifier)?;
+        });
+        continue;
+      }
+      ApiParameterLocation::Query => quote! {
+        for value in to_parameter_strings(value)? {
+          request = request.query(&[(#name, value)]);
+        }
+      },
+      ApiParameterLocation::Header => quote! {
+        request = request.header(#name, to_parameter_string(value)?);
+      },
+      ApiParameterLocation::Cookie => quote! {
+        cookies.push(format!("{}={}", #name, to_parameter_string(value)?));
+      },
+    };
+    request_tokens.append_all(if parameter.required {
+      quote! {
+        let value = &#parameter_identifier;
+        #parameter_tokens
+      }
+    } else {
+      quote! {
+        if let core::option::Option::Some(value) = &#parameter_identifier {
+          #parameter_tokens
+        }
+      }
+    });
+  }
+
+  if let Some(request_body) = &operation.request_body {
+    let content_type = &request_body.content_type;
+    let body_tokens = if request_body.is_json() {
+      quote! { serde_json::to_vec(value)? }
+    } else {
+      quote! { to_parameter_string(value)? }
+    };
+    let body_tokens = quote! {
+      request = request.header("content-type", #content_type).body(#body_tokens);
+    };
+    request_tokens.append_all(if request_body.required {
+      quote! {
+        let value = &body;
+        #body_tokens
+      }
+    } else {
+      quote! {
+        if let core::option::Option::Some(value) = &body {
+          #body_tokens
+        }
+      }
+    });
+  }
+
+  let segments_tokens = generate_segments_token_stream(operation, &parameter_identifiers);
+
+  // exact status codes first, then ranges and the default last
+  let mut responses: Vec<_> = operation.responses.iter().collect();
+  responses.sort_by_key(|response| match response.get_status_range() {
+    Some((minimum, maximum)) if minimum == maximum => 0,
+    Some(_) => 1,
+    None => 2,
+  });
+
+  let mut arms_tokens = quote! {};
+  let mut has_default = false;
+  for response in responses {
+    let variant_identifier = get_response_variant_identifier(response);
+    let value_tokens = response
+      .body
+      .as_ref()
+      .and_then(|body| Some((body, body.schema.as_ref()?)))
+      .map(|(body, schema)| {
+        let is_json = body.is_json();
+        let type_identifier = specification
+          .get_key(schema)
+          .and_then(|key| specification.get_type_identifier(&key));
+        match type_identifier {
+          Some(type_identifier) => quote! {
+            {
+              let value = from_body(&data, #is_json)?;
+              #type_identifier::from_json(&value, crate::errors::ValidationMode::First)?
+            }
+          },
+          None => quote! { from_body(&data, #is_json)? },
+        }
+      });
+
+    let pattern_tokens = match response.get_status_range() {
+      Some((minimum, maximum)) if minimum == maximum => {
+        let status = Literal::u16_unsuffixed(minimum);
+        quote! { #status }
+      }
+      Some((minimum, maximum)) => {
+        let minimum = Literal::u16_unsuffixed(minimum);
+        let maximum = Literal::u16_unsuffixed(maximum);
+        quote! { #minimum..=#maximum }
+      }
+      None => {
+        has_default = true;
+        quote! { _ }
+      }
+    };
+
+    let mut fields_tokens = Vec::new();
+    if !is_exact_status(response) {
+      fields_tokens.push(quote! { status });
+    }
+    fields_tokens.extend(value_tokens);
+    if let Some(headers_identifier) = get_headers_identifier(operation, response) {
+      fields_tokens.push(quote! { #headers_identifier::from_headers(&headers)? });
+    }
+    let result_tokens = if fields_tokens.is_empty() {
+      quote! { #response_identifier::#variant_identifier }
+    } else {
+      quote! { #response_identifier::#variant_identifier(#(#fields_tokens),*) }
+    };
+
+    arms_tokens.append_all(quote! {
+      #pattern_tokens => core::result::Result::Ok(#result_tokens),
+    });
+  }
+  if !has_default {
+    arms_tokens.append_all(quote! {
+      _ => core::result::Result::Err(ClientError::UnexpectedStatus(status)),
+    });
+  }
+
+  let has_cookies = operation
+    .parameters
+    .iter()
+    .any(|parameter| parameter.location == ApiParameterLocation::Cookie);
+  let (cookies_tokens, cookies_header_tokens) = if has_cookies {
+    (
+      quote! { let mut cookies: std::vec::Vec<std::string::String> = std::vec::Vec::new(); },
+      quote! {
+        if !cookies.is_empty() {
+          request = request.header("cookie", cookies.join("; "));
+        }
+      },
+    )
+  } else {
+    (quote! {}, quote! {})
+  };
+
+  let headers_tokens = if operation
+    .responses
+    .iter()
+    .any(|response| !response.headers.is_empty())
+  {
+    quote! { let headers = response.headers().clone(); }
+  } else {
+    quote! {}
+  };
+
+  // the body is always read, even if we don't need it
+  let data_tokens = if operation.responses.iter().any(|response| {
+    response
+      .body
+      .as_ref()
+      .is_some_and(|body| body.schema.is_some())
+  }) {
+    quote! { let data = response.bytes().await?; }
+  } else {
+    quote! { response.bytes().await?; }
+  };
+
+  let doc = format!("{} {}", operation.method.to_uppercase(), operation.path);
+  Ok(quote! {
+    #[doc = #doc]
+    pub async fn #operation_identifier(
+      &self,
+      #arguments_tokens
+    ) -> core::result::Result<#response_identifier, ClientError> {
+      #path_tokens
+
+      let mut url = self.base_url.clone();
+      {
+        let mut segments = url.path_segments_mut().map_err(|()| ClientError::Url)?;
+        segments.pop_if_empty();
+        #segments_tokens
+      }
+
+      let mut request = self.http_client.request(reqwest::Method::#method_identifier, url);
+      #cookies_tokens
+      #request_tokens
+      #cookies_header_tokens
+
+      let response = request.send().await?;
+      let status = response.status().as_u16();
+      #headers_tokens
+      #data_tokens
+
+      match status {
+        #arms_tokens
+      }
+    }
+  })
+}
+
+/// Push every segment of the path template, parameters are replaced by their value.
+fn generate_segments_token_stream(
+  operation: &ApiOperation,
+  parameter_identifiers: &[Ident],
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  for segment in operation
+    .path
+    .split('/')
+    .filter(|segment| !segment.is_empty())
+  {
+    let mut parts_tokens = Vec::new();
+    let mut rest = segment;
+    while !rest.is_empty() {
+      let parameter = rest
+        .strip_prefix('{')
+        .and_then(|tail| tail.split_once('}'))
+        .and_then(|(name, tail)| {
+          let parameter_identifier = operation
+            .parameters
+            .iter()
+            .zip(parameter_identifiers)
+            .find(|(parameter, _)| {
+              parameter.location == ApiParameterLocation::Path && parameter.name == name
+            })
+            .map(|(_, parameter_identifier)| parameter_identifier)?;
+          Some((parameter_identifier, tail))
+        });
+
+      if let Some((parameter_identifier, tail)) = parameter {
+        parts_tokens.push(quote! { #parameter_identifier.as_str() });
+        rest = tail;
+        continue;
+      }
+
+      // literal text up to the next parameter
+      let end = rest[1..]
+        .find('{')
+        .map(|index| index + 1)
+        .unwrap_or(rest.len());
+      let literal = &rest[..end];
+      parts_tokens.push(quote! { #literal });
+      rest = &rest[end..];
+    }
+
+    tokens.append_all(match parts_tokens.as_slice() {
+      [part_tokens] => quote! { segments.push(#part_tokens); },
+      parts_tokens => quote! { segments.push(&[#(#parts_tokens),*].concat()); },
+    });
+  }
+
+  tokens
+}
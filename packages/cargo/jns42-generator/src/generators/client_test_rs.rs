@@ -0,0 +1,238 @@
+use super::client_rs::{
+  get_headers_identifier, get_operation_identifier, get_parameter_identifiers,
+  get_response_identifier, get_response_variant_identifier, is_exact_status,
+};
+use super::mocks_rs::get_mock_identifier;
+use crate::models::Specification;
+use jns42_core::models::{ApiOperation, ApiResponse};
+use jns42_core::utilities::NodeLocation;
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{TokenStreamExt, format_ident, quote};
+use std::error::Error;
+
+pub fn generate_file_token_stream(
+  specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  // a stand-in server that answers every request with a mock of the response for the
+  // status it is told to use
+  tokens.append_all(quote! {
+    type ServerResponse = hyper::Response<http_body_util::Full<hyper::body::Bytes>>;
+
+    /// Serves one kind of response on a random local port, the base url of the server is
+    /// returned.
+    async fn serve(
+      method: &'static str,
+      status: u16,
+      body: core::option::Option<(&'static str, std::vec::Vec<u8>)>,
+    ) -> core::result::Result<reqwest::Url, std::boxed::Box<dyn std::error::Error>> {
+      let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+      let address = listener.local_addr()?;
+
+      tokio::spawn(async move {
+        loop {
+          let core::result::Result::Ok((stream, _)) = listener.accept().await else {
+            break;
+          };
+          let body = body.clone();
+          let service = hyper::service::service_fn(move |request: hyper::Request<hyper::body::Incoming>| {
+            let body = body.clone();
+            async move {
+              let mut response = hyper::Response::builder();
+              if request.method().as_str() != method {
+                let response = response.status(405).body(http_body_util::Full::default())?;
+                return core::result::Result::<ServerResponse, hyper::http::Error>::Ok(response);
+              }
+
+              response = response.status(status);
+              let response = match body {
+                core::option::Option::Some((content_type, data)) => response
+                  .header("content-type", content_type)
+                  .body(http_body_util::Full::new(hyper::body::Bytes::from(data)))?,
+                core::option::Option::None => response.body(http_body_util::Full::default())?,
+              };
+              core::result::Result::Ok(response)
+            }
+          });
+
+          tokio::spawn(async move {
+            let _ = hyper::server::conn::http1::Builder::new()
+              .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+              .await;
+          });
+        }
+      });
+
+      core::result::Result::Ok(format!("http://{}/api/", address).parse()?)
+    }
+  });
+
+  for operation in &specification.operations {
+    tokens.append_all(generate_test_token_stream(specification, operation)?);
+  }
+
+  Ok(tokens)
+}
+
+fn generate_test_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let Some((status, response)) = get_mock_response(operation) else {
+    return Ok(quote! {});
+  };
+  // the stand-in server does not send headers
+  if response.headers.iter().any(|header| header.required) {
+    return Ok(quote! {});
+  }
+  let Some((arguments_tokens, argument_identifiers)) =
+    generate_mock_arguments_token_stream(specification, operation)
+  else {
+    return Ok(quote! {});
+  };
+
+  let Some(body_tokens) = generate_response_body_token_stream(specification, response) else {
+    return Ok(quote! {});
+  };
+
+  let test_identifier = format_ident!("r#test_{}", get_operation_identifier(operation));
+  let operation_identifier = get_operation_identifier(operation);
+  let method = operation.method.to_uppercase();
+  let status = Literal::u16_unsuffixed(status);
+  let pattern_tokens = generate_response_pattern_token_stream(operation, response);
+
+  Ok(quote! {
+    #[tokio::test]
+    async fn #test_identifier() -> core::result::Result<(), std::boxed::Box<dyn std::error::Error>> {
+      for seed in 0..4 {
+        let base_url = serve(#method, #status, #body_tokens).await?;
+        let client = crate::client::Client::new(base_url);
+
+        #arguments_tokens
+        let response = client.#operation_identifier(#(#argument_identifiers),*).await?;
+        assert!(matches!(response, #pattern_tokens), "{:?}", response);
+      }
+
+      core::result::Result::Ok(())
+    }
+  })
+}
+
+/// Matches the variant of the response in the client, whatever the fields are
+pub fn generate_response_pattern_token_stream(
+  operation: &ApiOperation,
+  response: &ApiResponse,
+) -> TokenStream {
+  let response_identifier = get_response_identifier(operation);
+  let variant_identifier = get_response_variant_identifier(response);
+
+  let has_fields = !is_exact_status(response)
+    || response
+      .body
+      .as_ref()
+      .is_some_and(|body| body.schema.is_some())
+    || get_headers_identifier(operation, response).is_some();
+  if has_fields {
+    quote! { crate::client::#response_identifier::#variant_identifier(..) }
+  } else {
+    quote! { crate::client::#response_identifier::#variant_identifier }
+  }
+}
+
+/// The response with the lowest exact status code, this is what the stand-in servers send
+pub fn get_mock_response(operation: &ApiOperation) -> Option<(u16, &ApiResponse)> {
+  operation
+    .responses
+    .iter()
+    .filter_map(|response| match response.get_status_range() {
+      Some((minimum, maximum)) if minimum == maximum => Some((minimum, response)),
+      _ => None,
+    })
+    .min_by_key(|(status, _)| *status)
+}
+
+/// Statements that assign a mock to every argument of the operation, with the identifiers of
+/// the arguments. `None` if an argument cannot be mocked. The statements expect `seed`.
+pub fn generate_mock_arguments_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> Option<(TokenStream, Vec<Ident>)> {
+  let mut tokens = quote! {};
+  let parameter_identifiers = get_parameter_identifiers(operation);
+  for (parameter, parameter_identifier) in operation.parameters.iter().zip(&parameter_identifiers) {
+    let value_tokens = generate_mock_value_token_stream(specification, &parameter.schema)?;
+    tokens.append_all(quote! { let #parameter_identifier = #value_tokens; });
+    if !parameter.required {
+      tokens.append_all(quote! {
+        let #parameter_identifier = core::option::Option::Some(#parameter_identifier);
+      });
+    }
+  }
+
+  let mut argument_identifiers = parameter_identifiers;
+  if let Some(request_body) = &operation.request_body {
+    let value_tokens = generate_mock_value_token_stream(specification, &request_body.schema)?;
+    tokens.append_all(quote! { let body = #value_tokens; });
+    if !request_body.required {
+      tokens.append_all(quote! {
+        let body = core::option::Option::Some(body);
+      });
+    }
+    argument_identifiers.push(format_ident!("body"));
+  }
+
+  Some((tokens, argument_identifiers))
+}
+
+/// A mock value for the parameter, header or body with `schema`, `None` if we cannot mock it
+pub fn generate_mock_value_token_stream(
+  specification: &Specification,
+  schema: &Option<NodeLocation>,
+) -> Option<TokenStream> {
+  let Some(schema) = schema else {
+    return Some(quote! { format!("mock{}", seed) });
+  };
+
+  let key = specification.get_key(schema)?;
+  if !specification.is_mockable(&key) {
+    return None;
+  }
+
+  let mock_identifier = get_mock_identifier(specification, &key)?;
+  let type_identifier = specification.get_type_identifier(&key)?;
+  Some(quote! {
+    #type_identifier::from_json(
+      &crate::mocks::#mock_identifier(&mut crate::mocks::MockContext::new(seed)),
+      crate::errors::ValidationMode::All,
+    )?
+  })
+}
+
+/// The content type and data that the server answers with, `None` if we cannot mock it
+fn generate_response_body_token_stream(
+  specification: &Specification,
+  response: &ApiResponse,
+) -> Option<TokenStream> {
+  let Some(body) = &response.body else {
+    return Some(quote! { core::option::Option::None });
+  };
+  let content_type = &body.content_type;
+
+  let Some(schema) = &body.schema else {
+    return Some(quote! { core::option::Option::Some((#content_type, std::vec::Vec::new())) });
+  };
+
+  let key = specification.get_key(schema)?;
+  if !specification.is_mockable(&key) || !body.is_json() {
+    return None;
+  }
+
+  let mock_identifier = get_mock_identifier(specification, &key)?;
+  Some(quote! {
+    core::option::Option::Some((
+      #content_type,
+      serde_json::to_vec(&crate::mocks::#mock_identifier(&mut crate::mocks::MockContext::new(seed)))?,
+    ))
+  })
+}
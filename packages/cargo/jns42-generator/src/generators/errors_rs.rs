@@ -9,27 +9,218 @@ pub fn generate_file_token_stream(
   let mut tokens = quote! {};
 
   tokens.append_all(quote! {
-    #[derive(core::clone::Clone, core::fmt::Debug, core::cmp::PartialEq, core::cmp::PartialOrd, core::cmp::Eq, core::cmp::Ord)]
+    /// A single assertion that failed.
+    #[derive(core::clone::Clone, core::fmt::Debug, core::cmp::PartialEq, core::cmp::Eq, serde::Serialize)]
+    #[serde(rename_all = "camelCase")]
     pub struct ValidationError {
-        r#type: &'static str,
+        /// Name of the type that the value failed to validate against
+        pub type_name: &'static str,
+        /// JSON pointer to the value in the instance
+        pub instance_path: std::string::String,
+        /// The keyword of the assertion that failed, like `minimum` or `required`
+        pub keyword: &'static str,
+        /// The value of the keyword in the schema
+        pub expected: serde_json::Value,
+        /// Location of the schema that the type was generated from
+        pub schema_location: core::option::Option<&'static str>,
     }
   });
 
   tokens.append_all(quote! {
-    impl ValidationError {
-        pub fn new(r#type: &'static str) -> Self {
-            Self { r#type }
+    impl core::fmt::Display for ValidationError {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(
+              f,
+              "validation error for type {} at \"{}\": {} {}",
+              self.type_name, self.instance_path, self.keyword, self.expected
+            )?;
+            if let core::option::Option::Some(schema_location) = self.schema_location {
+              write!(f, " ({})", schema_location)?;
+            }
+            core::result::Result::Ok(())
         }
     }
   });
 
   tokens.append_all(quote! {
-    impl core::fmt::Display for ValidationError {
+    impl std::error::Error for ValidationError {}
+  });
+
+  tokens.append_all(quote! {
+    /// All errors that were found while validating, there is at least one.
+    #[derive(core::clone::Clone, core::fmt::Debug, core::cmp::PartialEq, core::cmp::Eq, serde::Serialize)]
+    #[serde(transparent)]
+    pub struct ValidationErrors(pub std::vec::Vec<ValidationError>);
+  });
+
+  tokens.append_all(quote! {
+    impl core::fmt::Display for ValidationErrors {
         fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-            write!(f, "validation error for type {}", self.r#type)
+            for (index, error) in self.0.iter().enumerate() {
+              if index > 0 {
+                writeln!(f)?;
+              }
+              write!(f, "{}", error)?;
+            }
+            core::result::Result::Ok(())
+        }
+    }
+  });
+
+  tokens.append_all(quote! {
+    impl std::error::Error for ValidationErrors {}
+  });
+
+  tokens.append_all(quote! {
+    impl std::ops::Deref for ValidationErrors {
+        type Target = [ValidationError];
+        fn deref(&self) -> &Self::Target {
+            &self.0
+        }
+    }
+  });
+
+  tokens.append_all(quote! {
+    impl core::iter::IntoIterator for ValidationErrors {
+        type Item = ValidationError;
+        type IntoIter = std::vec::IntoIter<ValidationError>;
+        fn into_iter(self) -> Self::IntoIter {
+            self.0.into_iter()
         }
     }
+  });
 
+  tokens.append_all(quote! {
+    /// Stop validating at the first error, or collect every error.
+    #[derive(core::clone::Clone, core::marker::Copy, core::fmt::Debug, core::cmp::PartialEq, core::cmp::Eq, core::default::Default)]
+    pub enum ValidationMode {
+        #[default]
+        First,
+        All,
+    }
+  });
+
+  tokens.append_all(quote! {
+    /// Keeps track of where we are in the instance and of the errors found so far.
+    #[derive(core::fmt::Debug)]
+    pub struct ValidationContext {
+        mode: ValidationMode,
+        path: std::vec::Vec<std::string::String>,
+        errors: std::vec::Vec<ValidationError>,
+    }
+  });
+
+  tokens.append_all(quote! {
+    impl ValidationContext {
+        pub fn new(mode: ValidationMode) -> Self {
+            Self {
+              mode,
+              path: std::vec::Vec::new(),
+              errors: std::vec::Vec::new(),
+            }
+        }
+
+        /// A new context at the same location in the instance, errors that are recorded in
+        /// the new context are not recorded here.
+        pub fn fork(&self, mode: ValidationMode) -> Self {
+            Self {
+              mode,
+              path: self.path.clone(),
+              errors: std::vec::Vec::new(),
+            }
+        }
+
+        pub fn push_path(&mut self, part: impl std::string::ToString) {
+            self.path.push(part.to_string());
+        }
+
+        pub fn pop_path(&mut self) {
+            self.path.pop();
+        }
+
+        /// JSON pointer to the current location in the instance
+        pub fn instance_path(&self) -> std::string::String {
+            self
+              .path
+              .iter()
+              .map(|part| format!("/{}", part.replace('~', "~0").replace('/', "~1")))
+              .collect()
+        }
+
+        pub fn record(
+          &mut self,
+          type_name: &'static str,
+          schema_location: core::option::Option<&'static str>,
+          keyword: &'static str,
+          expected: serde_json::Value,
+        ) {
+            self.errors.push(ValidationError {
+              type_name,
+              instance_path: self.instance_path(),
+              keyword,
+              expected,
+              schema_location,
+            });
+        }
+
+        pub fn error_count(&self) -> usize {
+            self.errors.len()
+        }
+
+        /// Whether validating should stop, this is the case when we only want the first
+        /// error and we have it.
+        pub fn is_done(&self) -> bool {
+            self.mode == ValidationMode::First && !self.errors.is_empty()
+        }
+
+        /// The value if there are no errors, otherwise the errors. If there is no value there
+        /// is always at least one error, a `type` error for `T` if nothing else was recorded.
+        pub fn into_result<T>(mut self, value: core::option::Option<T>) -> core::result::Result<T, ValidationErrors> {
+            match value {
+              core::option::Option::Some(value) if self.errors.is_empty() => core::result::Result::Ok(value),
+              _ => {
+                if self.errors.is_empty() {
+                  self.record(core::any::type_name::<T>(), core::option::Option::None, "type", serde_json::Value::Null);
+                }
+                core::result::Result::Err(ValidationErrors(self.errors))
+              }
+            }
+        }
+    }
+  });
+
+  tokens.append_all(quote! {
+    /// Compares json values like json schema does, numbers are equal when their values are,
+    /// so `1` equals `1.0`.
+    pub fn json_equals(left: &serde_json::Value, right: &serde_json::Value) -> bool {
+        match (left, right) {
+          (serde_json::Value::Number(left), serde_json::Value::Number(right)) => {
+            if let (core::option::Option::Some(left), core::option::Option::Some(right)) = (left.as_i64(), right.as_i64()) {
+              return left == right;
+            }
+            if let (core::option::Option::Some(left), core::option::Option::Some(right)) = (left.as_u64(), right.as_u64()) {
+              return left == right;
+            }
+            // integers that do not fit in 64 bits are equal when they are written the same
+            let is_integer = |number: &serde_json::Number| !number.to_string().contains(['.', 'e', 'E']);
+            if is_integer(left) && is_integer(right) {
+              return left.to_string() == right.to_string();
+            }
+            left.as_f64() == right.as_f64()
+          }
+          (serde_json::Value::Array(left), serde_json::Value::Array(right)) => {
+            left.len() == right.len()
+              && left.iter().zip(right).all(|(left, right)| json_equals(left, right))
+          }
+          (serde_json::Value::Object(left), serde_json::Value::Object(right)) => {
+            left.len() == right.len()
+              && left.iter().all(|(name, left)| {
+                right.get(name).is_some_and(|right| json_equals(left, right))
+              })
+          }
+          (left, right) => left == right,
+        }
+    }
   });
 
   Ok(tokens)
@@ -10,7 +10,7 @@ pub fn generate_file_token_stream(
   let mut tokens = quote! {};
 
   for (key, item) in specification.arena.iter().enumerate() {
-    tokens.append_all(generate_test_token_stream(specification, &key, item));
+    tokens.append_all(generate_test_token_stream(specification, &key, item)?);
   }
 
   Ok(tokens)
@@ -0,0 +1,13 @@
+use crate::models::Specification;
+use jns42_core::validation::FORMATS_SOURCE;
+use proc_macro2::TokenStream;
+use std::error::Error;
+
+/// The format checks of jns42-core, generated code checks formats exactly like the validator.
+pub fn generate_file_token_stream(
+  _specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let tokens = FORMATS_SOURCE.parse()?;
+
+  Ok(tokens)
+}
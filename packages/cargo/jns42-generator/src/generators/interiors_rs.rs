@@ -1,11 +1,11 @@
-use crate::models::Specification;
+use crate::models::{Specification, StringFormat};
 use jns42_core::{
   models::{ArenaSchemaItem, SchemaType},
   naming::Sentence,
 };
-use proc_macro2::TokenStream;
+use proc_macro2::{Ident, TokenStream};
 use quote::{TokenStreamExt, format_ident, quote};
-use std::{collections::HashSet, error::Error};
+use std::{collections::BTreeSet, error::Error};
 
 pub fn generate_file_token_stream(
   specification: &Specification,
@@ -13,7 +13,7 @@ pub fn generate_file_token_stream(
   let mut tokens = quote! {};
 
   for (key, item) in specification.arena.iter().enumerate() {
-    tokens.append_all(generate_type_token_stream(specification, &key, item));
+    tokens.append_all(generate_type_token_stream(specification, &key, item)?);
   }
 
   Ok(tokens)
@@ -54,8 +54,8 @@ fn generate_type_token_stream(
     });
 
     return Ok(tokens);
-  } else if let Some(types) = &item.types {
-    if types.len() == 1 {
+  } else if let Some(types) = item.types.as_ref().filter(|types| types.len() == 1) {
+    {
       let r#type = types.first().unwrap();
       match r#type {
         SchemaType::Never => {
@@ -79,19 +79,55 @@ fn generate_type_token_stream(
           });
         }
         SchemaType::Integer => {
+          let integer_type = specification
+            .get_integer_type(item)
+            .unwrap()
+            .get_type_token_stream();
           tokens.append_all(quote! {
-            pub type #identifier = i64;
+            pub type #identifier = #integer_type;
           });
         }
         SchemaType::Number => {
+          let number_type = specification.number_type.get_type_token_stream();
           tokens.append_all(quote! {
-            pub type #identifier = f64;
+            pub type #identifier = #number_type;
           });
         }
         SchemaType::String => {
-          tokens.append_all(quote! {
-            pub type #identifier = std::string::String;
-          });
+          // strings with content hold the decoded content
+          if let Some(content_identifier) = item
+            .get_json_content_schema()
+            .and_then(|key| specification.get_type_identifier(&key))
+          {
+            tokens.append_all(quote! {
+              pub type #identifier = #content_identifier;
+            });
+          } else if item.is_base64_content() {
+            tokens.append_all(quote! {
+              pub type #identifier = std::vec::Vec<u8>;
+            });
+          } else if let Some(format) = StringFormat::from_item(item) {
+            // only one of the types exists, so both get the documentation
+            let feature = format.get_feature();
+            let format_type = format.get_type_token_stream();
+            tokens.append_all(quote! {
+              #[cfg(feature = #feature)]
+              pub type #identifier = #format_type;
+            });
+            if !documentation.is_empty() {
+              tokens.append_all(quote! {
+                #[doc = #documentation]
+              });
+            }
+            tokens.append_all(quote! {
+              #[cfg(not(feature = #feature))]
+              pub type #identifier = std::string::String;
+            });
+          } else {
+            tokens.append_all(quote! {
+              pub type #identifier = std::string::String;
+            });
+          }
         }
         SchemaType::Array => {
           if let Some(tuple_items_keys) = &item.tuple_items {
@@ -118,31 +154,45 @@ fn generate_type_token_stream(
             });
           } else {
             tokens.append_all(quote! {
-              pub type #identifier = std::vec::Vec<()>;
+              pub type #identifier = std::vec::Vec<serde_json::Value>;
             });
           }
         }
         SchemaType::Object => {
           if let Some(object_properties_entries) = &item.object_properties {
-            let required: HashSet<_> = item
+            let required: BTreeSet<_> = item
               .required
               .as_ref()
               .map(|value| value.iter().collect())
               .unwrap_or_default();
-            let inner_tokens = object_properties_entries
-              .iter()
-              .map(|(member_name, object_properties_key)| {
+            let member_names: BTreeSet<_> = object_properties_entries
+              .keys()
+              .chain(required.iter().copied())
+              .collect();
+            let inner_tokens = member_names
+              .into_iter()
+              .map(|member_name| {
                 let member_identifier =
                   format_ident!("r#{}", Sentence::new(member_name).to_snake_case());
-                let object_properties_identifier =
-                  specification.get_type_identifier(object_properties_key);
+                // required properties without a schema may be anything
+                let object_properties_identifier = object_properties_entries
+                  .get(member_name)
+                  .and_then(|object_properties_key| {
+                    specification.get_type_identifier(object_properties_key)
+                  })
+                  .unwrap_or_else(|| quote! { serde_json::Value });
 
                 if required.contains(member_name) {
                   quote! {
+                    #[serde(rename = #member_name)]
                     pub #member_identifier: #object_properties_identifier
                   }
                 } else {
                   quote! {
+                    #[serde(
+                      rename = #member_name,
+                      skip_serializing_if = "std::option::Option::is_none"
+                    )]
                     pub #member_identifier: std::option::Option<#object_properties_identifier>
                   }
                 }
@@ -150,11 +200,43 @@ fn generate_type_token_stream(
               .reduce(|a, b| quote! {#a, #b})
               .unwrap_or_default();
 
-            tokens.append_all(quote! {
-              #[derive(core::fmt::Debug, serde::Serialize, serde::Deserialize, core::clone::Clone)]
-              pub struct #identifier {
-                #inner_tokens
+            match get_struct_remainder(specification, item) {
+              StructRemainder::Closed => {
+                tokens.append_all(quote! {
+                  #[derive(core::fmt::Debug, serde::Serialize, core::clone::Clone)]
+                  #[serde(deny_unknown_fields)]
+                  pub struct #identifier {
+                    #inner_tokens
+                  }
+                });
+              }
+              StructRemainder::Map {
+                identifier: remainder_identifier,
+                type_identifier: remainder_type_identifier,
+              } => {
+                tokens.append_all(quote! {
+                  #[derive(core::fmt::Debug, serde::Serialize, core::clone::Clone)]
+                  pub struct #identifier {
+                    #inner_tokens,
+                    #[serde(flatten)]
+                    pub #remainder_identifier: std::collections::HashMap<std::string::String, #remainder_type_identifier>
+                  }
+                });
+              }
+              StructRemainder::Dropped => {
+                tokens.append_all(quote! {
+                  #[derive(core::fmt::Debug, serde::Serialize, core::clone::Clone)]
+                  pub struct #identifier {
+                    #inner_tokens
+                  }
+                });
               }
+            }
+          } else if item.pattern_properties.is_some() {
+            // the type of every value depends on the name of the property, so the values are
+            // checked while validating
+            tokens.append_all(quote! {
+              pub type #identifier = std::collections::HashMap<std::string::String, serde_json::Value>;
             });
           } else if let Some(map_properties_key) = &item.map_properties {
             let map_properties_identifier = specification.get_type_identifier(map_properties_key);
@@ -164,7 +246,7 @@ fn generate_type_token_stream(
             });
           } else {
             tokens.append_all(quote! {
-              pub type #identifier = std::collections::HashMap<std::string::String, ()>;
+              pub type #identifier = std::collections::HashMap<std::string::String, serde_json::Value>;
             });
           }
         }
@@ -203,13 +285,14 @@ fn generate_type_token_stream(
     let mut inner_tokens = quote! {};
     for sub_key in one_of {
       let sub_identifier = specification.get_identifier(sub_key);
+      let sub_type_identifier = specification.get_type_identifier(sub_key);
       inner_tokens.append_all(quote! {
-          #sub_identifier(#sub_identifier),
+          #sub_identifier(#sub_type_identifier),
       });
     }
 
     tokens.append_all(quote! {
-      #[derive(core::fmt::Debug, serde::Serialize, serde::Deserialize, core::clone::Clone)]
+      #[derive(core::fmt::Debug, serde::Serialize, core::clone::Clone)]
       #[serde(untagged)]
       pub enum #identifier {
         #inner_tokens
@@ -219,10 +302,77 @@ fn generate_type_token_stream(
     return Ok(tokens);
   }
 
+  // without a single type the value is kept as is, it is checked against the any of and all
+  // of of the item when validating
   tokens.append_all(quote! {
-    #[derive(core::fmt::Debug, serde::Serialize, serde::Deserialize, core::clone::Clone)]
-    pub struct #identifier();
+    pub type #identifier = serde_json::Value;
+    impl core::convert::From<#type_identifier> for #identifier {
+      fn from(value: #type_identifier) -> Self {
+          value.0
+      }
+    }
   });
 
   Ok(tokens)
 }
+
+/// What happens to the properties of a struct that are not members of the struct
+pub enum StructRemainder {
+  /// Other properties are not allowed
+  Closed,
+  /// Other properties are kept in a map field with `identifier`
+  Map {
+    identifier: Ident,
+    type_identifier: TokenStream,
+  },
+  /// Other properties are allowed, but not kept
+  Dropped,
+}
+
+/// The map properties of an item with object properties decide what happens to the other
+/// properties. Additional and unevaluated properties both end up as map properties.
+pub fn get_struct_remainder(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> StructRemainder {
+  let Some(map_properties_key) = item.map_properties else {
+    return StructRemainder::Dropped;
+  };
+
+  if specification
+    .arena
+    .get_item(map_properties_key)
+    .types
+    .as_ref()
+    .is_some_and(|types| types == &[SchemaType::Never])
+  {
+    return StructRemainder::Closed;
+  }
+
+  // properties that match a pattern are not part of the remainder, but they cannot be
+  // members either
+  if item.pattern_properties.is_some() {
+    return StructRemainder::Dropped;
+  }
+
+  let Some(type_identifier) = specification.get_type_identifier(&map_properties_key) else {
+    return StructRemainder::Dropped;
+  };
+
+  let member_identifiers: BTreeSet<_> = item
+    .object_properties
+    .iter()
+    .flat_map(|object_properties| object_properties.keys())
+    .chain(item.required.iter().flatten())
+    .map(|member_name| Sentence::new(member_name).to_snake_case())
+    .collect();
+  let mut identifier = "remainder".to_owned();
+  while member_identifiers.contains(&identifier) {
+    identifier = format!("{}_", identifier);
+  }
+
+  StructRemainder::Map {
+    identifier: format_ident!("r#{}", identifier),
+    type_identifier,
+  }
+}
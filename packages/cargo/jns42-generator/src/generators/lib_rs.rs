@@ -4,13 +4,15 @@ use quote::{TokenStreamExt, quote};
 use std::error::Error;
 
 pub fn generate_file_token_stream(
-  _specification: &Specification,
+  specification: &Specification,
 ) -> Result<TokenStream, Box<dyn Error>> {
   let mut tokens = quote! {};
 
   tokens.append_all(quote! {
     pub mod errors;
     pub mod interiors;
+    pub mod mocks;
+    pub mod parsers;
     pub mod types;
     #[cfg(test)]
     pub mod examples_test;
@@ -18,5 +20,22 @@ pub fn generate_file_token_stream(
     pub mod mocks_test;
   });
 
+  if specification.has_formats() {
+    tokens.append_all(quote! {
+      pub mod formats;
+    });
+  }
+
+  if !specification.operations.is_empty() {
+    tokens.append_all(quote! {
+      pub mod client;
+      pub mod server;
+      #[cfg(test)]
+      pub mod client_test;
+      #[cfg(test)]
+      pub mod server_test;
+    });
+  }
+
   Ok(tokens)
 }
@@ -1,49 +1,242 @@
+use super::parsers_rs::get_parser_identifier;
 use crate::models::Specification;
 use jns42_core::utilities::NodeLocation;
 use proc_macro2::TokenStream;
-use quote::{TokenStreamExt, quote};
+use quote::{TokenStreamExt, format_ident, quote};
 use std::error::Error;
 
 pub fn generate_file_token_stream(
   specification: &Specification,
+  package_name: &str,
   entry_location: &NodeLocation,
 ) -> Result<TokenStream, Box<dyn Error>> {
   let mut tokens = quote! {};
 
-  let entry_key = specification
-    .arena
-    .iter()
-    .enumerate()
-    .find_map(|(key, item)| (item.location.as_ref()? == entry_location).then_some(key))
-    .unwrap();
+  let entry_key = specification.get_key(entry_location).unwrap();
 
-  let Some(identifier) = specification.get_type_identifier(&entry_key) else {
+  let Some(entry_name) = specification.get_name(&entry_key) else {
     return Ok(quote! {});
   };
 
+  // every type can be asserted, they are selected by name
+  let mut type_names = Vec::new();
+  let mut assert_cases_tokens = quote! {};
+  for key in 0..specification.arena.count() {
+    let (Some(name), Some(type_identifier), Some(parser_identifier)) = (
+      specification.get_name(&key),
+      specification.get_type_identifier(&key),
+      get_parser_identifier(specification, &key),
+    ) else {
+      continue;
+    };
+
+    assert_cases_tokens.append_all(quote! {
+      #name => {
+        let value = match parser_options {
+          core::option::Option::Some(parser_options) => {
+            crate::parsers::#parser_identifier(&value, parser_options).unwrap_or(value)
+          }
+          core::option::Option::None => value,
+        };
+        #type_identifier::from_json(&value, mode).map(|_| ())
+      }
+    });
+    type_names.push(name);
+  }
+
+  // the modules of the library are used, so the program does not compile its own copy of
+  // them
+  let library_identifier = format_ident!("{}", package_name.replace('-', "_"));
+
   tokens.append_all(quote! {
-    mod errors;
-    mod interiors;
-    mod types;
+    use #library_identifier::{errors, parsers, types};
 
     use std::io::Read;
     use clap::Parser;
 
-    fn main() -> core::result::Result<(), std::boxed::Box<dyn std::error::Error>> {
+    /// All documents are valid
+    const EXIT_VALID: u8 = 0;
+    /// At least one of the documents is not valid
+    const EXIT_INVALID: u8 = 1;
+    /// The input could not be read or parsed, clap uses this for invalid arguments too
+    const EXIT_ERROR: u8 = 2;
+
+    fn main() -> std::process::ExitCode {
       let options: ProgramOptions = ProgramOptions::parse();
 
-      match options.command {
-        ProgramCommands::Assert(options) => {
-          let mut buffer = String::new();
-          std::io::stdin().read_to_string(&mut buffer)?;
+      let exit_code = match options.command {
+        ProgramCommands::Assert(options) => assert(options),
+      };
+
+      std::process::ExitCode::from(exit_code)
+    }
+
+    fn assert(options: AssertCommandOptions) -> u8 {
+      let AssertCommandOptions {
+        file,
+        r#type,
+        format,
+        parse,
+        all,
+        quiet,
+      } = options;
+
+      let format = format
+        .or_else(|| InputFormat::from_path(file.as_ref()?))
+        .unwrap_or(InputFormat::Json);
+      let mode = if all {
+        crate::errors::ValidationMode::All
+      } else {
+        crate::errors::ValidationMode::First
+      };
+      let parser_options = parse.then(crate::parsers::ParserOptions::default);
+
+      let mut text = std::string::String::new();
+      let result = match &file {
+        core::option::Option::Some(file) if file.as_os_str() != "-" => {
+          std::fs::File::open(file).and_then(|mut reader| reader.read_to_string(&mut text))
+        }
+        _ => std::io::stdin().read_to_string(&mut text),
+      };
+      if let core::result::Result::Err(error) = result {
+        if !quiet {
+          eprintln!("{}", error);
+        }
+        return EXIT_ERROR;
+      }
+
+      let documents = format.read_documents(&text);
+      // a stream reports the line or document number for every error
+      let stream = format == InputFormat::Jsonl || documents.len() > 1;
+
+      // a stream also reports the result of every line or document, and a summary
+      let report = stream && !quiet;
+      let document_count = documents.len();
+
+      let mut exit_code = EXIT_VALID;
+      let mut invalid_count = 0;
+      let mut error_count = 0;
+      for (number, document) in documents {
+        let prefix = if stream {
+          format!("{} {}: ", format.unit(), number)
+        } else {
+          std::string::String::new()
+        };
+
+        let value = match document {
+          core::result::Result::Ok(value) => value,
+          core::result::Result::Err(error) => {
+            if !quiet {
+              eprintln!("{}{}", prefix, error);
+            }
+            if report {
+              println!("{}error", prefix);
+            }
+            error_count += 1;
+            exit_code = EXIT_ERROR;
+            continue;
+          }
+        };
 
-          // TODO implement parse
-          // TODO implement quiet
-          let _entity: #identifier = serde_json::from_str(&buffer)?;
-        },
+        match assert_value(&r#type, value, parser_options.as_ref(), mode) {
+          core::result::Result::Ok(()) => {
+            if report {
+              println!("{}valid", prefix);
+            }
+          }
+          core::result::Result::Err(errors) => {
+            if !quiet {
+              for error in errors.iter() {
+                eprintln!("{}{}", prefix, error);
+              }
+            }
+            if report {
+              println!("{}invalid", prefix);
+            }
+            invalid_count += 1;
+            exit_code = exit_code.max(EXIT_INVALID);
+          }
+        }
       }
 
-      Ok(())
+      if report {
+        println!(
+          "{} {}s checked, {} invalid, {} could not be read",
+          document_count,
+          format.unit(),
+          invalid_count,
+          error_count
+        );
+      }
+
+      exit_code
+    }
+
+    fn assert_value(
+      type_name: &str,
+      value: serde_json::Value,
+      parser_options: core::option::Option<&crate::parsers::ParserOptions>,
+      mode: crate::errors::ValidationMode,
+    ) -> core::result::Result<(), crate::errors::ValidationErrors> {
+      match type_name {
+        #assert_cases_tokens
+        _ => unreachable!("clap only allows known type names"),
+      }
+    }
+
+    #[derive(clap::ValueEnum, core::clone::Clone, core::marker::Copy, core::fmt::Debug, core::cmp::PartialEq, core::cmp::Eq)]
+    pub enum InputFormat {
+      /// A single json document
+      Json,
+      /// Newline delimited json, one document per line
+      Jsonl,
+      /// One or more yaml documents
+      Yaml,
+    }
+
+    impl InputFormat {
+      fn from_path(path: &std::path::Path) -> core::option::Option<Self> {
+        match path.extension()?.to_str()? {
+          "json" => core::option::Option::Some(Self::Json),
+          "jsonl" | "ndjson" => core::option::Option::Some(Self::Jsonl),
+          "yaml" | "yml" => core::option::Option::Some(Self::Yaml),
+          _ => core::option::Option::None,
+        }
+      }
+
+      fn unit(&self) -> &'static str {
+        match self {
+          Self::Json | Self::Yaml => "document",
+          Self::Jsonl => "line",
+        }
+      }
+
+      /// Reads the documents in `text` together with their line or document number.
+      fn read_documents(
+        &self,
+        text: &str,
+      ) -> std::vec::Vec<(usize, core::result::Result<serde_json::Value, std::string::String>)> {
+        match self {
+          Self::Json => std::vec![(1, serde_json::from_str(text).map_err(|error| error.to_string()))],
+          Self::Jsonl => text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+              (index + 1, serde_json::from_str(line).map_err(|error| error.to_string()))
+            })
+            .collect(),
+          Self::Yaml => serde_yaml::Deserializer::from_str(text)
+            .enumerate()
+            .map(|(index, document)| {
+              (
+                index + 1,
+                serde::Deserialize::deserialize(document).map_err(|error: serde_yaml::Error| error.to_string()),
+              )
+            })
+            .collect(),
+        }
+      }
     }
 
     #[derive(clap::Parser, core::fmt::Debug)]
@@ -55,13 +248,28 @@ pub fn generate_file_token_stream(
 
     #[derive(clap::Subcommand, core::fmt::Debug)]
     pub enum ProgramCommands {
+      /// Assert that the documents in a file, or stdin, are valid
       Assert(AssertCommandOptions),
     }
 
     #[derive(clap::Parser, core::fmt::Debug)]
     pub struct AssertCommandOptions {
+      /// File to read, stdin if omitted or -
+      // clap recognizes optional arguments by the plain Option
+      pub file: Option<std::path::PathBuf>,
+      /// Name of the type to assert
+      #[arg(long, default_value = #entry_name, value_parser = [#(#type_names),*])]
+      pub r#type: std::string::String,
+      /// Format of the input, derived from the extension of the file or json
+      #[arg(long, value_enum)]
+      pub format: Option<InputFormat>,
+      /// Parse the data before asserting, this turns strings into numbers or booleans
       #[arg(long)]
       pub parse: bool,
+      /// Report all errors, not only the first one
+      #[arg(long)]
+      pub all: bool,
+      /// Don't display errors, or the result of every line or document of a stream
       #[arg(long)]
       pub quiet: bool,
     }
@@ -0,0 +1,699 @@
+use super::types_rs::generate_json_token_stream;
+use crate::models::{
+  IntegerBound, NumberType, Specification, get_integer_maximum, get_integer_minimum,
+};
+use jns42_core::{
+  models::{ArenaSchemaItem, SchemaType},
+  utilities::Decimal,
+};
+use proc_macro2::{Ident, TokenStream};
+use quote::{TokenStreamExt, format_ident, quote};
+use std::{collections::BTreeSet, error::Error};
+
+/// How many times we try to mock a one of before we give up
+const ONE_OF_ATTEMPTS: usize = 16;
+
+pub fn generate_file_token_stream(
+  specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  tokens.append_all(quote! {
+    /// Configures mock generation, the defaults are used when the schema does not constrain
+    /// a value.
+    #[derive(core::clone::Clone, core::fmt::Debug)]
+    pub struct MockOptions {
+        /// Optional properties and extra items are only generated up to this depth
+        pub maximum_depth: usize,
+        /// Numbers are generated as a multiple of one divided by this
+        pub number_precision: i64,
+        /// Strings are made of these characters, this should not be empty
+        pub string_characters: std::vec::Vec<char>,
+        pub default_minimum_value: i64,
+        pub default_maximum_value: i64,
+        pub default_minimum_items: usize,
+        pub default_maximum_items: usize,
+        pub default_minimum_properties: usize,
+        pub default_maximum_properties: usize,
+        pub default_minimum_string_length: usize,
+        pub default_maximum_string_length: usize,
+    }
+  });
+
+  tokens.append_all(quote! {
+    impl core::default::Default for MockOptions {
+        fn default() -> Self {
+            Self {
+              maximum_depth: 3,
+              number_precision: 1000,
+              string_characters: ('a'..='z').collect(),
+              default_minimum_value: -1000,
+              default_maximum_value: 1000,
+              default_minimum_items: 1,
+              default_maximum_items: 3,
+              default_minimum_properties: 1,
+              default_maximum_properties: 3,
+              default_minimum_string_length: 5,
+              default_maximum_string_length: 20,
+            }
+        }
+    }
+  });
+
+  tokens.append_all(quote! {
+    /// Keeps track of the seed and the depth while generating mocks. Mocks are deterministic,
+    /// the same seed and options always result in the same values.
+    #[derive(core::clone::Clone, core::fmt::Debug)]
+    pub struct MockContext {
+        seed: u64,
+        depth: usize,
+        options: MockOptions,
+    }
+  });
+
+  tokens.append_all(quote! {
+    impl MockContext {
+        pub fn new(seed: u64) -> Self {
+            Self::with_options(seed, MockOptions::default())
+        }
+
+        pub fn with_options(seed: u64, options: MockOptions) -> Self {
+            Self {
+              seed,
+              depth: 0,
+              options,
+            }
+        }
+
+        pub fn options(&self) -> &MockOptions {
+            &self.options
+        }
+
+        /// The next pseudo random value, every one of the 64 bits is random
+        pub fn next_seed(&mut self) -> u64 {
+            // https://prng.di.unimi.it/splitmix64.c
+            self.seed = self.seed.wrapping_add(0x9e3779b97f4a7c15);
+            let value = self.seed;
+            let value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+            let value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+            value ^ (value >> 31)
+        }
+
+        /// A value below `bound`, every value is equally likely. Values from the last,
+        /// incomplete, multiple of `bound` are skipped, they would favor the lower results.
+        pub fn next_below(&mut self, bound: u64) -> u64 {
+            if bound == 0 {
+              return 0;
+            }
+            let limit = u64::MAX - u64::MAX % bound;
+            loop {
+              let value = self.next_seed();
+              if value < limit {
+                return value % bound;
+              }
+            }
+        }
+
+        pub fn next_index(&mut self, length: usize) -> usize {
+            self.next_below(length as u64) as usize
+        }
+
+        /// A value between `minimum` and `maximum`, both inclusive
+        pub fn next_range(&mut self, minimum: i64, maximum: i64) -> i64 {
+            if maximum <= minimum {
+              return minimum;
+            }
+            match maximum.abs_diff(minimum).checked_add(1) {
+              core::option::Option::Some(range) => minimum.wrapping_add(self.next_below(range) as i64),
+              // every i64 is in the range
+              core::option::Option::None => self.next_seed() as i64,
+            }
+        }
+
+        /// A value between the bounds, if a bound is missing the default is used. A default
+        /// never conflicts with the other bound.
+        pub fn next_integer(
+          &mut self,
+          minimum: core::option::Option<i64>,
+          maximum: core::option::Option<i64>,
+          default_minimum: i64,
+          default_maximum: i64,
+        ) -> i64 {
+            let (minimum, maximum) = match (minimum, maximum) {
+              (core::option::Option::Some(minimum), core::option::Option::Some(maximum)) => (minimum, maximum),
+              (core::option::Option::Some(minimum), core::option::Option::None) => (minimum, default_maximum.max(minimum)),
+              (core::option::Option::None, core::option::Option::Some(maximum)) => (default_minimum.min(maximum), maximum),
+              (core::option::Option::None, core::option::Option::None) => (default_minimum, default_maximum),
+            };
+            self.next_range(minimum, maximum)
+        }
+
+        /// Like `next_integer`, but for counts of things
+        pub fn next_count(
+          &mut self,
+          minimum: core::option::Option<usize>,
+          maximum: core::option::Option<usize>,
+          default_minimum: usize,
+          default_maximum: usize,
+        ) -> usize {
+            self.next_integer(
+              minimum.map(|minimum| minimum as i64),
+              maximum.map(|maximum| maximum as i64),
+              default_minimum as i64,
+              default_maximum as i64,
+            ) as usize
+        }
+
+        pub fn next_string(&mut self, length: usize) -> std::string::String {
+            (0..length)
+              .map(|_| {
+                let index = self.next_index(self.options.string_characters.len());
+                self.options.string_characters[index]
+              })
+              .collect()
+        }
+
+        /// When we are too deep we only generate what is required
+        pub fn is_deep(&self) -> bool {
+            self.depth >= self.options.maximum_depth
+        }
+    }
+  });
+
+  for (key, item) in specification.arena.iter().enumerate() {
+    tokens.append_all(generate_mock_token_stream(specification, &key, item)?);
+  }
+
+  Ok(tokens)
+}
+
+pub fn get_mock_identifier(specification: &Specification, key: &usize) -> Option<Ident> {
+  let name = specification.get_snake_name(key)?;
+  Some(format_ident!("mock_{}", name))
+}
+
+fn generate_mock_token_stream(
+  specification: &Specification,
+  key: &usize,
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  if !specification.is_mockable(key) {
+    return Ok(quote! {});
+  }
+
+  let Some(mock_identifier) = get_mock_identifier(specification, key) else {
+    return Ok(quote! {});
+  };
+  let name = specification.get_name(key);
+
+  let documentation = format!(
+    "Generates a mock value for the {} type, the value is valid for the type.",
+    name.unwrap_or_default()
+  );
+
+  let definition_tokens = generate_mock_definition_token_stream(specification, key)?;
+
+  let mut tokens = quote! {
+    #[doc = #documentation]
+    pub fn #mock_identifier(context: &mut MockContext) -> serde_json::Value {
+      context.depth += 1;
+      let value = { #definition_tokens };
+      context.depth -= 1;
+      value
+    }
+  };
+
+  // aliases are the same type as the type they are referring to, so they already have a mock
+  // constructor
+  if item.reference.is_none() {
+    let type_identifier = specification.get_type_identifier(key);
+    tokens.append_all(quote! {
+      impl #type_identifier {
+          /// Generates a mock instance of this type, the same seed always results in the
+          /// same instance.
+          pub fn mock(seed: u64) -> core::result::Result<Self, crate::errors::ValidationErrors> {
+              Self::mock_with_context(&mut MockContext::new(seed))
+          }
+
+          pub fn mock_with_context(
+            context: &mut MockContext,
+          ) -> core::result::Result<Self, crate::errors::ValidationErrors> {
+              Self::from_json(&#mock_identifier(context), crate::errors::ValidationMode::All)
+          }
+      }
+    });
+  }
+
+  Ok(tokens)
+}
+
+/// Generates an expression that evaluates to a mock value for the item with `key`. The
+/// expression expects `context` to be the mock context.
+fn generate_mock_reference_token_stream(
+  specification: &Specification,
+  key: &usize,
+) -> Result<TokenStream, Box<dyn Error>> {
+  match get_mock_identifier(specification, key) {
+    Some(mock_identifier) => Ok(quote! { #mock_identifier(context) }),
+    None => {
+      let definition_tokens = generate_mock_definition_token_stream(specification, key)?;
+      Ok(quote! { { #definition_tokens } })
+    }
+  }
+}
+
+fn generate_mock_definition_token_stream(
+  specification: &Specification,
+  key: &usize,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let item = specification.arena.get_item(*key);
+
+  if let Some(reference) = &item.reference {
+    return generate_mock_reference_token_stream(specification, reference);
+  }
+
+  if let Some(options) = item.options.as_ref().filter(|options| !options.is_empty()) {
+    let options = options.iter().map(generate_json_token_stream);
+    return Ok(quote! {
+      let mut options = [#(#options),*];
+      let index = context.next_index(options.len());
+      options[index].take()
+    });
+  }
+
+  if let Some(one_of) = &item.one_of {
+    let mut cases_tokens = quote! {};
+    let mut index = 0usize;
+    for sub_key in one_of {
+      if !specification.is_mockable(sub_key) {
+        continue;
+      }
+      let sub_tokens = generate_mock_reference_token_stream(specification, sub_key)?;
+      cases_tokens.append_all(quote! {
+        #index => #sub_tokens,
+      });
+      index += 1;
+    }
+    let variant_tokens = quote! {
+      match context.next_index(#index) {
+        #cases_tokens
+        _ => unreachable!(),
+      }
+    };
+
+    // a mock of a variant may be valid for other variants too, then the value is not valid
+    // for the one of. We keep trying until we have a value that is valid.
+    let Some(type_identifier) = specification.get_type_identifier(key) else {
+      return Ok(variant_tokens);
+    };
+    return Ok(quote! {
+      let mut value = #variant_tokens;
+      for _ in 0..#ONE_OF_ATTEMPTS {
+        if #type_identifier::from_json(&value, crate::errors::ValidationMode::First).is_ok() {
+          break;
+        }
+        value = #variant_tokens;
+      }
+      value
+    });
+  }
+
+  let Some(r#type) = item
+    .types
+    .as_ref()
+    .filter(|types| types.len() == 1)
+    .and_then(|types| types.first())
+  else {
+    return Err("cannot mock an item without a single type".into());
+  };
+
+  let tokens = match r#type {
+    SchemaType::Never | SchemaType::Any => {
+      return Err("cannot mock never or any".into());
+    }
+    SchemaType::Null => quote! {
+      serde_json::Value::Null
+    },
+    SchemaType::Boolean => quote! {
+      serde_json::Value::Bool(context.next_seed() % 2 == 1)
+    },
+    SchemaType::Integer => {
+      let step = item
+        .multiple_of
+        .as_ref()
+        .map(Decimal::to_f64)
+        .unwrap_or(1.0);
+      let (minimum_tokens, maximum_tokens) = generate_integer_bounds_token_stream(item, step);
+      let step = step as i64;
+      quote! {
+        let index = context.next_integer(
+          #minimum_tokens,
+          #maximum_tokens,
+          (context.options.default_minimum_value as f64 / #step as f64).ceil() as i64,
+          (context.options.default_maximum_value as f64 / #step as f64).floor() as i64,
+        );
+        serde_json::Value::from(index * #step)
+      }
+    }
+    SchemaType::Number => {
+      if let Some(multiple_of) = &item.multiple_of {
+        let step = multiple_of.to_f64();
+        let (minimum_tokens, maximum_tokens) = generate_step_bounds_token_stream(item, step);
+        // exact decimals are multiplied exactly, so the mock is really a multiple
+        let value_tokens = match specification.number_type {
+          NumberType::F64 => quote! {
+            serde_json::Value::from(index as f64 * #step)
+          },
+          NumberType::Decimal => {
+            let step = specification
+              .number_type
+              .get_literal_token_stream(multiple_of);
+            quote! {
+              let value = bigdecimal::BigDecimal::from(index) * #step;
+              serde_json::Value::Number(value.normalized().to_string().parse().unwrap())
+            }
+          }
+        };
+        quote! {
+          let index = context.next_integer(
+            #minimum_tokens,
+            #maximum_tokens,
+            (context.options.default_minimum_value as f64 / #step).ceil() as i64,
+            (context.options.default_maximum_value as f64 / #step).floor() as i64,
+          );
+          #value_tokens
+        }
+      } else {
+        // the value is generated as an integer that is divided by the precision
+        let (minimum_tokens, maximum_tokens) = generate_bounds_token_stream(item, |value| {
+          quote! { #value * context.options.number_precision as f64 }
+        });
+        quote! {
+          let precision = context.options.number_precision;
+          let value = context.next_integer(
+            #minimum_tokens,
+            #maximum_tokens,
+            context.options.default_minimum_value * precision,
+            context.options.default_maximum_value * precision,
+          );
+          serde_json::Value::from(value as f64 / precision as f64)
+        }
+      }
+    }
+    SchemaType::String => {
+      let minimum_tokens = generate_count_token_stream(item.minimum_length);
+      let maximum_tokens = generate_count_token_stream(item.maximum_length);
+      let content_key = item
+        .get_json_content_schema()
+        .filter(|key| specification.get_type_identifier(key).is_some());
+
+      // the content is mocked and then encoded
+      let mut tokens = match content_key {
+        Some(content_key) => {
+          let content_tokens = generate_mock_reference_token_stream(specification, &content_key)?;
+          quote! {
+            let content = #content_tokens;
+            let string = content.to_string();
+          }
+        }
+        None => quote! {
+          let length = context.next_count(
+            #minimum_tokens,
+            #maximum_tokens,
+            context.options.default_minimum_string_length,
+            context.options.default_maximum_string_length,
+          );
+          let string = context.next_string(length);
+        },
+      };
+      if item.is_base64_content() {
+        tokens.append_all(quote! {
+          let string = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, string);
+        });
+      }
+      quote! {
+        #tokens
+        serde_json::Value::String(string)
+      }
+    }
+    SchemaType::Array => {
+      let mut elements_tokens = quote! {};
+      for tuple_items_key in item.tuple_items.iter().flatten() {
+        let element_tokens = generate_mock_reference_token_stream(specification, tuple_items_key)?;
+        elements_tokens.append_all(quote! {
+          elements.push(#element_tokens);
+        });
+      }
+
+      // tuples have exactly the tuple items, other arrays get elements of the items type or
+      // any value if there is no items type
+      let element_tokens = match (&item.tuple_items, &item.array_items) {
+        (_, Some(array_items_key)) => Some(generate_mock_reference_token_stream(
+          specification,
+          array_items_key,
+        )?),
+        (None, None) => Some(quote! { serde_json::Value::Null }),
+        (Some(_), None) => None,
+      };
+      if let Some(element_tokens) = element_tokens {
+        let tuple_items_length = item.tuple_items.as_ref().map(Vec::len).unwrap_or_default();
+        let minimum_tokens = generate_count_token_stream(item.minimum_items);
+        let maximum_tokens = generate_count_token_stream(item.maximum_items);
+        elements_tokens.append_all(quote! {
+          let count = if context.is_deep() {
+            #minimum_tokens.unwrap_or_default()
+          } else {
+            context.next_count(
+              #minimum_tokens,
+              #maximum_tokens,
+              context.options.default_minimum_items,
+              context.options.default_maximum_items,
+            )
+          };
+          for _ in #tuple_items_length..count {
+            elements.push(#element_tokens);
+          }
+        });
+      }
+
+      quote! {
+        let mut elements = std::vec::Vec::new();
+        #elements_tokens
+        serde_json::Value::Array(elements)
+      }
+    }
+    SchemaType::Object => {
+      let mut members_tokens = quote! {};
+
+      let required: BTreeSet<_> = item.required.iter().flatten().collect();
+      let object_properties = item.object_properties.clone().unwrap_or_default();
+      let property_names: BTreeSet<_> = object_properties.keys().chain(required.clone()).collect();
+
+      if property_names.is_empty() {
+        // a map, there are no properties that would drop members when round tripping
+        let member_tokens = match &item.map_properties {
+          Some(map_properties_key) => {
+            generate_mock_reference_token_stream(specification, map_properties_key)?
+          }
+          None => quote! { serde_json::Value::Null },
+        };
+        let name_tokens = match &item.property_names {
+          Some(property_names_key) => {
+            let property_name_tokens =
+              generate_mock_reference_token_stream(specification, property_names_key)?;
+            quote! {
+              match #property_name_tokens {
+                serde_json::Value::String(name) => name,
+                name => name.to_string(),
+              }
+            }
+          }
+          None => quote! {
+            let length = context.next_count(
+              core::option::Option::None,
+              core::option::Option::None,
+              context.options.default_minimum_string_length,
+              context.options.default_maximum_string_length,
+            );
+            context.next_string(length)
+          },
+        };
+        let minimum_tokens = generate_count_token_stream(item.minimum_properties);
+        let maximum_tokens = generate_count_token_stream(item.maximum_properties);
+
+        if item
+          .map_properties
+          .is_none_or(|key| specification.is_mockable(&key))
+        {
+          members_tokens.append_all(quote! {
+            let count = if context.is_deep() {
+              #minimum_tokens.unwrap_or_default()
+            } else {
+              context.next_count(
+                #minimum_tokens,
+                #maximum_tokens,
+                context.options.default_minimum_properties,
+                context.options.default_maximum_properties,
+              )
+            };
+            for _ in 0..count {
+              let name = { #name_tokens };
+              let member = #member_tokens;
+              members.insert(name, member);
+            }
+          });
+        }
+      }
+
+      for name in property_names {
+        match object_properties.get(name) {
+          None => {
+            members_tokens.append_all(quote! {
+              members.insert(#name.to_owned(), serde_json::Value::Null);
+            });
+          }
+          Some(object_properties_key) if required.contains(name) => {
+            let member_tokens =
+              generate_mock_reference_token_stream(specification, object_properties_key)?;
+            members_tokens.append_all(quote! {
+              members.insert(#name.to_owned(), #member_tokens);
+            });
+          }
+          Some(object_properties_key) => {
+            if !specification.is_mockable(object_properties_key) {
+              continue;
+            }
+            let member_tokens =
+              generate_mock_reference_token_stream(specification, object_properties_key)?;
+            members_tokens.append_all(quote! {
+              if !context.is_deep() {
+                members.insert(#name.to_owned(), #member_tokens);
+              }
+            });
+          }
+        }
+      }
+
+      // members that other members depend on are added, this is repeated so that the
+      // dependencies of added members are added too
+      if let Some(dependent_required) = &item.dependent_required {
+        let mut dependent_tokens = quote! {};
+        for (name, required) in dependent_required {
+          for required_name in required {
+            let member_tokens = match object_properties.get(required_name) {
+              Some(object_properties_key) => {
+                generate_mock_reference_token_stream(specification, object_properties_key)?
+              }
+              None => quote! { serde_json::Value::Null },
+            };
+            dependent_tokens.append_all(quote! {
+              if members.contains_key(#name) && !members.contains_key(#required_name) {
+                members.insert(#required_name.to_owned(), #member_tokens);
+              }
+            });
+          }
+        }
+        let count = dependent_required.len();
+        members_tokens.append_all(quote! {
+          for _ in 0..#count {
+            #dependent_tokens
+          }
+        });
+      }
+
+      if members_tokens.is_empty() {
+        quote! {
+          serde_json::Value::Object(serde_json::Map::new())
+        }
+      } else {
+        quote! {
+          let mut members = serde_json::Map::new();
+          #members_tokens
+          serde_json::Value::Object(members)
+        }
+      }
+    }
+  };
+
+  Ok(tokens)
+}
+
+fn generate_count_token_stream(value: Option<u32>) -> TokenStream {
+  match value {
+    Some(value) => {
+      let value = value as usize;
+      quote! { core::option::Option::<usize>::Some(#value) }
+    }
+    None => quote! { core::option::Option::<usize>::None },
+  }
+}
+
+/// Generates the bounds of the value, as optional integers. Every bound is scaled via `scale`
+/// first. Exclusive bounds are made inclusive.
+fn generate_bounds_token_stream(
+  item: &ArenaSchemaItem,
+  scale: impl Fn(f64) -> TokenStream,
+) -> (TokenStream, TokenStream) {
+  let mut minimum_tokens = Vec::new();
+  if let Some(value) = &item.minimum_inclusive {
+    let value = scale(value.to_f64());
+    minimum_tokens.push(quote! { (#value).ceil() as i64 });
+  }
+  if let Some(value) = &item.minimum_exclusive {
+    let value = scale(value.to_f64());
+    minimum_tokens.push(quote! { (#value).floor() as i64 + 1 });
+  }
+  let mut maximum_tokens = Vec::new();
+  if let Some(value) = &item.maximum_inclusive {
+    let value = scale(value.to_f64());
+    maximum_tokens.push(quote! { (#value).floor() as i64 });
+  }
+  if let Some(value) = &item.maximum_exclusive {
+    let value = scale(value.to_f64());
+    maximum_tokens.push(quote! { (#value).ceil() as i64 - 1 });
+  }
+
+  let minimum_tokens = match minimum_tokens.as_slice() {
+    [] => quote! { core::option::Option::<i64>::None },
+    [first, rest @ ..] => quote! { core::option::Option::Some(#first #(.max(#rest))*) },
+  };
+  let maximum_tokens = match maximum_tokens.as_slice() {
+    [] => quote! { core::option::Option::<i64>::None },
+    [first, rest @ ..] => quote! { core::option::Option::Some(#first #(.min(#rest))*) },
+  };
+
+  (minimum_tokens, maximum_tokens)
+}
+
+/// Like `generate_step_bounds_token_stream`, but for integers. Those are also bounded by
+/// formats like `int8`.
+fn generate_integer_bounds_token_stream(
+  item: &ArenaSchemaItem,
+  step: f64,
+) -> (TokenStream, TokenStream) {
+  let minimum_tokens = match get_integer_minimum(item) {
+    Some(IntegerBound { value, .. }) => {
+      let value = value.to_f64();
+      quote! { core::option::Option::Some((#value / #step).ceil() as i64) }
+    }
+    None => quote! { core::option::Option::<i64>::None },
+  };
+  let maximum_tokens = match get_integer_maximum(item) {
+    Some(IntegerBound { value, .. }) => {
+      let value = value.to_f64();
+      quote! { core::option::Option::Some((#value / #step).floor() as i64) }
+    }
+    None => quote! { core::option::Option::<i64>::None },
+  };
+
+  (minimum_tokens, maximum_tokens)
+}
+
+/// Like `generate_bounds_token_stream`, but the bounds are for the number of times `step`
+/// fits in the value.
+fn generate_step_bounds_token_stream(
+  item: &ArenaSchemaItem,
+  step: f64,
+) -> (TokenStream, TokenStream) {
+  generate_bounds_token_stream(item, |value| quote! { #value / #step })
+}
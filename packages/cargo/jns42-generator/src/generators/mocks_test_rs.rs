@@ -1,3 +1,4 @@
+use super::mocks_rs::get_mock_identifier;
 use crate::models::Specification;
 use jns42_core::models::ArenaSchemaItem;
 use proc_macro2::TokenStream;
@@ -10,7 +11,7 @@ pub fn generate_file_token_stream(
   let mut tokens = quote! {};
 
   for (key, item) in specification.arena.iter().enumerate() {
-    tokens.append_all(generate_test_token_stream(specification, &key, item));
+    tokens.append_all(generate_test_token_stream(specification, &key, item)?);
   }
 
   Ok(tokens)
@@ -19,18 +20,39 @@ pub fn generate_file_token_stream(
 fn generate_test_token_stream(
   specification: &Specification,
   key: &usize,
-  _item: &ArenaSchemaItem,
+  item: &ArenaSchemaItem,
 ) -> Result<TokenStream, Box<dyn Error>> {
   let mut tokens = quote! {};
 
-  let Some(identifier) = specification.get_snake_identifier(key) else {
+  if !specification.is_mockable(key) || item.location.is_none() {
+    return Ok(quote! {});
+  }
+
+  let (Some(identifier), Some(type_identifier), Some(mock_identifier)) = (
+    specification.get_snake_identifier(key),
+    specification.get_type_identifier(key),
+    get_mock_identifier(specification, key),
+  ) else {
     return Ok(quote! {});
   };
 
+  // every mock should be valid and should survive a round trip via serde
   tokens.append_all(quote! {
-    // #[test]
-    fn #identifier() {
+    #[test]
+    fn #identifier() -> core::result::Result<(), std::boxed::Box<dyn std::error::Error>> {
+      for seed in 0..16 {
+        let mut context = crate::mocks::MockContext::new(seed);
+        let value = crate::mocks::#mock_identifier(&mut context);
+
+        let instance = #type_identifier::from_json(&value, crate::errors::ValidationMode::All)?;
+        let data = serde_json::to_string(&instance)?;
+        let instance = serde_json::from_str::<#type_identifier>(&data)?;
+
+        // maps have no order, so we compare values
+        assert_eq!(serde_json::to_value(&instance)?, serde_json::from_str::<serde_json::Value>(&data)?);
+      }
 
+      core::result::Result::Ok(())
     }
   });
 
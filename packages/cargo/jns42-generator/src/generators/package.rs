@@ -27,14 +27,23 @@ pub async fn generate_package(
   fs::create_dir_all(root_path).await?;
   fs::create_dir_all(src_path).await?;
 
-  let content = super::cargo_toml::generate_file_content(package_name, package_version)?;
+  let content =
+    super::cargo_toml::generate_file_content(package_name, package_version, specification)?;
   fs::write(root_path.join("Cargo.toml"), content).await?;
 
+  // the simplified schema that the code is generated from
+  let entry_key = specification
+    .get_key(entry_location)
+    .ok_or("entry location not found")?;
+  let content = serde_json::to_string_pretty(&specification.get_document(&entry_key))?;
+  fs::write(root_path.join("schema.json"), content).await?;
+
   let tokens = super::lib_rs::generate_file_token_stream(specification)?;
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("lib.rs"), content).await?;
 
-  let tokens = super::main_rs::generate_file_token_stream(specification, entry_location)?;
+  let tokens =
+    super::main_rs::generate_file_token_stream(specification, package_name, entry_location)?;
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("main.rs"), content).await?;
 
@@ -42,6 +51,12 @@ pub async fn generate_package(
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("errors.rs"), content).await?;
 
+  if specification.has_formats() {
+    let tokens = super::formats_rs::generate_file_token_stream(specification)?;
+    let content = super::file::generate_file_content(tokens)?;
+    fs::write(src_path.join("formats.rs"), content).await?;
+  }
+
   let tokens = super::types_rs::generate_file_token_stream(specification)?;
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("types.rs"), content).await?;
@@ -50,6 +65,14 @@ pub async fn generate_package(
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("interiors.rs"), content).await?;
 
+  let tokens = super::parsers_rs::generate_file_token_stream(specification)?;
+  let content = super::file::generate_file_content(tokens)?;
+  fs::write(src_path.join("parsers.rs"), content).await?;
+
+  let tokens = super::mocks_rs::generate_file_token_stream(specification)?;
+  let content = super::file::generate_file_content(tokens)?;
+  fs::write(src_path.join("mocks.rs"), content).await?;
+
   let tokens = super::examples_test_rs::generate_file_token_stream(specification)?;
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("examples_test.rs"), content).await?;
@@ -58,5 +81,23 @@ pub async fn generate_package(
   let content = super::file::generate_file_content(tokens)?;
   fs::write(src_path.join("mocks_test.rs"), content).await?;
 
+  if !specification.operations.is_empty() {
+    let tokens = super::client_rs::generate_file_token_stream(specification)?;
+    let content = super::file::generate_file_content(tokens)?;
+    fs::write(src_path.join("client.rs"), content).await?;
+
+    let tokens = super::client_test_rs::generate_file_token_stream(specification)?;
+    let content = super::file::generate_file_content(tokens)?;
+    fs::write(src_path.join("client_test.rs"), content).await?;
+
+    let tokens = super::server_rs::generate_file_token_stream(specification)?;
+    let content = super::file::generate_file_content(tokens)?;
+    fs::write(src_path.join("server.rs"), content).await?;
+
+    let tokens = super::server_test_rs::generate_file_token_stream(specification)?;
+    let content = super::file::generate_file_content(tokens)?;
+    fs::write(src_path.join("server_test.rs"), content).await?;
+  }
+
   Ok(())
 }
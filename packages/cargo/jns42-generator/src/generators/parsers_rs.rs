@@ -0,0 +1,280 @@
+use crate::models::Specification;
+use jns42_core::models::{ArenaSchemaItem, SchemaType};
+use proc_macro2::{Ident, TokenStream, TokenTree};
+use quote::{TokenStreamExt, format_ident, quote};
+use std::error::Error;
+
+pub fn generate_file_token_stream(
+  specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  tokens.append_all(quote! {
+    /// Parsing turns strings into the types of the schema, this is how values arrive from
+    /// query strings, form data or environment variables.
+    #[derive(core::clone::Clone, core::fmt::Debug)]
+    pub struct ParserOptions {
+        pub true_string_values: std::vec::Vec<std::string::String>,
+        pub false_string_values: std::vec::Vec<std::string::String>,
+    }
+  });
+
+  tokens.append_all(quote! {
+    impl core::default::Default for ParserOptions {
+        fn default() -> Self {
+            Self {
+              true_string_values: ["", "true", "yes", "on", "1"].map(str::to_owned).into(),
+              false_string_values: ["false", "no", "off", "0"].map(str::to_owned).into(),
+            }
+        }
+    }
+  });
+
+  for (key, item) in specification.arena.iter().enumerate() {
+    tokens.append_all(generate_parser_token_stream(specification, &key, item)?);
+  }
+
+  Ok(tokens)
+}
+
+pub fn get_parser_identifier(specification: &Specification, key: &usize) -> Option<Ident> {
+  let name = specification.get_snake_name(key)?;
+  Some(format_ident!("parse_{}", name))
+}
+
+fn generate_parser_token_stream(
+  specification: &Specification,
+  key: &usize,
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let Some(parser_identifier) = get_parser_identifier(specification, key) else {
+    return Ok(quote! {});
+  };
+  let name = specification.get_name(key);
+
+  let documentation = format!(
+    "Parses a value for the {} type, returns `None` if the value cannot be parsed.",
+    name.unwrap_or_default()
+  );
+
+  let parse_reference =
+    |key: &usize, value: TokenStream| match get_parser_identifier(specification, key) {
+      Some(parser_identifier) => quote! { #parser_identifier(#value, options) },
+      None => quote! { core::option::Option::Some(#value.clone()) },
+    };
+
+  let body_tokens = if let Some(reference) = &item.reference {
+    parse_reference(reference, quote! { value })
+  } else if let Some(one_of) = &item.one_of {
+    // the first variant that is valid after parsing wins, if none of them are valid we take
+    // whatever parsed first
+    let mut variants_tokens = quote! {};
+    for sub_key in one_of {
+      let Some(sub_parser_identifier) = get_parser_identifier(specification, sub_key) else {
+        continue;
+      };
+      let sub_type_identifier = specification.get_type_identifier(sub_key);
+      variants_tokens.append_all(quote! {
+        if let core::option::Option::Some(variant) = #sub_parser_identifier(value, options) {
+          if #sub_type_identifier::from_json(&variant, crate::errors::ValidationMode::First).is_ok() {
+            return core::option::Option::Some(variant);
+          }
+          parsed = parsed.or(core::option::Option::Some(variant));
+        }
+      });
+    }
+    quote! {
+      let mut parsed = core::option::Option::None;
+      #variants_tokens
+      parsed
+    }
+  } else if let Some(r#type) = item
+    .types
+    .as_ref()
+    .filter(|types| types.len() == 1)
+    .and_then(|types| types.first())
+  {
+    match r#type {
+      SchemaType::Never | SchemaType::Any => quote! {
+        core::option::Option::Some(value.clone())
+      },
+      SchemaType::Null => quote! {
+        match value {
+          serde_json::Value::Null => core::option::Option::Some(serde_json::Value::Null),
+          serde_json::Value::Array(elements) if elements.is_empty() => {
+            core::option::Option::Some(serde_json::Value::Null)
+          }
+          serde_json::Value::Array(elements) if elements.len() == 1 => {
+            #parser_identifier(&elements[0], options)
+          }
+          serde_json::Value::String(value) if value.trim().is_empty() => {
+            core::option::Option::Some(serde_json::Value::Null)
+          }
+          _ => core::option::Option::None,
+        }
+      },
+      SchemaType::Boolean => quote! {
+        match value {
+          serde_json::Value::Null => core::option::Option::Some(serde_json::Value::Bool(false)),
+          serde_json::Value::Array(elements) if elements.is_empty() => {
+            core::option::Option::Some(serde_json::Value::Bool(false))
+          }
+          serde_json::Value::Array(elements) if elements.len() == 1 => {
+            #parser_identifier(&elements[0], options)
+          }
+          serde_json::Value::Bool(value) => core::option::Option::Some(serde_json::Value::Bool(*value)),
+          serde_json::Value::Number(value) => {
+            core::option::Option::Some(serde_json::Value::Bool(value.as_f64() != core::option::Option::Some(0.0)))
+          }
+          serde_json::Value::String(value) => {
+            let value = value.trim();
+            if options.true_string_values.iter().any(|true_string_value| true_string_value == value) {
+              core::option::Option::Some(serde_json::Value::Bool(true))
+            } else if options.false_string_values.iter().any(|false_string_value| false_string_value == value) {
+              core::option::Option::Some(serde_json::Value::Bool(false))
+            } else {
+              core::option::Option::None
+            }
+          }
+          _ => core::option::Option::None,
+        }
+      },
+      SchemaType::Integer | SchemaType::Number => quote! {
+        match value {
+          serde_json::Value::Array(elements) if elements.len() == 1 => {
+            #parser_identifier(&elements[0], options)
+          }
+          serde_json::Value::Number(_) => core::option::Option::Some(value.clone()),
+          serde_json::Value::Bool(value) => core::option::Option::Some(serde_json::Value::from(*value as i64)),
+          serde_json::Value::String(value) => {
+            let value = value.trim();
+            // integers are parsed as integers so they do not lose precision
+            value
+              .parse::<i64>()
+              .ok()
+              .map(serde_json::Value::from)
+              .or_else(|| {
+                value
+                  .parse::<f64>()
+                  .ok()
+                  .and_then(serde_json::Number::from_f64)
+                  .map(serde_json::Value::Number)
+              })
+          }
+          _ => core::option::Option::None,
+        }
+      },
+      SchemaType::String => quote! {
+        match value {
+          serde_json::Value::Array(elements) if elements.len() == 1 => {
+            #parser_identifier(&elements[0], options)
+          }
+          serde_json::Value::String(_) => core::option::Option::Some(value.clone()),
+          serde_json::Value::Number(value) => core::option::Option::Some(serde_json::Value::String(value.to_string())),
+          serde_json::Value::Bool(value) => core::option::Option::Some(serde_json::Value::String(value.to_string())),
+          _ => core::option::Option::None,
+        }
+      },
+      SchemaType::Array => {
+        let mut cases_tokens = quote! {};
+        for (index, tuple_items_key) in item.tuple_items.iter().flatten().enumerate() {
+          let element_tokens = parse_reference(tuple_items_key, quote! { element });
+          cases_tokens.append_all(quote! {
+            #index => #element_tokens,
+          });
+        }
+        let default_tokens = match &item.array_items {
+          Some(array_items_key) => parse_reference(array_items_key, quote! { element }),
+          None => quote! { core::option::Option::Some(element.clone()) },
+        };
+
+        // a single value is an array with one element
+        quote! {
+          let elements = match value {
+            serde_json::Value::Array(elements) => elements.as_slice(),
+            value => std::slice::from_ref(value),
+          };
+          core::option::Option::Some(serde_json::Value::Array(
+            elements
+              .iter()
+              .enumerate()
+              .map(|(index, element)| {
+                match index {
+                  #cases_tokens
+                  _ => #default_tokens,
+                }
+                .unwrap_or_else(|| element.clone())
+              })
+              .collect(),
+          ))
+        }
+      }
+      SchemaType::Object => {
+        let mut cases_tokens = quote! {};
+        for (name, object_properties_key) in item.object_properties.iter().flatten() {
+          let member_tokens = parse_reference(object_properties_key, quote! { member });
+          cases_tokens.append_all(quote! {
+            #name => #member_tokens,
+          });
+        }
+        let mut default_tokens = quote! { core::option::Option::None };
+        for sub_key in item
+          .map_properties
+          .iter()
+          .chain(item.pattern_properties.iter().flatten().map(|(_, key)| key))
+        {
+          let member_tokens = parse_reference(sub_key, quote! { member });
+          default_tokens = quote! { #default_tokens.or_else(|| #member_tokens) };
+        }
+
+        quote! {
+          let serde_json::Value::Object(members) = value else {
+            return core::option::Option::None;
+          };
+          core::option::Option::Some(serde_json::Value::Object(
+            members
+              .iter()
+              .map(|(name, member)| {
+                let member = match name.as_str() {
+                  #cases_tokens
+                  _ => #default_tokens,
+                }
+                .unwrap_or_else(|| member.clone());
+                (name.clone(), member)
+              })
+              .collect(),
+          ))
+        }
+      }
+    }
+  } else {
+    quote! {
+      core::option::Option::Some(value.clone())
+    }
+  };
+
+  // the options are only used if a sub schema has a parser
+  let options_identifier = if contains_identifier(body_tokens.clone(), "options") {
+    format_ident!("options")
+  } else {
+    format_ident!("_options")
+  };
+
+  Ok(quote! {
+    #[doc = #documentation]
+    pub fn #parser_identifier(
+      value: &serde_json::Value,
+      #options_identifier: &ParserOptions,
+    ) -> core::option::Option<serde_json::Value> {
+      #body_tokens
+    }
+  })
+}
+
+fn contains_identifier(tokens: TokenStream, identifier: &str) -> bool {
+  tokens.into_iter().any(|tree| match tree {
+    TokenTree::Ident(ident) => ident == identifier,
+    TokenTree::Group(group) => contains_identifier(group.stream(), identifier),
+    _ => false,
+  })
+}
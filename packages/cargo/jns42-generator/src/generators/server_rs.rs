@@ -0,0 +1,568 @@
+use super::client_rs::{
+  generate_arguments_token_stream, generate_response_token_stream, get_header_identifier,
+  get_headers_identifier, get_location_name, get_operation_identifier, get_parameter_identifiers,
+  get_response_identifier, get_response_variant_identifier, is_exact_status,
+};
+use super::parsers_rs::get_parser_identifier;
+use crate::models::Specification;
+use jns42_core::models::{ApiBody, ApiOperation, ApiParameterLocation, ApiResponse};
+use jns42_core::utilities::NodeLocation;
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::{TokenStreamExt, format_ident, quote};
+use std::error::Error;
+
+pub fn generate_file_token_stream(
+  specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  tokens.append_all(quote! {
+    pub type ServerResponse = hyper::Response<http_body_util::Full<hyper::body::Bytes>>;
+
+    /// Errors from the implementation of an operation, these result in a 500 response
+    pub type ServerError = std::boxed::Box<dyn std::error::Error + core::marker::Send + core::marker::Sync>;
+
+    /// A response without a body
+    pub fn status_response(status: u16) -> ServerResponse {
+      let mut response = hyper::Response::new(http_body_util::Full::default());
+      *response.status_mut() =
+        hyper::StatusCode::from_u16(status).unwrap_or(hyper::StatusCode::INTERNAL_SERVER_ERROR);
+      response
+    }
+
+    /// A response with the value as body, strings are sent as they are if the body is not
+    /// json.
+    pub fn to_response(
+      status: u16,
+      content_type: &str,
+      is_json: bool,
+      value: &impl serde::Serialize,
+    ) -> core::result::Result<ServerResponse, ServerError> {
+      let data = if is_json {
+        serde_json::to_vec(value)?
+      } else {
+        match serde_json::to_value(value)? {
+          serde_json::Value::String(value) => value.into_bytes(),
+          value => value.to_string().into_bytes(),
+        }
+      };
+
+      let response = hyper::Response::builder()
+        .status(hyper::StatusCode::from_u16(status)?)
+        .header("content-type", content_type)
+        .body(http_body_util::Full::new(hyper::body::Bytes::from(data)))?;
+      core::result::Result::Ok(response)
+    }
+
+    /// Header values are serialized like parameters, strings as they are and other values as
+    /// json.
+    pub fn to_header_value(
+      value: &impl serde::Serialize,
+    ) -> core::result::Result<hyper::header::HeaderValue, ServerError> {
+      let value = match serde_json::to_value(value)? {
+        serde_json::Value::String(value) => value,
+        value => value.to_string(),
+      };
+      core::result::Result::Ok(hyper::header::HeaderValue::from_str(&value)?)
+    }
+
+    /// A 400 response with the validation errors as json
+    pub fn bad_request(errors: crate::errors::ValidationErrors) -> ServerResponse {
+      to_response(400, "application/json", true, &errors).unwrap_or_else(|_error| status_response(400))
+    }
+
+    /// One value is a string and more values are an array, so parsers can handle both
+    pub fn to_parameter_value(values: std::vec::Vec<std::string::String>) -> core::option::Option<serde_json::Value> {
+      let mut values: std::vec::Vec<_> = values.into_iter().map(serde_json::Value::String).collect();
+      match values.len() {
+        0 | 1 => values.pop(),
+        _ => core::option::Option::Some(serde_json::Value::Array(values)),
+      }
+    }
+
+    pub fn query_values(query: &[(std::string::String, std::string::String)], name: &str) -> std::vec::Vec<std::string::String> {
+      query
+        .iter()
+        .filter(|(key, _)| key == name)
+        .map(|(_, value)| value.clone())
+        .collect()
+    }
+
+    pub fn header_values(headers: &hyper::HeaderMap, name: &str) -> std::vec::Vec<std::string::String> {
+      headers
+        .get_all(name)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .map(str::to_owned)
+        .collect()
+    }
+
+    pub fn cookie_values(headers: &hyper::HeaderMap, name: &str) -> std::vec::Vec<std::string::String> {
+      header_values(headers, "cookie")
+        .iter()
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .filter(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_owned())
+        .collect()
+    }
+  });
+
+  let mut methods_tokens = quote! {};
+  let mut routes_tokens = quote! {};
+  let mut handlers_tokens = quote! {};
+  for operation in &specification.operations {
+    tokens.append_all(generate_response_token_stream(specification, operation)?);
+    tokens.append_all(generate_into_response_token_stream(operation));
+    methods_tokens.append_all(generate_method_token_stream(specification, operation));
+    routes_tokens.append_all(generate_route_token_stream(operation));
+    handlers_tokens.append_all(generate_handler_token_stream(specification, operation));
+  }
+
+  tokens.append_all(quote! {
+    /// Implements the operations of the api, every operation is a method.
+    pub trait Server: core::marker::Send + core::marker::Sync {
+      #methods_tokens
+    }
+
+    /// Routes requests to the operations of the server. Parameters and bodies are parsed and
+    /// validated, invalid requests get a 400 response with the validation errors.
+    #[derive(core::fmt::Debug)]
+    pub struct Router<S> {
+      server: S,
+      base_path: std::string::String,
+      parser_options: crate::parsers::ParserOptions,
+    }
+
+    impl<S: Server> Router<S> {
+      pub fn new(server: S) -> Self {
+        Self {
+          server,
+          base_path: std::string::String::new(),
+          parser_options: core::default::Default::default(),
+        }
+      }
+
+      /// Only handle requests with a path that starts with `base_path`, the operations are
+      /// relative to it.
+      pub fn with_base_path(mut self, base_path: impl std::string::ToString) -> Self {
+        self.base_path = base_path.to_string().trim_end_matches('/').to_owned();
+        self
+      }
+
+      pub fn with_parser_options(mut self, parser_options: crate::parsers::ParserOptions) -> Self {
+        self.parser_options = parser_options;
+        self
+      }
+
+      pub fn server(&self) -> &S {
+        &self.server
+      }
+
+      /// Handle a request, this never fails so it can be used with `hyper::service::service_fn`.
+      pub async fn handle<B: hyper::body::Body>(
+        &self,
+        request: hyper::Request<B>,
+      ) -> core::result::Result<ServerResponse, core::convert::Infallible> {
+        let core::option::Option::Some(path) = request.uri().path().strip_prefix(self.base_path.as_str()) else {
+          return core::result::Result::Ok(status_response(404));
+        };
+        let path = path.to_owned();
+
+        // if the path matches an operation but the method does not we respond with a 405
+        let mut path_found = false;
+        #routes_tokens
+
+        core::result::Result::Ok(status_response(if path_found { 405 } else { 404 }))
+      }
+
+      #handlers_tokens
+    }
+  });
+
+  Ok(tokens)
+}
+
+fn get_handler_identifier(operation: &ApiOperation) -> proc_macro2::Ident {
+  format_ident!("r#handle_{}", get_operation_identifier(operation))
+}
+
+fn generate_method_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> TokenStream {
+  let operation_identifier = get_operation_identifier(operation);
+  let response_identifier = get_response_identifier(operation);
+  let arguments_tokens = generate_arguments_token_stream(specification, operation);
+
+  let doc = format!("{} {}", operation.method.to_uppercase(), operation.path);
+  quote! {
+    #[doc = #doc]
+    fn #operation_identifier(
+      &self,
+      #arguments_tokens
+    ) -> impl core::future::Future<Output = core::result::Result<#response_identifier, ServerError>> + core::marker::Send;
+  }
+}
+
+fn generate_into_response_token_stream(operation: &ApiOperation) -> TokenStream {
+  let mut tokens = quote! {};
+  let response_identifier = get_response_identifier(operation);
+
+  let mut arms_tokens = quote! {};
+  for response in &operation.responses {
+    let variant_identifier = get_response_variant_identifier(response);
+    let body = response.body.as_ref().filter(|body| body.schema.is_some());
+    let headers_identifier = get_headers_identifier(operation, response);
+
+    let mut fields_tokens = Vec::new();
+    let status_tokens = match response.get_status_range() {
+      Some((minimum, _)) if is_exact_status(response) => {
+        let status = Literal::u16_unsuffixed(minimum);
+        quote! { #status }
+      }
+      _ => {
+        fields_tokens.push(quote! { status });
+        quote! { status }
+      }
+    };
+    if body.is_some() {
+      fields_tokens.push(quote! { value });
+    }
+    if headers_identifier.is_some() {
+      fields_tokens.push(quote! { headers });
+    }
+
+    let pattern_tokens = if fields_tokens.is_empty() {
+      quote! {}
+    } else {
+      quote! { (#(#fields_tokens),*) }
+    };
+
+    let result_tokens = match body {
+      Some(body) => {
+        let content_type = &body.content_type;
+        let is_json = body.is_json();
+        quote! { to_response(#status_tokens, #content_type, #is_json, &value) }
+      }
+      None => quote! { core::result::Result::Ok(status_response(#status_tokens)) },
+    };
+
+    let result_tokens = match headers_identifier {
+      Some(headers_identifier) => {
+        tokens.append_all(generate_write_headers_token_stream(
+          response,
+          &headers_identifier,
+        ));
+        quote! {
+          {
+            let mut response = #result_tokens?;
+            headers.write(response.headers_mut())?;
+            core::result::Result::Ok(response)
+          }
+        }
+      }
+      None => result_tokens,
+    };
+
+    arms_tokens.append_all(quote! {
+      Self::#variant_identifier #pattern_tokens => #result_tokens,
+    });
+  }
+
+  tokens.append_all(quote! {
+    impl #response_identifier {
+      pub fn into_response(self) -> core::result::Result<ServerResponse, ServerError> {
+        match self {
+          #arms_tokens
+        }
+      }
+    }
+  });
+
+  tokens
+}
+
+fn generate_write_headers_token_stream(
+  response: &ApiResponse,
+  headers_identifier: &Ident,
+) -> TokenStream {
+  let mut inserts_tokens = quote! {};
+  for header in &response.headers {
+    let name = header.name.to_lowercase();
+    let header_identifier = get_header_identifier(header);
+    let insert_tokens = quote! {
+      headers.insert(hyper::header::HeaderName::try_from(#name)?, to_header_value(value)?);
+    };
+    inserts_tokens.append_all(if header.required {
+      quote! {
+        let value = &self.#header_identifier;
+        #insert_tokens
+      }
+    } else {
+      quote! {
+        if let core::option::Option::Some(value) = &self.#header_identifier {
+          #insert_tokens
+        }
+      }
+    });
+  }
+
+  quote! {
+    impl #headers_identifier {
+      pub fn write(&self, headers: &mut hyper::HeaderMap) -> core::result::Result<(), ServerError> {
+        #inserts_tokens
+        core::result::Result::Ok(())
+      }
+    }
+  }
+}
+
+/// Matches the path of the request against the path template of the operation, path
+/// parameters become named groups.
+fn generate_route_token_stream(operation: &ApiOperation) -> TokenStream {
+  let parameter_identifiers = get_parameter_identifiers(operation);
+  let mut pattern = "^".to_owned();
+  let mut rest = operation.path.as_str();
+  while let Some(start) = rest.find('{') {
+    let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+      break;
+    };
+    let name = &rest[start + 1..end];
+    pattern += &regex::escape(&rest[..start]);
+    match operation
+      .parameters
+      .iter()
+      .zip(&parameter_identifiers)
+      .find(|(parameter, _)| {
+        parameter.location == ApiParameterLocation::Path && parameter.name == name
+      }) {
+      Some((_, parameter_identifier)) => {
+        pattern += &format!(
+          "(?P<{}>[^/]+)",
+          parameter_identifier.to_string().trim_start_matches("r#")
+        )
+      }
+      None => pattern += &regex::escape(&rest[start..=end]),
+    }
+    rest = &rest[end + 1..];
+  }
+  pattern += &regex::escape(rest);
+  pattern += "$";
+
+  let handler_identifier = get_handler_identifier(operation);
+  let method_identifier = format_ident!("{}", operation.method.to_uppercase());
+
+  quote! {
+    {
+      static PATH: std::sync::LazyLock<regex::Regex> =
+        std::sync::LazyLock::new(|| regex::Regex::new(#pattern).unwrap());
+      if let core::option::Option::Some(captures) = PATH.captures(&path) {
+        path_found = true;
+        if request.method() == hyper::Method::#method_identifier {
+          return self.#handler_identifier(&captures, request).await;
+        }
+      }
+    }
+  }
+}
+
+fn generate_handler_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> TokenStream {
+  let handler_identifier = get_handler_identifier(operation);
+  let operation_identifier = get_operation_identifier(operation);
+  let parameter_identifiers = get_parameter_identifiers(operation);
+
+  let mut parse_tokens = quote! {};
+  let mut argument_identifiers = Vec::new();
+  let mut argument_values_tokens = Vec::new();
+
+  let has_path = operation
+    .parameters
+    .iter()
+    .any(|parameter| parameter.location == ApiParameterLocation::Path);
+  let captures_identifier = if has_path {
+    format_ident!("captures")
+  } else {
+    format_ident!("_captures")
+  };
+
+  if operation
+    .parameters
+    .iter()
+    .any(|parameter| parameter.location == ApiParameterLocation::Query)
+  {
+    parse_tokens.append_all(quote! {
+      let query: std::vec::Vec<(std::string::String, std::string::String)> =
+        form_urlencoded::parse(request.uri().query().unwrap_or_default().as_bytes())
+          .into_owned()
+          .collect();
+    });
+  }
+
+  for (parameter, parameter_identifier) in operation.parameters.iter().zip(&parameter_identifiers) {
+    let name = &parameter.name;
+    let location_name = get_location_name(parameter.location);
+    let source_tokens = match parameter.location {
+      ApiParameterLocation::Path => {
+        let group = parameter_identifier.to_string();
+        let group = group.trim_start_matches("r#");
+        quote! {
+          #captures_identifier
+            .name(#group)
+            .map(|value| percent_encoding::percent_decode_str(value.as_str()).decode_utf8_lossy().into_owned())
+            .into_iter()
+            .collect()
+        }
+      }
+      ApiParameterLocation::Query => quote! { query_values(&query, #name) },
+      ApiParameterLocation::Header => quote! { header_values(request.headers(), #name) },
+      ApiParameterLocation::Cookie => quote! { cookie_values(request.headers(), #name) },
+    };
+    let convert_tokens = generate_convert_token_stream(specification, parameter.schema.as_ref());
+    let missing_tokens = generate_missing_token_stream(name, parameter.required);
+
+    parse_tokens.append_all(quote! {
+      context.push_path(#location_name);
+      context.push_path(#name);
+      let #parameter_identifier = match to_parameter_value(#source_tokens) {
+        core::option::Option::Some(value) => #convert_tokens,
+        core::option::Option::None => {
+          #missing_tokens
+          core::option::Option::None
+        }
+      };
+      context.pop_path();
+      context.pop_path();
+    });
+
+    argument_identifiers.push(parameter_identifier.clone());
+    argument_values_tokens.push(if parameter.required {
+      quote! { #parameter_identifier? }
+    } else {
+      quote! { #parameter_identifier }
+    });
+  }
+
+  if let Some(request_body) = &operation.request_body {
+    parse_tokens.append_all(generate_body_token_stream(specification, request_body));
+
+    let body_identifier = format_ident!("body");
+    argument_values_tokens.push(if request_body.required {
+      quote! { #body_identifier? }
+    } else {
+      quote! { #body_identifier }
+    });
+    argument_identifiers.push(body_identifier);
+  }
+
+  quote! {
+    async fn #handler_identifier<B: hyper::body::Body>(
+      &self,
+      #captures_identifier: &regex::Captures<'_>,
+      request: hyper::Request<B>,
+    ) -> core::result::Result<ServerResponse, core::convert::Infallible> {
+      let mut context = crate::errors::ValidationContext::new(crate::errors::ValidationMode::All);
+
+      #parse_tokens
+
+      // every required argument is there if there are no errors
+      let arguments = (|| core::option::Option::Some((#(#argument_values_tokens,)*)))();
+      let (#(#argument_identifiers,)*) = match context.into_result(arguments) {
+        core::result::Result::Ok(arguments) => arguments,
+        core::result::Result::Err(errors) => return core::result::Result::Ok(bad_request(errors)),
+      };
+
+      let response = self
+        .server
+        .#operation_identifier(#(#argument_identifiers),*)
+        .await
+        .and_then(|response| response.into_response());
+      core::result::Result::Ok(response.unwrap_or_else(|_error| status_response(500)))
+    }
+  }
+}
+
+fn generate_body_token_stream(
+  specification: &Specification,
+  request_body: &ApiBody,
+) -> TokenStream {
+  let content_type = &request_body.content_type;
+  let convert_tokens = generate_convert_token_stream(specification, request_body.schema.as_ref());
+  let missing_tokens = generate_missing_token_stream("body", request_body.required);
+
+  let decode_tokens = if request_body.is_json() {
+    quote! {
+      match serde_json::from_slice::<serde_json::Value>(&data) {
+        core::result::Result::Ok(value) => core::option::Option::Some(value),
+        core::result::Result::Err(_error) => {
+          context.record("body", core::option::Option::None, "contentMediaType", serde_json::Value::String(#content_type.to_owned()));
+          core::option::Option::None
+        }
+      }
+    }
+  } else {
+    quote! {
+      core::option::Option::Some(serde_json::Value::String(std::string::String::from_utf8_lossy(&data).into_owned()))
+    }
+  };
+
+  quote! {
+    let data = match http_body_util::BodyExt::collect(request.into_body()).await {
+      core::result::Result::Ok(collected) => collected.to_bytes(),
+      core::result::Result::Err(_error) => return core::result::Result::Ok(status_response(400)),
+    };
+    context.push_path("body");
+    let body = if data.is_empty() {
+      #missing_tokens
+      core::option::Option::None
+    } else {
+      let value = #decode_tokens;
+      match value {
+        core::option::Option::Some(value) => #convert_tokens,
+        core::option::Option::None => core::option::Option::None,
+      }
+    };
+    context.pop_path();
+  }
+}
+
+/// Turns `value` into the type of the schema, errors are recorded in the context
+fn generate_convert_token_stream(
+  specification: &Specification,
+  schema: Option<&NodeLocation>,
+) -> TokenStream {
+  let Some(schema) = schema else {
+    return quote! {
+      match value {
+        serde_json::Value::String(value) => core::option::Option::Some(value),
+        value => core::option::Option::Some(value.to_string()),
+      }
+    };
+  };
+
+  let key = specification.get_key(schema);
+  let type_identifier = key.and_then(|key| specification.get_type_identifier(&key));
+  let parser_identifier = key.and_then(|key| get_parser_identifier(specification, &key));
+  match (type_identifier, parser_identifier) {
+    (Some(type_identifier), Some(parser_identifier)) => quote! {
+      {
+        let value = crate::parsers::#parser_identifier(&value, &self.parser_options).unwrap_or(value);
+        #type_identifier::from_json_with_context(&value, &mut context)
+      }
+    },
+    _ => quote! { core::option::Option::Some(value) },
+  }
+}
+
+fn generate_missing_token_stream(name: &str, required: bool) -> TokenStream {
+  if required {
+    quote! {
+      context.record(#name, core::option::Option::None, "required", serde_json::Value::String(#name.to_owned()));
+    }
+  } else {
+    quote! {}
+  }
+}
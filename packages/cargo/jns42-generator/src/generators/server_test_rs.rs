@@ -0,0 +1,240 @@
+use super::client_rs::{
+  generate_arguments_token_stream, get_header_identifier, get_headers_identifier,
+  get_operation_identifier, get_response_identifier, get_response_variant_identifier,
+};
+use super::client_test_rs::{
+  generate_mock_arguments_token_stream, generate_mock_value_token_stream,
+  generate_response_pattern_token_stream, get_mock_response,
+};
+use super::mocks_rs::get_mock_identifier;
+use crate::models::Specification;
+use jns42_core::models::{ApiOperation, ApiParameterLocation};
+use proc_macro2::TokenStream;
+use quote::{TokenStreamExt, format_ident, quote};
+use std::error::Error;
+use std::iter;
+
+pub fn generate_file_token_stream(
+  specification: &Specification,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  // the router serves a server that answers every operation with a mock, the generated
+  // client calls it
+  tokens.append_all(quote! {
+    async fn serve(
+      server: MockServer,
+    ) -> core::result::Result<reqwest::Url, std::boxed::Box<dyn std::error::Error>> {
+      let router = std::sync::Arc::new(crate::server::Router::new(server).with_base_path("/api"));
+      let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+      let address = listener.local_addr()?;
+
+      tokio::spawn(async move {
+        loop {
+          let core::result::Result::Ok((stream, _)) = listener.accept().await else {
+            break;
+          };
+          let router = router.clone();
+          let service = hyper::service::service_fn(move |request| {
+            let router = router.clone();
+            async move { router.handle(request).await }
+          });
+
+          tokio::spawn(async move {
+            let _ = hyper::server::conn::http1::Builder::new()
+              .serve_connection(hyper_util::rt::TokioIo::new(stream), service)
+              .await;
+          });
+        }
+      });
+
+      core::result::Result::Ok(format!("http://{}/api/", address).parse()?)
+    }
+  });
+
+  let mut methods_tokens = quote! {};
+  for operation in &specification.operations {
+    methods_tokens.append_all(generate_method_token_stream(specification, operation));
+    tokens.append_all(generate_test_token_stream(specification, operation));
+    tokens.append_all(generate_bad_request_test_token_stream(operation));
+  }
+
+  tokens.append_all(quote! {
+    struct MockServer {
+      seed: u64,
+    }
+
+    #[allow(unused_variables)]
+    impl crate::server::Server for MockServer {
+      #methods_tokens
+    }
+  });
+
+  Ok(tokens)
+}
+
+fn generate_method_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> TokenStream {
+  let operation_identifier = get_operation_identifier(operation);
+  let response_identifier = get_response_identifier(operation);
+  let arguments_tokens = generate_arguments_token_stream(specification, operation);
+
+  let response_tokens = generate_mock_response_token_stream(specification, operation)
+    .unwrap_or_else(|| quote! { core::result::Result::Err("not mockable".into()) });
+
+  quote! {
+    fn #operation_identifier(
+      &self,
+      #arguments_tokens
+    ) -> impl core::future::Future<
+      Output = core::result::Result<crate::server::#response_identifier, crate::server::ServerError>,
+    > + core::marker::Send {
+      let seed = self.seed;
+      async move { #response_tokens }
+    }
+  }
+}
+
+/// The response of the mock server, `None` if we cannot mock it
+fn generate_mock_response_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> Option<TokenStream> {
+  let (_, response) = get_mock_response(operation)?;
+  let response_identifier = get_response_identifier(operation);
+  let variant_identifier = get_response_variant_identifier(response);
+
+  // optional headers that cannot be mocked are left out
+  let headers_tokens = match get_headers_identifier(operation, response) {
+    Some(headers_identifier) => {
+      let mut fields_tokens = quote! {};
+      for header in &response.headers {
+        let header_identifier = get_header_identifier(header);
+        let value_tokens = generate_mock_value_token_stream(specification, &header.schema);
+        fields_tokens.append_all(match (value_tokens, header.required) {
+          (Some(value_tokens), true) => quote! { #header_identifier: #value_tokens, },
+          (Some(value_tokens), false) => {
+            quote! { #header_identifier: core::option::Option::Some(#value_tokens), }
+          }
+          (None, true) => return None,
+          (None, false) => quote! { #header_identifier: core::option::Option::None, },
+        });
+      }
+      Some(quote! { crate::server::#headers_identifier { #fields_tokens } })
+    }
+    None => None,
+  };
+
+  let Some(schema) = response.body.as_ref().and_then(|body| body.schema.as_ref()) else {
+    return Some(match headers_tokens {
+      Some(headers_tokens) => quote! {
+        core::result::Result::Ok(crate::server::#response_identifier::#variant_identifier(#headers_tokens))
+      },
+      None => quote! {
+        let _ = seed;
+        core::result::Result::Ok(crate::server::#response_identifier::#variant_identifier)
+      },
+    });
+  };
+
+  let key = specification.get_key(schema)?;
+  if !specification.is_mockable(&key) {
+    return None;
+  }
+  let mock_identifier = get_mock_identifier(specification, &key)?;
+  let value_tokens = match specification.get_type_identifier(&key) {
+    Some(type_identifier) => quote! {
+      #type_identifier::from_json(&value, crate::errors::ValidationMode::All)?
+    },
+    None => quote! { value },
+  };
+
+  let fields_tokens = iter::once(value_tokens).chain(headers_tokens);
+  Some(quote! {
+    let value = crate::mocks::#mock_identifier(&mut crate::mocks::MockContext::new(seed));
+    core::result::Result::Ok(crate::server::#response_identifier::#variant_identifier(#(#fields_tokens),*))
+  })
+}
+
+/// Calls the router via the client, parameters and bodies should arrive at the server and
+/// the response of the server should arrive at the client.
+fn generate_test_token_stream(
+  specification: &Specification,
+  operation: &ApiOperation,
+) -> TokenStream {
+  if generate_mock_response_token_stream(specification, operation).is_none() {
+    return quote! {};
+  }
+  let Some((_, response)) = get_mock_response(operation) else {
+    return quote! {};
+  };
+  let Some((arguments_tokens, argument_identifiers)) =
+    generate_mock_arguments_token_stream(specification, operation)
+  else {
+    return quote! {};
+  };
+
+  let test_identifier = format_ident!("r#test_{}", get_operation_identifier(operation));
+  let operation_identifier = get_operation_identifier(operation);
+  let pattern_tokens = generate_response_pattern_token_stream(operation, response);
+
+  quote! {
+    #[tokio::test]
+    async fn #test_identifier() -> core::result::Result<(), std::boxed::Box<dyn std::error::Error>> {
+      for seed in 0..4 {
+        let base_url = serve(MockServer { seed }).await?;
+        let client = crate::client::Client::new(base_url);
+
+        #arguments_tokens
+        let response = client.#operation_identifier(#(#argument_identifiers),*).await?;
+        assert!(matches!(response, #pattern_tokens), "{:?}", response);
+      }
+
+      core::result::Result::Ok(())
+    }
+  }
+}
+
+/// A body that is not json should get a 400 response with validation errors. We only test
+/// operations without path parameters, so we know the path.
+fn generate_bad_request_test_token_stream(operation: &ApiOperation) -> TokenStream {
+  if !operation
+    .request_body
+    .as_ref()
+    .is_some_and(|request_body| request_body.is_json())
+  {
+    return quote! {};
+  }
+  if operation
+    .parameters
+    .iter()
+    .any(|parameter| parameter.required || parameter.location == ApiParameterLocation::Path)
+  {
+    return quote! {};
+  }
+
+  let test_identifier = format_ident!("r#test_{}_bad_request", get_operation_identifier(operation));
+  let method_identifier = format_ident!("{}", operation.method.to_uppercase());
+  let path = operation.path.trim_start_matches('/');
+
+  quote! {
+    #[tokio::test]
+    async fn #test_identifier() -> core::result::Result<(), std::boxed::Box<dyn std::error::Error>> {
+      let base_url = serve(MockServer { seed: 0 }).await?;
+      let response = reqwest::Client::new()
+        .request(reqwest::Method::#method_identifier, base_url.join(#path)?)
+        .header("content-type", "application/json")
+        .body("{")
+        .send()
+        .await?;
+      assert_eq!(response.status().as_u16(), 400);
+
+      let errors: std::vec::Vec<serde_json::Value> = serde_json::from_slice(&response.bytes().await?)?;
+      assert!(!errors.is_empty());
+
+      core::result::Result::Ok(())
+    }
+  }
+}
@@ -1,8 +1,20 @@
-use crate::models::Specification;
-use jns42_core::models::{ArenaSchemaItem, SchemaType};
+use super::interiors_rs::{StructRemainder, get_struct_remainder};
+use crate::models::{
+  IntegerBound, IntegerType, NumberType, Specification, StringFormat, get_integer_maximum,
+  get_integer_maximums, get_integer_minimum, get_integer_minimums,
+};
+use jns42_core::{
+  models::{ArenaSchemaItem, SchemaType},
+  naming::Sentence,
+  utilities::{Decimal, EcmaPattern, PatternError},
+  validation::is_known_format,
+};
 use proc_macro2::TokenStream;
-use quote::{TokenStreamExt, quote};
-use std::error::Error;
+use quote::{TokenStreamExt, format_ident, quote};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  error::Error,
+};
 
 pub fn generate_file_token_stream(
   specification: &Specification,
@@ -10,7 +22,7 @@ pub fn generate_file_token_stream(
   let mut tokens = quote! {};
 
   for (key, item) in specification.arena.iter().enumerate() {
-    tokens.append_all(generate_type_token_stream(specification, &key, item));
+    tokens.append_all(generate_type_token_stream(specification, &key, item)?);
   }
 
   Ok(tokens)
@@ -50,7 +62,13 @@ fn generate_type_token_stream(
     });
   } else {
     let name = specification.get_name(key);
-    let interior_name = specification.get_interior_name(key);
+    let schema_location = match &item.location {
+      Some(location) => {
+        let location = location.to_string();
+        quote! { core::option::Option::Some(#location) }
+      }
+      None => quote! { core::option::Option::None },
+    };
     let interior_identifier = specification.get_interior_identifier(key);
     let boxed = item
       .types
@@ -58,70 +76,216 @@ fn generate_type_token_stream(
       .flatten()
       .any(|r#type| *r#type == SchemaType::Object)
       || item.one_of.is_some();
-    let to_string = item.types.iter().flatten().any(|r#type| {
-      matches!(
-        *r#type,
-        SchemaType::Boolean | SchemaType::Integer | SchemaType::Number | SchemaType::String
+    let content = has_content(specification, item);
+    let to_string = !content
+      && item.types.iter().flatten().any(|r#type| {
+        matches!(
+          *r#type,
+          SchemaType::Boolean | SchemaType::Integer | SchemaType::Number | SchemaType::String
+        )
+      });
+
+    let (inner_identifier, new_instance) = if boxed {
+      (
+        quote! { Box<#interior_identifier> },
+        quote! { Self(Box::new(value)) },
       )
-    });
+    } else {
+      (quote! { #interior_identifier }, quote! { Self(value) })
+    };
+
+    if content {
+      // the content is encoded again when serializing
+      let encode_tokens = generate_content_encode_token_stream(specification, item);
+      tokens.append_all(quote! {
+        #[derive(core::fmt::Debug, serde::Deserialize, core::clone::Clone)]
+        #[serde(try_from = "serde_json::Value")]
+        pub struct #identifier(pub(super) #inner_identifier);
+
+        impl serde::Serialize for #identifier {
+          fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+          where
+            S: serde::Serializer,
+          {
+            #encode_tokens
+            serializer.serialize_str(&string)
+          }
+        }
+      });
+    } else if specification.get_integer_type(item) == Some(IntegerType::BigInt)
+      || specification.get_number_type(item) == Some(NumberType::Decimal)
+    {
+      // big integers and decimals are written as json numbers instead of strings
+      tokens.append_all(quote! {
+        #[derive(core::fmt::Debug, serde::Deserialize, core::clone::Clone)]
+        #[serde(try_from = "serde_json::Value")]
+        pub struct #identifier(pub(super) #inner_identifier);
 
-    if boxed {
+        impl serde::Serialize for #identifier {
+          fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+          where
+            S: serde::Serializer,
+          {
+            let number: serde_json::Number = self
+              .0
+              .to_string()
+              .parse()
+              .map_err(serde::ser::Error::custom)?;
+            number.serialize(serializer)
+          }
+        }
+      });
+    } else {
       tokens.append_all(quote! {
         #[derive(core::fmt::Debug, serde::Serialize, serde::Deserialize, core::clone::Clone)]
-        #[serde(try_from = #interior_name)]
-        pub struct #identifier(pub(super) Box<#interior_identifier>);
+        #[serde(try_from = "serde_json::Value")]
+        pub struct #identifier(pub(super) #inner_identifier);
       });
+    }
+
+    let interior_tokens = generate_interior_token_stream(specification, key, item)?;
+    // without a single type the interior is the json value itself, so a new instance is
+    // checked like a json value
+    let untyped = item.one_of.is_none() && item.types.as_ref().is_none_or(|types| types.len() != 1);
+    let new_tokens = if untyped {
+      quote! {
+        Self::from_json(&value, crate::errors::ValidationMode::First)
+      }
+    } else {
+      quote! {
+        let instance = #new_instance;
+        let mut context = crate::errors::ValidationContext::new(crate::errors::ValidationMode::First);
+        instance.validate(&mut context);
+        context.into_result(core::option::Option::Some(instance))
+      }
+    };
+    tokens.append_all(quote! {
+      impl #identifier {
+          /// Name of the type, as it is reported in validation errors
+          pub const NAME: &'static str = #name;
+          /// Location of the schema that the type was generated from
+          pub const SCHEMA_LOCATION: core::option::Option<&'static str> = #schema_location;
+
+          /// Validates a JSON value and turns it into this type. Depending on the `mode`
+          /// we stop at the first error or collect all of them.
+          pub fn from_json(
+            value: &serde_json::Value,
+            mode: crate::errors::ValidationMode,
+          ) -> core::result::Result<Self, crate::errors::ValidationErrors> {
+              let mut context = crate::errors::ValidationContext::new(mode);
+              let instance = Self::from_json_with_context(value, &mut context);
+              context.into_result(instance)
+          }
+
+          /// Validates a JSON value and turns it into this type, errors are recorded in the
+          /// `context`.
+          pub fn from_json_with_context(
+            value: &serde_json::Value,
+            context: &mut crate::errors::ValidationContext,
+          ) -> core::option::Option<Self> {
+              let error_count = context.error_count();
+              let value = Self::interior_from_json(value, context)?;
+              let instance = #new_instance;
+              instance.validate(context);
+              (context.error_count() == error_count).then_some(instance)
+          }
+
+          fn interior_from_json(
+            value: &serde_json::Value,
+            context: &mut crate::errors::ValidationContext,
+          ) -> core::option::Option<#interior_identifier> {
+              #interior_tokens
+          }
+
+          fn new(value: #interior_identifier) -> core::result::Result<Self, crate::errors::ValidationErrors> {
+              #new_tokens
+          }
+      }
+    });
 
+    let never = item
+      .types
+      .iter()
+      .flatten()
+      .any(|r#type| *r#type == SchemaType::Never);
+    let assertions_tokens = generate_assertions_token_stream(specification, item)?;
+    if never {
       tokens.append_all(quote! {
         impl #identifier {
-            fn new(value: #interior_identifier) -> core::result::Result<Self, crate::errors::ValidationError> {
-                let instance = Self(Box::new(value));
-                if instance.validate() {
-                  core::result::Result::Ok(instance)
-                } else {
-                  core::result::Result::Err(crate::errors::ValidationError::new(#name))
-                }
+            fn validate(&self, context: &mut crate::errors::ValidationContext) -> bool {
+              context.record(Self::NAME, Self::SCHEMA_LOCATION, "false", serde_json::Value::Bool(false));
+              false
             }
         }
       });
-    } else {
+    } else if assertions_tokens.is_empty() {
       tokens.append_all(quote! {
-        #[derive(core::fmt::Debug, serde::Serialize, serde::Deserialize, core::clone::Clone)]
-        #[serde(try_from = #interior_name)]
-        pub struct #identifier(pub(super) #interior_identifier);
+        impl #identifier {
+            fn validate(&self, _context: &mut crate::errors::ValidationContext) -> bool {
+              true
+            }
+        }
       });
+    } else if let Some(format) = StringFormat::from_item(item) {
+      // with the feature the string is checked before it is parsed
+      let feature = format.get_feature();
+      tokens.append_all(quote! {
+        #[cfg(feature = #feature)]
+        impl #identifier {
+            fn validate(&self, _context: &mut crate::errors::ValidationContext) -> bool {
+              true
+            }
+        }
 
+        #[cfg(not(feature = #feature))]
+        impl #identifier {
+            fn validate(&self, context: &mut crate::errors::ValidationContext) -> bool {
+              let error_count = context.error_count();
+              let value: &#interior_identifier = self;
+              #assertions_tokens
+              context.error_count() == error_count
+            }
+        }
+      });
+    } else {
       tokens.append_all(quote! {
         impl #identifier {
-            fn new(value: #interior_identifier) -> core::result::Result<Self, crate::errors::ValidationError> {
-                let instance = Self(value);
-                if instance.validate() {
-                  core::result::Result::Ok(instance)
-                } else {
-                  core::result::Result::Err(crate::errors::ValidationError::new(#name))
-                }
+            fn validate(&self, context: &mut crate::errors::ValidationContext) -> bool {
+              let error_count = context.error_count();
+              let value: &#interior_identifier = self;
+              #assertions_tokens
+              context.error_count() == error_count
             }
         }
       });
     }
 
-    tokens.append_all(quote! {
-      impl #identifier {
-          fn validate(&self) -> bool {
-            true
-          }
-      }
-    });
-
     tokens.append_all(quote! {
       impl core::convert::TryFrom<#interior_identifier> for #identifier {
-        type Error = crate::errors::ValidationError;
+        type Error = crate::errors::ValidationErrors;
         fn try_from(value: #interior_identifier) -> core::result::Result<Self, Self::Error> {
             Self::new(value)
         }
       }
     });
 
+    // when the interior is a json value, the conversion above already does this
+    let any = item
+      .types
+      .iter()
+      .flatten()
+      .any(|r#type| *r#type == SchemaType::Any);
+    if !any && !untyped {
+      tokens.append_all(quote! {
+        impl core::convert::TryFrom<serde_json::Value> for #identifier {
+        type Error = crate::errors::ValidationErrors;
+        fn try_from(value: serde_json::Value) -> core::result::Result<Self, Self::Error> {
+            Self::from_json(&value, crate::errors::ValidationMode::First)
+        }
+      }
+      });
+    }
+
     tokens.append_all(quote! {
       impl std::ops::Deref for #identifier {
         type Target = #interior_identifier;
@@ -131,7 +295,26 @@ fn generate_type_token_stream(
       }
     });
 
-    if to_string {
+    if let Some(format) = StringFormat::from_item(item).filter(|_| to_string) {
+      let feature = format.get_feature();
+      let display_tokens = format.get_display_token_stream();
+      tokens.append_all(quote! {
+        #[cfg(feature = #feature)]
+        impl std::fmt::Display for #identifier {
+          fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            let value = &self.0;
+            write!(f, "{}", #display_tokens)
+          }
+        }
+
+        #[cfg(not(feature = #feature))]
+        impl std::fmt::Display for #identifier {
+          fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+          }
+        }
+      });
+    } else if to_string {
       tokens.append_all(quote! {
         impl std::fmt::Display for #identifier {
           fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -144,3 +327,1814 @@ fn generate_type_token_stream(
 
   Ok(tokens)
 }
+
+/// Generates the body of a function that turns `value`, a `&serde_json::Value`, into the
+/// interior of the type. Sub types are validated along the way, errors are recorded in
+/// `context`.
+fn generate_interior_token_stream(
+  specification: &Specification,
+  key: &usize,
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let not_tokens = generate_not_token_stream(specification, item);
+  let tokens = generate_typed_interior_token_stream(specification, key, item)?;
+  Ok(quote! {
+    #not_tokens
+    #tokens
+  })
+}
+
+/// Fails if the value is valid for the `not` that is left after transforming.
+fn generate_not_token_stream(specification: &Specification, item: &ArenaSchemaItem) -> TokenStream {
+  let Some(not_key) = &item.not else {
+    return quote! {};
+  };
+  let Some(not_type_identifier) = specification.get_type_identifier(not_key) else {
+    return quote! {};
+  };
+
+  let failure_tokens = generate_failure_token_stream(
+    "not",
+    generate_json_token_stream(&serde_json::Value::from(specification.get_name(not_key))),
+  );
+  quote! {
+    let not_valid = {
+      let mut context = context.fork(crate::errors::ValidationMode::First);
+      #not_type_identifier::from_json_with_context(value, &mut context).is_some()
+    };
+    if not_valid {
+      #failure_tokens
+      if context.is_done() {
+        return core::option::Option::None;
+      }
+    }
+  }
+}
+
+fn generate_typed_interior_token_stream(
+  specification: &Specification,
+  key: &usize,
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let interior_identifier = specification.get_interior_identifier(key);
+
+  if let Some(one_of) = &item.one_of {
+    // the value is valid if it is valid for exactly one of the variants
+    let mut variants_tokens = quote! {};
+    let mut names = Vec::new();
+    for sub_key in one_of {
+      let Some(sub_identifier) = specification.get_identifier(sub_key) else {
+        continue;
+      };
+      let sub_type_identifier = specification.get_type_identifier(sub_key);
+      names.push(serde_json::Value::from(specification.get_name(sub_key)));
+      variants_tokens.append_all(quote! {
+        {
+          let mut context = context.fork(crate::errors::ValidationMode::First);
+          if let core::option::Option::Some(variant) =
+            #sub_type_identifier::from_json_with_context(value, &mut context)
+          {
+            variants.push(#interior_identifier::#sub_identifier(variant));
+          }
+        }
+      });
+    }
+
+    let failure_tokens = generate_failure_token_stream(
+      "oneOf",
+      generate_json_token_stream(&serde_json::Value::Array(names)),
+    );
+    return Ok(quote! {
+      let mut variants = std::vec::Vec::new();
+      #variants_tokens
+      if variants.len() != 1 {
+        #failure_tokens
+        return core::option::Option::None;
+      }
+      variants.pop()
+    });
+  }
+
+  let Some(r#type) = item
+    .types
+    .as_ref()
+    .filter(|types| types.len() == 1)
+    .and_then(|types| types.first())
+  else {
+    return Ok(generate_untyped_interior_token_stream(specification, item));
+  };
+
+  let type_failure_tokens = |type_name: &str| {
+    let failure_tokens = generate_failure_token_stream("type", quote! { #type_name.into() });
+    quote! {
+      #failure_tokens
+      return core::option::Option::None;
+    }
+  };
+
+  let tokens = match r#type {
+    SchemaType::Never => quote! {
+      // this will fail validation
+      let _ = (value, context);
+      core::option::Option::Some(())
+    },
+    SchemaType::Any => quote! {
+      let _ = context;
+      core::option::Option::Some(value.clone())
+    },
+    SchemaType::Null => {
+      let type_failure_tokens = type_failure_tokens("null");
+      quote! {
+        if !value.is_null() {
+          #type_failure_tokens
+        }
+        core::option::Option::Some(())
+      }
+    }
+    SchemaType::Boolean => {
+      let type_failure_tokens = type_failure_tokens("boolean");
+      quote! {
+        let core::option::Option::Some(value) = value.as_bool() else {
+          #type_failure_tokens
+        };
+        core::option::Option::Some(value)
+      }
+    }
+    SchemaType::Integer => {
+      let type_failure_tokens = type_failure_tokens("integer");
+      let integer_type = specification.get_integer_type(item).unwrap();
+      let type_tokens = integer_type.get_type_token_stream();
+      let from_f64_tokens = integer_type.get_from_f64_token_stream();
+      // integers that do not fit the type violate one of the bounds, or the type if that
+      // side is not bounded
+      let range_failure_tokens = |bound: Option<IntegerBound>| match bound {
+        Some(bound) => generate_failure_token_stream(
+          bound.keyword,
+          generate_json_token_stream(&bound.keyword_value),
+        ),
+        None => generate_failure_token_stream("type", quote! { "integer".into() }),
+      };
+      let minimum_failure_tokens = range_failure_tokens(get_integer_minimum(item));
+      let maximum_failure_tokens = range_failure_tokens(get_integer_maximum(item));
+      // 1.0 is an integer too
+      quote! {
+        let core::option::Option::Some(number) = value.as_number() else {
+          #type_failure_tokens
+        };
+        let core::option::Option::Some(value) = number
+          .to_string()
+          .parse::<#type_tokens>()
+          .ok()
+          .or_else(|| {
+            number
+              .as_f64()
+              .filter(|number| number.fract() == 0.0)
+              .and_then(#from_f64_tokens)
+          })
+        else {
+          match number.as_f64() {
+            core::option::Option::Some(number) if number.fract() == 0.0 && number < 0.0 => {
+              #minimum_failure_tokens
+            }
+            core::option::Option::Some(number) if number.fract() == 0.0 => {
+              #maximum_failure_tokens
+            }
+            _ => {
+              #type_failure_tokens
+            }
+          }
+          return core::option::Option::None;
+        };
+        core::option::Option::Some(value)
+      }
+    }
+    SchemaType::Number => {
+      let type_failure_tokens = type_failure_tokens("number");
+      let from_json_tokens = specification.number_type.get_from_json_token_stream();
+      quote! {
+        let core::option::Option::Some(value) = #from_json_tokens else {
+          #type_failure_tokens
+        };
+        core::option::Option::Some(value)
+      }
+    }
+    SchemaType::String => {
+      let type_failure_tokens = type_failure_tokens("string");
+      if has_content(specification, item) {
+        let decode_tokens = generate_content_decode_token_stream(specification, item)?;
+        quote! {
+          let core::option::Option::Some(value) = value.as_str() else {
+            #type_failure_tokens
+          };
+          #decode_tokens
+        }
+      } else if let Some(format) = StringFormat::from_item(item) {
+        let parse_tokens = generate_format_parse_token_stream(item, format)?;
+        let feature = format.get_feature();
+        quote! {
+          let core::option::Option::Some(value) = value.as_str() else {
+            #type_failure_tokens
+          };
+          #[cfg(feature = #feature)]
+          let value = {
+            #parse_tokens
+          };
+          #[cfg(not(feature = #feature))]
+          let value = value.to_owned();
+          core::option::Option::Some(value)
+        }
+      } else {
+        quote! {
+          let core::option::Option::Some(value) = value.as_str() else {
+            #type_failure_tokens
+          };
+          core::option::Option::Some(value.to_owned())
+        }
+      }
+    }
+    SchemaType::Array => {
+      let type_failure_tokens = type_failure_tokens("array");
+      if let Some(tuple_items) = &item.tuple_items {
+        // a tuple has exactly as many elements as there are tuple items
+        let count = tuple_items.len();
+        let minimum_failure_tokens =
+          generate_failure_token_stream("minItems", quote! { #count.into() });
+        let maximum_failure_tokens =
+          generate_failure_token_stream("maxItems", quote! { #count.into() });
+
+        let mut elements_tokens = quote! {};
+        let mut element_identifiers = Vec::new();
+        for (index, tuple_items_key) in tuple_items.iter().enumerate() {
+          let element_identifier = format_ident!("element{}", index);
+          let element_tokens = match specification.get_type_identifier(tuple_items_key) {
+            Some(tuple_items_identifier) => quote! {
+              #tuple_items_identifier::from_json_with_context(&array[#index], context)
+            },
+            None => quote! { core::option::Option::Some(()) },
+          };
+          elements_tokens.append_all(quote! {
+            context.push_path(#index);
+            let #element_identifier = #element_tokens;
+            context.pop_path();
+            if context.is_done() {
+              return core::option::Option::None;
+            }
+          });
+          element_identifiers.push(element_identifier);
+        }
+
+        quote! {
+          let core::option::Option::Some(array) = value.as_array() else {
+            #type_failure_tokens
+          };
+          if array.len() < #count {
+            #minimum_failure_tokens
+            return core::option::Option::None;
+          }
+          if array.len() > #count {
+            #maximum_failure_tokens
+            return core::option::Option::None;
+          }
+          #elements_tokens
+          core::option::Option::Some((#(#element_identifiers?,)*))
+        }
+      } else {
+        let element_tokens = match item
+          .array_items
+          .as_ref()
+          .and_then(|key| specification.get_type_identifier(key))
+        {
+          Some(array_items_identifier) => quote! {
+            #array_items_identifier::from_json_with_context(element, context)
+          },
+          None => quote! { core::option::Option::Some(element.clone()) },
+        };
+        quote! {
+          let core::option::Option::Some(array) = value.as_array() else {
+            #type_failure_tokens
+          };
+          let mut elements = std::vec::Vec::with_capacity(array.len());
+          let mut valid = true;
+          for (index, element) in array.iter().enumerate() {
+            context.push_path(index);
+            let element = #element_tokens;
+            context.pop_path();
+            match element {
+              core::option::Option::Some(element) => elements.push(element),
+              core::option::Option::None => {
+                if context.is_done() {
+                  return core::option::Option::None;
+                }
+                valid = false;
+              }
+            }
+          }
+          valid.then_some(elements)
+        }
+      }
+    }
+    SchemaType::Object => {
+      let type_failure_tokens = type_failure_tokens("object");
+      if let Some(object_properties) = &item.object_properties {
+        let required: BTreeSet<_> = item.required.iter().flatten().collect();
+        let member_names: BTreeSet<_> = object_properties
+          .keys()
+          .chain(required.iter().copied())
+          .collect();
+
+        let mut members_tokens = quote! {};
+        let mut fields_tokens = quote! {};
+        members_tokens.append_all(generate_struct_remainder_token_stream(
+          specification,
+          item,
+          &member_names,
+        )?);
+        match get_struct_remainder(specification, item) {
+          StructRemainder::Map { identifier, .. } => {
+            fields_tokens.append_all(quote! {
+              #identifier: remainder?,
+            });
+          }
+          StructRemainder::Closed | StructRemainder::Dropped => {}
+        }
+        for member_name in member_names {
+          let member_identifier = format_ident!("r#{}", Sentence::new(member_name).to_snake_case());
+          let member_tokens = match object_properties
+            .get(member_name)
+            .and_then(|key| specification.get_type_identifier(key))
+          {
+            Some(object_properties_identifier) => quote! {
+              #object_properties_identifier::from_json_with_context(member, context)
+            },
+            None => quote! { core::option::Option::Some(member.clone()) },
+          };
+
+          if required.contains(member_name) {
+            let failure_tokens =
+              generate_failure_token_stream("required", quote! { #member_name.into() });
+            members_tokens.append_all(quote! {
+              let #member_identifier = match object.get(#member_name) {
+                core::option::Option::Some(member) => {
+                  context.push_path(#member_name);
+                  let member = #member_tokens;
+                  context.pop_path();
+                  member
+                }
+                core::option::Option::None => {
+                  #failure_tokens
+                  core::option::Option::None
+                }
+              };
+            });
+          } else {
+            members_tokens.append_all(quote! {
+              let #member_identifier = match object.get(#member_name) {
+                core::option::Option::Some(member) => {
+                  context.push_path(#member_name);
+                  let member = #member_tokens;
+                  context.pop_path();
+                  member.map(core::option::Option::Some)
+                }
+                core::option::Option::None => core::option::Option::Some(core::option::Option::None),
+              };
+            });
+          }
+          members_tokens.append_all(quote! {
+            if context.is_done() {
+              return core::option::Option::None;
+            }
+          });
+          fields_tokens.append_all(quote! {
+            #member_identifier: #member_identifier?,
+          });
+        }
+
+        let dependent_tokens = generate_dependent_token_stream(specification, item);
+        quote! {
+          let core::option::Option::Some(object) = value.as_object() else {
+            #type_failure_tokens
+          };
+          #dependent_tokens
+          #members_tokens
+          core::option::Option::Some(#interior_identifier {
+            #fields_tokens
+          })
+        }
+      } else {
+        let member_tokens = match item
+          .map_properties
+          .as_ref()
+          .filter(|_| item.pattern_properties.is_none())
+          .and_then(|key| specification.get_type_identifier(key))
+        {
+          Some(map_properties_identifier) => quote! {
+            #map_properties_identifier::from_json_with_context(member, context)
+          },
+          None => quote! { core::option::Option::Some(member.clone()) },
+        };
+        let dependent_tokens = generate_dependent_token_stream(specification, item);
+        quote! {
+          let core::option::Option::Some(object) = value.as_object() else {
+            #type_failure_tokens
+          };
+          #dependent_tokens
+          let mut members = std::collections::HashMap::with_capacity(object.len());
+          let mut valid = true;
+          for (name, member) in object {
+            context.push_path(name);
+            let member = #member_tokens;
+            context.pop_path();
+            match member {
+              core::option::Option::Some(member) => {
+                members.insert(name.clone(), member);
+              }
+              core::option::Option::None => {
+                if context.is_done() {
+                  return core::option::Option::None;
+                }
+                valid = false;
+              }
+            }
+          }
+          valid.then_some(members)
+        }
+      }
+    }
+  };
+
+  Ok(tokens)
+}
+
+/// Checks the dependent required properties and dependent schemas of `object`, they apply
+/// when the property that they depend on is present. Errors are recorded, the object is
+/// invalid afterwards.
+fn generate_dependent_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  for (name, required) in item.dependent_required.iter().flatten() {
+    let failure_tokens =
+      generate_failure_token_stream("dependentRequired", quote! { required.into() });
+    let required = required.iter();
+    tokens.append_all(quote! {
+      if object.contains_key(#name) {
+        for required in [#(#required),*] {
+          if !object.contains_key(required) {
+            #failure_tokens
+            if context.is_done() {
+              return core::option::Option::None;
+            }
+          }
+        }
+      }
+    });
+  }
+
+  for (name, sub_key) in item.dependent_schemas.iter().flatten() {
+    let Some(sub_type_identifier) = specification.get_type_identifier(sub_key) else {
+      continue;
+    };
+    tokens.append_all(quote! {
+      if object.contains_key(#name)
+        && #sub_type_identifier::from_json_with_context(value, context).is_none()
+        && context.is_done()
+      {
+        return core::option::Option::None;
+      }
+    });
+  }
+
+  tokens
+}
+
+/// Handles the properties of `object` that are not members of the struct. For a closed struct
+/// these are errors, otherwise they are parsed into a `remainder` map.
+fn generate_struct_remainder_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+  member_names: &BTreeSet<&String>,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let remainder = get_struct_remainder(specification, item);
+  if let StructRemainder::Dropped = remainder {
+    return Ok(quote! {});
+  }
+
+  // names of members and names that match a pattern are known
+  let mut known_tokens = quote! { false };
+  if !member_names.is_empty() {
+    let member_names = member_names.iter();
+    known_tokens.append_all(quote! { || matches!(name, #(#member_names)|*) });
+  }
+  let mut patterns_tokens = quote! {};
+  if let Some(pattern_properties) = &item.pattern_properties {
+    patterns_tokens = generate_patterns_token_stream(item, pattern_properties.keys())?;
+    known_tokens.append_all(quote! { || is_any_pattern_match(name) });
+  }
+  let known_tokens = quote! {
+    #patterns_tokens
+    let known = |name: &str| #known_tokens;
+  };
+
+  let tokens = match remainder {
+    StructRemainder::Closed => {
+      let failure_tokens =
+        generate_failure_token_stream("additionalProperties", quote! { false.into() });
+      quote! {
+        #known_tokens
+        let mut valid = true;
+        for name in object.keys() {
+          if known(name) {
+            continue;
+          }
+          context.push_path(name);
+          #failure_tokens
+          context.pop_path();
+          if context.is_done() {
+            return core::option::Option::None;
+          }
+          valid = false;
+        }
+        if !valid {
+          return core::option::Option::None;
+        }
+      }
+    }
+    StructRemainder::Map {
+      type_identifier, ..
+    } => quote! {
+      #known_tokens
+      let mut remainder = core::option::Option::Some(std::collections::HashMap::new());
+      for (name, member) in object {
+        if known(name) {
+          continue;
+        }
+        context.push_path(name);
+        let member = #type_identifier::from_json_with_context(member, context);
+        context.pop_path();
+        match (member, remainder.as_mut()) {
+          (core::option::Option::Some(member), core::option::Option::Some(remainder)) => {
+            remainder.insert(name.clone(), member);
+          }
+          (core::option::Option::Some(_), core::option::Option::None) => {}
+          (core::option::Option::None, _) => {
+            if context.is_done() {
+              return core::option::Option::None;
+            }
+            remainder = core::option::Option::None;
+          }
+        }
+      }
+    },
+    StructRemainder::Dropped => quote! {},
+  };
+
+  Ok(tokens)
+}
+
+/// Records an error for the `keyword`, `expected` is an expression that evaluates to a
+/// `serde_json::Value`.
+/// Items without a single type keep the value as is. The value must be valid for every all of
+/// and for at least one any of that are left after transforming.
+fn generate_untyped_interior_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  for sub_key in item.all_of.iter().flatten() {
+    let Some(sub_type_identifier) = specification.get_type_identifier(sub_key) else {
+      continue;
+    };
+    tokens.append_all(quote! {
+      #sub_type_identifier::from_json_with_context(value, context)?;
+    });
+  }
+
+  if let Some(any_of) = &item.any_of {
+    let mut variants_tokens = quote! {};
+    let mut names = Vec::new();
+    for sub_key in any_of {
+      let Some(sub_type_identifier) = specification.get_type_identifier(sub_key) else {
+        continue;
+      };
+      names.push(serde_json::Value::from(specification.get_name(sub_key)));
+      variants_tokens.append_all(quote! {
+        if !valid {
+          let mut context = context.fork(crate::errors::ValidationMode::First);
+          valid = #sub_type_identifier::from_json_with_context(value, &mut context).is_some();
+        }
+      });
+    }
+
+    let failure_tokens = generate_failure_token_stream(
+      "anyOf",
+      generate_json_token_stream(&serde_json::Value::Array(names)),
+    );
+    tokens.append_all(quote! {
+      let mut valid = false;
+      #variants_tokens
+      if !valid {
+        #failure_tokens
+        return core::option::Option::None;
+      }
+    });
+  }
+
+  let dependent_tokens = generate_dependent_token_stream(specification, item);
+  if !dependent_tokens.is_empty() {
+    tokens.append_all(quote! {
+      if let core::option::Option::Some(object) = value.as_object() {
+        #dependent_tokens
+      }
+    });
+  }
+
+  if tokens.is_empty() {
+    tokens.append_all(quote! {
+      let _ = context;
+    });
+  }
+
+  quote! {
+    #tokens
+    core::option::Option::Some(value.clone())
+  }
+}
+
+fn generate_failure_token_stream(keyword: &str, expected: TokenStream) -> TokenStream {
+  quote! {
+    context.record(Self::NAME, Self::SCHEMA_LOCATION, #keyword, #expected);
+  }
+}
+
+/// Like `generate_failure_token_stream`, but also returns `false` if validating should stop.
+fn generate_assertion_failure_token_stream(keyword: &str, expected: TokenStream) -> TokenStream {
+  let failure_tokens = generate_failure_token_stream(keyword, expected);
+  quote! {
+    #failure_tokens
+    if context.is_done() {
+      return false;
+    }
+  }
+}
+
+/// Like `generate_json_token_stream`, whole numbers are integers in json. Numbers that are not
+/// exactly a float are parsed from their text.
+fn generate_number_json_token_stream(value: &Decimal) -> TokenStream {
+  if let Some(value) = value.to_i128().and_then(|value| i64::try_from(value).ok()) {
+    quote! { serde_json::Value::from(#value) }
+  } else if Decimal::from_f64(value.to_f64()).as_ref() == Some(value) {
+    let value = value.to_f64();
+    quote! { serde_json::Value::from(#value) }
+  } else {
+    let value = value.to_plain_string();
+    quote! { serde_json::Value::Number(#value.parse().unwrap()) }
+  }
+}
+
+/// Generates an expression that evaluates to the `serde_json::Value` in `value`.
+pub fn generate_json_token_stream(value: &serde_json::Value) -> TokenStream {
+  match value {
+    serde_json::Value::Null => quote! { serde_json::Value::Null },
+    serde_json::Value::Bool(value) => quote! { serde_json::Value::Bool(#value) },
+    serde_json::Value::Number(value) => {
+      if let Some(value) = value.as_u64() {
+        quote! { serde_json::Value::from(#value) }
+      } else if let Some(value) = value.as_i64() {
+        quote! { serde_json::Value::from(#value) }
+      } else {
+        let value = value.as_f64().unwrap_or_default();
+        quote! { serde_json::Value::from(#value) }
+      }
+    }
+    serde_json::Value::String(value) => quote! { serde_json::Value::from(#value) },
+    serde_json::Value::Array(elements) => {
+      let elements = elements.iter().map(generate_json_token_stream);
+      quote! { serde_json::Value::Array(std::vec![#(#elements),*]) }
+    }
+    serde_json::Value::Object(members) => {
+      let members = members.iter().map(|(name, member)| {
+        let member = generate_json_token_stream(member);
+        quote! { (#name.to_owned(), #member) }
+      });
+      quote! { serde_json::Value::Object([#(#members),*].into_iter().collect()) }
+    }
+  }
+}
+
+/// Generates the statements that check the assertions of a schema item. The statements expect
+/// `value` to be a reference to the interior of the type and `context` to be the validation
+/// context. Every assertion that does not hold is recorded in the context.
+fn generate_assertions_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  // without a single type or one of the interior is the json value, like for any
+  let r#type = match item
+    .types
+    .as_ref()
+    .filter(|types| types.len() == 1)
+    .and_then(|types| types.first())
+  {
+    Some(r#type) => r#type,
+    None if item.one_of.is_none() => &SchemaType::Any,
+    None => return Ok(tokens),
+  };
+
+  match r#type {
+    SchemaType::Never | SchemaType::Null => {}
+    SchemaType::Any => {
+      tokens.append_all(generate_options_assertion_token_stream(
+        item,
+        quote! { value },
+      ));
+
+      let number_tokens = generate_number_assertions_token_stream(specification, item);
+      if !number_tokens.is_empty() {
+        let from_json_tokens = specification.number_type.get_from_json_token_stream();
+        let number_tokens = match specification.number_type {
+          NumberType::F64 => quote! {
+            if let Some(number) = #from_json_tokens {
+              #number_tokens
+            }
+          },
+          NumberType::Decimal => quote! {
+            if let Some(number) = &#from_json_tokens {
+              #number_tokens
+            }
+          },
+        };
+        tokens.append_all(number_tokens);
+      }
+
+      let string_tokens = generate_string_assertions_token_stream(item)?;
+      if !string_tokens.is_empty() {
+        tokens.append_all(quote! {
+          if let Some(string) = value.as_str() {
+            #string_tokens
+          }
+        });
+      }
+
+      let mut array_tokens =
+        generate_items_count_assertions_token_stream(item, quote! { array.len() });
+      if item.unique_items.unwrap_or_default() {
+        array_tokens.append_all(generate_unique_items_assertion_token_stream(quote! {
+          let elements = array;
+        }));
+      }
+      array_tokens.append_all(generate_contains_assertions_token_stream(
+        specification,
+        item,
+        quote! {
+          let elements = array;
+        },
+      ));
+      if !array_tokens.is_empty() {
+        tokens.append_all(quote! {
+          if let Some(array) = value.as_array() {
+            #array_tokens
+          }
+        });
+      }
+
+      let object_tokens =
+        generate_map_assertions_token_stream(specification, item, quote! { object })?;
+      if !object_tokens.is_empty() {
+        tokens.append_all(quote! {
+          if let Some(object) = value.as_object() {
+            #object_tokens
+          }
+        });
+      }
+    }
+    SchemaType::Boolean => {
+      if let Some(options) = &item.options {
+        let failure_tokens = generate_options_failure_token_stream(options);
+        let options: Vec<_> = options
+          .iter()
+          .filter_map(|option| option.as_bool())
+          .collect();
+        tokens.append_all(quote! {
+          if ![#(#options),*].contains(value) {
+            #failure_tokens
+          }
+        });
+      }
+    }
+    SchemaType::Integer => {
+      let integer_type = specification.get_integer_type(item).unwrap();
+      let type_tokens = integer_type.get_type_token_stream();
+
+      if let Some(options) = &item.options {
+        let failure_tokens = generate_options_failure_token_stream(options);
+        // options that do not fit the type can never match
+        let options: Vec<_> = options
+          .iter()
+          .filter_map(|option| option.as_number())
+          .filter_map(|option| Decimal::try_from(option).ok())
+          .filter(|option| option.is_integer() && integer_type.contains(option))
+          .map(|option| integer_type.get_literal_token_stream(&option))
+          .collect();
+        tokens.append_all(quote! {
+          if ![#(#options),*].contains(value) {
+            #failure_tokens
+          }
+        });
+      }
+
+      // the bounds are integers too, so they are checked exactly. Bounds that the type
+      // already guarantees are left out.
+      let range = integer_type.get_range();
+      for bound in get_integer_minimums(item) {
+        if range
+          .as_ref()
+          .is_some_and(|(minimum, _)| *minimum >= bound.value)
+        {
+          continue;
+        }
+        let failure_tokens = generate_assertion_failure_token_stream(
+          bound.keyword,
+          generate_json_token_stream(&bound.keyword_value),
+        );
+        let bound_tokens = integer_type.get_literal_token_stream(&bound.value);
+        tokens.append_all(quote! {
+          if *value < #bound_tokens {
+            #failure_tokens
+          }
+        });
+      }
+      for bound in get_integer_maximums(item) {
+        if range
+          .as_ref()
+          .is_some_and(|(_, maximum)| *maximum <= bound.value)
+        {
+          continue;
+        }
+        let failure_tokens = generate_assertion_failure_token_stream(
+          bound.keyword,
+          generate_json_token_stream(&bound.keyword_value),
+        );
+        let bound_tokens = integer_type.get_literal_token_stream(&bound.value);
+        tokens.append_all(quote! {
+          if *value > #bound_tokens {
+            #failure_tokens
+          }
+        });
+      }
+
+      if let Some(multiple_of) = item
+        .multiple_of
+        .as_ref()
+        .filter(|multiple_of| !multiple_of.is_integer())
+      {
+        // a multiple of a fraction, like 0.5, is checked like a number
+        let number_tokens = generate_multiple_of_assertion_token_stream(specification, multiple_of);
+        let to_number_tokens = match specification.number_type {
+          NumberType::F64 => integer_type.get_to_f64_token_stream(),
+          NumberType::Decimal => {
+            let to_decimal_tokens = integer_type.get_to_decimal_token_stream();
+            quote! { &#to_decimal_tokens }
+          }
+        };
+        tokens.append_all(quote! {
+          {
+            let number = #to_number_tokens;
+            #number_tokens
+          }
+        });
+      } else if let Some(multiple_of) = &item.multiple_of {
+        let failure_tokens = generate_assertion_failure_token_stream(
+          "multipleOf",
+          generate_number_json_token_stream(multiple_of),
+        );
+        let multiple_of_tokens = integer_type.get_literal_token_stream(multiple_of);
+        let condition_tokens = if integer_type == IntegerType::BigInt {
+          quote! {
+            !num_traits::Zero::is_zero(&(value % #multiple_of_tokens))
+          }
+        } else if integer_type.contains(multiple_of) && integer_type.is_unsigned() {
+          quote! { !value.is_multiple_of(#multiple_of_tokens) }
+        } else if integer_type.contains(multiple_of) {
+          quote! { *value % #multiple_of_tokens != 0 }
+        } else if integer_type.contains(&(&Decimal::from(0) - multiple_of)) {
+          // the smallest value of a signed type is the only multiple besides zero
+          quote! { *value != 0 && *value != #type_tokens::MIN }
+        } else {
+          quote! { *value != 0 }
+        };
+        tokens.append_all(quote! {
+          if #condition_tokens {
+            #failure_tokens
+          }
+        });
+      }
+    }
+    SchemaType::Number => {
+      let number_type = specification.number_type;
+      if let Some(options) = &item.options {
+        let failure_tokens = generate_options_failure_token_stream(options);
+        let options: Vec<_> = options
+          .iter()
+          .filter_map(|option| option.as_number())
+          .filter_map(|option| Decimal::try_from(option).ok())
+          .map(|option| number_type.get_literal_token_stream(&option))
+          .collect();
+        tokens.append_all(quote! {
+          if ![#(#options),*].contains(value) {
+            #failure_tokens
+          }
+        });
+      }
+
+      let number_tokens = generate_number_assertions_token_stream(specification, item);
+      if !number_tokens.is_empty() {
+        // decimals are not `Copy`, they are compared by reference
+        let value_tokens = match number_type {
+          NumberType::F64 => quote! { *value },
+          NumberType::Decimal => quote! { value },
+        };
+        tokens.append_all(quote! {
+          {
+            let number = #value_tokens;
+            #number_tokens
+          }
+        });
+      }
+    }
+    // strings with content are checked before they are decoded
+    SchemaType::String if has_content(specification, item) => {}
+    SchemaType::String => {
+      let string_tokens = generate_string_assertions_token_stream(item)?;
+      if !string_tokens.is_empty() {
+        tokens.append_all(quote! {
+          {
+            let string = value.as_str();
+            #string_tokens
+          }
+        });
+      }
+    }
+    SchemaType::Array => {
+      tokens.append_all(generate_options_assertion_token_stream(
+        item,
+        quote! { &serde_json::to_value(value).unwrap_or_default() },
+      ));
+
+      if let Some(tuple_items) = &item.tuple_items {
+        let count = tuple_items.len();
+        tokens.append_all(generate_items_count_assertions_token_stream(
+          item,
+          quote! { #count },
+        ));
+        let elements_tokens = quote! {
+          let elements = serde_json::to_value(value).unwrap_or_default();
+          let elements = elements.as_array().map(|elements| elements.as_slice()).unwrap_or_default();
+        };
+        if item.unique_items.unwrap_or_default() {
+          tokens.append_all(generate_unique_items_assertion_token_stream(
+            elements_tokens.clone(),
+          ));
+        }
+        tokens.append_all(generate_contains_assertions_token_stream(
+          specification,
+          item,
+          elements_tokens,
+        ));
+      } else {
+        tokens.append_all(generate_items_count_assertions_token_stream(
+          item,
+          quote! { value.len() },
+        ));
+        let elements_tokens = quote! {
+          let elements: std::vec::Vec<_> = value
+            .iter()
+            .map(|element| serde_json::to_value(element).unwrap_or_default())
+            .collect();
+        };
+        if item.unique_items.unwrap_or_default() {
+          tokens.append_all(generate_unique_items_assertion_token_stream(
+            elements_tokens.clone(),
+          ));
+        }
+        tokens.append_all(generate_contains_assertions_token_stream(
+          specification,
+          item,
+          elements_tokens,
+        ));
+      }
+    }
+    SchemaType::Object => {
+      tokens.append_all(generate_options_assertion_token_stream(
+        item,
+        quote! { &serde_json::to_value(value).unwrap_or_default() },
+      ));
+
+      if let Some(object_properties) = &item.object_properties {
+        tokens.append_all(generate_struct_assertions_token_stream(
+          specification,
+          item,
+          object_properties,
+        )?);
+      } else {
+        tokens.append_all(generate_map_assertions_token_stream(
+          specification,
+          item,
+          quote! { value },
+        )?);
+      }
+    }
+  }
+
+  Ok(tokens)
+}
+
+fn generate_options_failure_token_stream(options: &[serde_json::Value]) -> TokenStream {
+  let options = generate_json_token_stream(&serde_json::Value::Array(options.to_vec()));
+  generate_assertion_failure_token_stream("enum", options)
+}
+
+/// Checks `value`, an expression that evaluates to a `&serde_json::Value`, against the options
+/// (`enum` and `const`) of the item.
+fn generate_options_assertion_token_stream(
+  item: &ArenaSchemaItem,
+  value: TokenStream,
+) -> TokenStream {
+  let Some(options) = &item.options else {
+    return quote! {};
+  };
+
+  let failure_tokens = generate_options_failure_token_stream(options);
+  let options = options.iter().map(generate_json_token_stream);
+  quote! {
+    {
+      static OPTIONS: std::sync::LazyLock<std::vec::Vec<serde_json::Value>> =
+        std::sync::LazyLock::new(|| std::vec![#(#options),*]);
+      if !OPTIONS.iter().any(|option| crate::errors::json_equals(option, #value)) {
+        #failure_tokens
+      }
+    }
+  }
+}
+
+/// Checks `number`, a `f64` or a `&BigDecimal` depending on the number type, against the numeric
+/// assertions of the item.
+fn generate_number_assertions_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  let bounds = [
+    (&item.minimum_inclusive, "minimum", quote! { < }),
+    (&item.minimum_exclusive, "exclusiveMinimum", quote! { <= }),
+    (&item.maximum_inclusive, "maximum", quote! { > }),
+    (&item.maximum_exclusive, "exclusiveMaximum", quote! { >= }),
+  ];
+  for (bound, keyword, operator_tokens) in bounds {
+    let Some(bound) = bound else {
+      continue;
+    };
+    let failure_tokens =
+      generate_assertion_failure_token_stream(keyword, generate_number_json_token_stream(bound));
+    let bound_tokens = specification.number_type.get_operand_token_stream(bound);
+    tokens.append_all(quote! {
+      if number #operator_tokens #bound_tokens {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(multiple_of) = &item.multiple_of {
+    tokens.append_all(generate_multiple_of_assertion_token_stream(
+      specification,
+      multiple_of,
+    ));
+  }
+
+  tokens
+}
+
+/// Checks `number`, like in `generate_number_assertions_token_stream`, against `multipleOf`.
+fn generate_multiple_of_assertion_token_stream(
+  specification: &Specification,
+  multiple_of: &Decimal,
+) -> TokenStream {
+  let failure_tokens = generate_assertion_failure_token_stream(
+    "multipleOf",
+    generate_number_json_token_stream(multiple_of),
+  );
+  let multiple_of_tokens = specification
+    .number_type
+    .get_operand_token_stream(multiple_of);
+  match specification.number_type {
+    // the quotient of a multiple is allowed to be off by a few units in the last place, so
+    // 0.3 is a multiple of 0.1
+    NumberType::F64 => quote! {
+      {
+        let quotient = number / #multiple_of_tokens;
+        if !quotient.is_finite()
+          || (quotient - quotient.round()).abs() > quotient.abs().max(1.0) * f64::EPSILON * 4.0
+        {
+          #failure_tokens
+        }
+      }
+    },
+    // decimals are exact, so 0.3 is a multiple of 0.1 and 0.001 is not a multiple of 0.01
+    NumberType::Decimal => quote! {
+      if !bigdecimal::Zero::is_zero(&(number % #multiple_of_tokens)) {
+        #failure_tokens
+      }
+    },
+  }
+}
+
+/// Checks `string`, a `&str`, against the options and the string assertions of the item.
+fn generate_string_assertions_token_stream(
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  if let Some(options) = &item.options {
+    let failure_tokens = generate_options_failure_token_stream(options);
+    let options: Vec<_> = options
+      .iter()
+      .filter_map(|option| option.as_str())
+      .collect();
+    tokens.append_all(quote! {
+      if ![#(#options),*].contains(&string) {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(minimum_length) = item.minimum_length {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("minLength", quote! { #minimum_length.into() });
+    let minimum_length = minimum_length as usize;
+    tokens.append_all(quote! {
+      if string.chars().count() < #minimum_length {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(maximum_length) = item.maximum_length {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("maxLength", quote! { #maximum_length.into() });
+    let maximum_length = maximum_length as usize;
+    tokens.append_all(quote! {
+      if string.chars().count() > #maximum_length {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(value_format) = item
+    .value_format
+    .as_deref()
+    .filter(|value_format| is_known_format(value_format))
+  {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("format", quote! { #value_format.into() });
+    tokens.append_all(quote! {
+      if !crate::formats::is_valid_format(#value_format, string) {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(value_pattern) = &item.value_pattern {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("pattern", quote! { #value_pattern.into() });
+    let pattern_tokens = generate_pattern_token_stream(item, value_pattern)?;
+    tokens.append_all(quote! {
+      {
+        #pattern_tokens
+        if !is_pattern_match(string) {
+          #failure_tokens
+        }
+      }
+    });
+  }
+
+  Ok(tokens)
+}
+
+/// `true` if the item is a string that holds base64 encoded data or a json document with a
+/// schema. The interior of such a string is the decoded content.
+fn has_content(specification: &Specification, item: &ArenaSchemaItem) -> bool {
+  item
+    .types
+    .as_ref()
+    .is_some_and(|types| types == &[SchemaType::String])
+    && (item.is_base64_content() || get_content_type_identifier(specification, item).is_some())
+}
+
+fn get_content_type_identifier(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> Option<TokenStream> {
+  item
+    .get_json_content_schema()
+    .and_then(|key| specification.get_type_identifier(&key))
+}
+
+/// Checks `value`, a `&str`, against the string assertions and parses it into the type of the
+/// format. Evaluates to the parsed value.
+fn generate_format_parse_token_stream(
+  item: &ArenaSchemaItem,
+  format: StringFormat,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  let string_tokens = generate_string_assertions_token_stream(item)?;
+  if !string_tokens.is_empty() {
+    tokens.append_all(quote! {
+      let validate = |string: &str, context: &mut crate::errors::ValidationContext| -> bool {
+        let error_count = context.error_count();
+        #string_tokens
+        context.error_count() == error_count
+      };
+      if !validate(value, context) {
+        return core::option::Option::None;
+      }
+    });
+  }
+
+  let name = format.get_name();
+  let failure_tokens = generate_failure_token_stream("format", quote! { #name.into() });
+  let parse_tokens = format.get_parse_token_stream();
+  tokens.append_all(quote! {
+    let core::result::Result::Ok(value) = #parse_tokens else {
+      #failure_tokens
+      return core::option::Option::None;
+    };
+    value
+  });
+
+  Ok(tokens)
+}
+
+/// Turns the content in `self.0` into `string`, a `String`.
+fn generate_content_encode_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> TokenStream {
+  let json = get_content_type_identifier(specification, item).is_some();
+  match (json, item.is_base64_content()) {
+    (true, true) => quote! {
+      let data = serde_json::to_vec(&self.0).map_err(serde::ser::Error::custom)?;
+      let string = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, data);
+    },
+    (true, false) => quote! {
+      let string = serde_json::to_string(&self.0).map_err(serde::ser::Error::custom)?;
+    },
+    (false, _) => quote! {
+      let string = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &self.0);
+    },
+  }
+}
+
+/// Checks `value`, a `&str`, against the string assertions and decodes the content in it. The
+/// content is validated against the content schema.
+fn generate_content_decode_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  let string_tokens = generate_string_assertions_token_stream(item)?;
+  if !string_tokens.is_empty() {
+    tokens.append_all(quote! {
+      let validate = |string: &str, context: &mut crate::errors::ValidationContext| -> bool {
+        let error_count = context.error_count();
+        #string_tokens
+        context.error_count() == error_count
+      };
+      if !validate(value, context) {
+        return core::option::Option::None;
+      }
+    });
+  }
+
+  if item.is_base64_content() {
+    let failure_tokens =
+      generate_failure_token_stream("contentEncoding", quote! { "base64".into() });
+    tokens.append_all(quote! {
+      let core::result::Result::Ok(value) =
+        base64::Engine::decode(&base64::engine::general_purpose::STANDARD, value)
+      else {
+        #failure_tokens
+        return core::option::Option::None;
+      };
+    });
+  }
+
+  let Some(content_type_identifier) = get_content_type_identifier(specification, item) else {
+    tokens.append_all(quote! {
+      core::option::Option::Some(value)
+    });
+    return Ok(tokens);
+  };
+
+  let content_media_type = item.content_media_type.clone().unwrap_or_default();
+  let failure_tokens =
+    generate_failure_token_stream("contentMediaType", quote! { #content_media_type.into() });
+  let parse_tokens = if item.is_base64_content() {
+    quote! { serde_json::from_slice::<serde_json::Value>(&value) }
+  } else {
+    quote! { serde_json::from_str::<serde_json::Value>(value) }
+  };
+  tokens.append_all(quote! {
+    let core::result::Result::Ok(value) = #parse_tokens else {
+      #failure_tokens
+      return core::option::Option::None;
+    };
+    #content_type_identifier::from_json_with_context(&value, context)
+  });
+
+  Ok(tokens)
+}
+
+/// Checks `count`, a `usize`, against the minimum and maximum number of items.
+fn generate_items_count_assertions_token_stream(
+  item: &ArenaSchemaItem,
+  count: TokenStream,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  if let Some(minimum_items) = item.minimum_items {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("minItems", quote! { #minimum_items.into() });
+    let minimum_items = minimum_items as usize;
+    tokens.append_all(quote! {
+      if #count < #minimum_items {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(maximum_items) = item.maximum_items {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("maxItems", quote! { #maximum_items.into() });
+    let maximum_items = maximum_items as usize;
+    tokens.append_all(quote! {
+      if #count > #maximum_items {
+        #failure_tokens
+      }
+    });
+  }
+
+  tokens
+}
+
+/// Checks that no two `elements` are equal. The `elements` are a slice of `serde_json::Value`
+/// that is declared by the `elements_tokens`.
+fn generate_unique_items_assertion_token_stream(elements_tokens: TokenStream) -> TokenStream {
+  let failure_tokens =
+    generate_assertion_failure_token_stream("uniqueItems", quote! { true.into() });
+  quote! {
+    {
+      #elements_tokens
+      for (index, element) in elements.iter().enumerate() {
+        if elements[..index].iter().any(|other| crate::errors::json_equals(other, element)) {
+          context.push_path(index);
+          #failure_tokens
+          context.pop_path();
+        }
+      }
+    }
+  }
+}
+
+/// Counts the elements that are valid against the contains type and checks the count against
+/// the minimum and maximum number of contained elements. The statements in `elements_tokens`
+/// should assign `elements`, a sequence of `serde_json::Value`.
+fn generate_contains_assertions_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+  elements_tokens: TokenStream,
+) -> TokenStream {
+  let Some(contains_identifier) = item
+    .contains
+    .as_ref()
+    .and_then(|key| specification.get_type_identifier(key))
+  else {
+    return quote! {};
+  };
+
+  let mut count_tokens = quote! {};
+
+  let minimum_contains = item.minimum_contains.unwrap_or(1);
+  if minimum_contains > 0 {
+    // without minContains the array should contain at least one element
+    let keyword = if item.minimum_contains.is_some() {
+      "minContains"
+    } else {
+      "contains"
+    };
+    let failure_tokens =
+      generate_assertion_failure_token_stream(keyword, quote! { #minimum_contains.into() });
+    let minimum_contains = minimum_contains as usize;
+    count_tokens.append_all(quote! {
+      if count < #minimum_contains {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(maximum_contains) = item.maximum_contains {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("maxContains", quote! { #maximum_contains.into() });
+    let maximum_contains = maximum_contains as usize;
+    count_tokens.append_all(quote! {
+      if count > #maximum_contains {
+        #failure_tokens
+      }
+    });
+  }
+
+  if count_tokens.is_empty() {
+    return quote! {};
+  }
+
+  quote! {
+    {
+      #elements_tokens
+      let count = elements
+        .iter()
+        .filter(|element| {
+          let mut context = context.fork(crate::errors::ValidationMode::First);
+          #contains_identifier::from_json_with_context(element, &mut context).is_some()
+        })
+        .count();
+      #count_tokens
+    }
+  }
+}
+
+/// Checks `count`, a `usize`, against the minimum and maximum number of properties.
+fn generate_properties_count_assertions_token_stream(
+  item: &ArenaSchemaItem,
+  count: TokenStream,
+) -> TokenStream {
+  let mut tokens = quote! {};
+
+  if let Some(minimum_properties) = item.minimum_properties {
+    let failure_tokens = generate_assertion_failure_token_stream(
+      "minProperties",
+      quote! { #minimum_properties.into() },
+    );
+    let minimum_properties = minimum_properties as usize;
+    tokens.append_all(quote! {
+      if #count < #minimum_properties {
+        #failure_tokens
+      }
+    });
+  }
+
+  if let Some(maximum_properties) = item.maximum_properties {
+    let failure_tokens = generate_assertion_failure_token_stream(
+      "maxProperties",
+      quote! { #maximum_properties.into() },
+    );
+    let maximum_properties = maximum_properties as usize;
+    tokens.append_all(quote! {
+      if #count > #maximum_properties {
+        #failure_tokens
+      }
+    });
+  }
+
+  tokens
+}
+
+/// Checks the value of a property against a sub type. `member` is an expression that
+/// evaluates to a `serde_json::Value` and the path of the property is expected to be pushed.
+fn generate_member_assertion_token_stream(
+  sub_type_identifier: &TokenStream,
+  member: TokenStream,
+) -> TokenStream {
+  quote! {
+    if #sub_type_identifier::from_json_with_context(&#member, context).is_none()
+      && context.is_done()
+    {
+      return false;
+    }
+  }
+}
+
+/// Checks `map`, a map of strings to serializable values, against the object assertions of the
+/// item.
+fn generate_map_assertions_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+  map: TokenStream,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = generate_properties_count_assertions_token_stream(item, quote! { #map.len() });
+
+  for name in item.required.iter().flatten() {
+    let failure_tokens =
+      generate_assertion_failure_token_stream("required", quote! { #name.into() });
+    tokens.append_all(quote! {
+      if !#map.contains_key(#name) {
+        #failure_tokens
+      }
+    });
+  }
+
+  // a map has no fields for the properties, like when the value is untyped
+  for (name, key) in item.object_properties.iter().flatten() {
+    let Some(property_identifier) = specification.get_type_identifier(key) else {
+      continue;
+    };
+    let member_tokens = generate_member_assertion_token_stream(
+      &property_identifier,
+      quote! { serde_json::to_value(member).unwrap_or_default() },
+    );
+    tokens.append_all(quote! {
+      if let Some(member) = #map.get(#name) {
+        context.push_path(#name);
+        #member_tokens
+        context.pop_path();
+      }
+    });
+  }
+
+  if let Some(property_names_identifier) = item
+    .property_names
+    .as_ref()
+    .and_then(|key| specification.get_type_identifier(key))
+  {
+    let member_tokens = generate_member_assertion_token_stream(
+      &property_names_identifier,
+      quote! { serde_json::Value::String(name.clone()) },
+    );
+    tokens.append_all(quote! {
+      for name in #map.keys() {
+        context.push_path(name);
+        #member_tokens
+        context.pop_path();
+      }
+    });
+  }
+
+  let Some(pattern_properties) = &item.pattern_properties else {
+    return Ok(tokens);
+  };
+
+  for (pattern, key) in pattern_properties {
+    let Some(pattern_properties_identifier) = specification.get_type_identifier(key) else {
+      continue;
+    };
+    let pattern_tokens = generate_pattern_token_stream(item, pattern)?;
+    let member_tokens = generate_member_assertion_token_stream(
+      &pattern_properties_identifier,
+      quote! { serde_json::to_value(member).unwrap_or_default() },
+    );
+    tokens.append_all(quote! {
+      {
+        #pattern_tokens
+        for (name, member) in #map.iter().filter(|(name, _)| is_pattern_match(name)) {
+          context.push_path(name);
+          #member_tokens
+          context.pop_path();
+        }
+      }
+    });
+  }
+
+  // the values of the properties that are not named and do not match any pattern are the
+  // additional properties
+  if let Some(map_properties_identifier) = item
+    .map_properties
+    .as_ref()
+    .and_then(|key| specification.get_type_identifier(key))
+  {
+    let patterns_tokens = generate_patterns_token_stream(item, pattern_properties.keys())?;
+    let member_tokens = generate_member_assertion_token_stream(
+      &map_properties_identifier,
+      quote! { serde_json::to_value(member).unwrap_or_default() },
+    );
+    let names = item
+      .object_properties
+      .iter()
+      .flat_map(|properties| properties.keys());
+    tokens.append_all(quote! {
+      {
+        #patterns_tokens
+        let names: &[&str] = &[#(#names),*];
+        for (name, member) in #map
+          .iter()
+          .filter(|(name, _)| !names.contains(&name.as_str()) && !is_any_pattern_match(name))
+        {
+          context.push_path(name);
+          #member_tokens
+          context.pop_path();
+        }
+      }
+    });
+  }
+
+  Ok(tokens)
+}
+
+/// Checks `value`, a struct with a field for every property, against the object assertions of
+/// the item. The names of the properties are known here, so most of the work is done while
+/// generating.
+fn generate_struct_assertions_token_stream(
+  specification: &Specification,
+  item: &ArenaSchemaItem,
+  object_properties: &BTreeMap<String, usize>,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut tokens = quote! {};
+
+  let required: BTreeSet<_> = item.required.iter().flatten().collect();
+  let member_names: BTreeSet<_> = object_properties
+    .keys()
+    .chain(required.iter().copied())
+    .collect();
+
+  let property_names_identifier = item
+    .property_names
+    .as_ref()
+    .and_then(|key| specification.get_type_identifier(key));
+  let mut pattern_properties = Vec::new();
+  for (pattern, key) in item.pattern_properties.iter().flatten() {
+    let Some(pattern_properties_identifier) = specification.get_type_identifier(key) else {
+      continue;
+    };
+    let pattern =
+      EcmaPattern::new(pattern).map_err(|error| invalid_pattern_error(item, pattern, error))?;
+    pattern_properties.push((pattern, pattern_properties_identifier));
+  }
+
+  let mut present_tokens = Vec::new();
+  for member_name in member_names {
+    let member_identifier = format_ident!("r#{}", Sentence::new(member_name).to_snake_case());
+
+    let mut member_tokens = quote! {};
+
+    if let Some(property_names_identifier) = &property_names_identifier {
+      member_tokens.append_all(generate_member_assertion_token_stream(
+        property_names_identifier,
+        quote! { serde_json::Value::from(#member_name) },
+      ));
+    }
+
+    for (pattern, pattern_properties_identifier) in &pattern_properties {
+      if !pattern.is_match(member_name) {
+        continue;
+      }
+      member_tokens.append_all(generate_member_assertion_token_stream(
+        pattern_properties_identifier,
+        quote! { serde_json::to_value(member).unwrap_or_default() },
+      ));
+    }
+
+    if required.contains(member_name) {
+      present_tokens.push(quote! { true });
+      if !member_tokens.is_empty() {
+        tokens.append_all(quote! {
+          {
+            let member = &value.#member_identifier;
+            context.push_path(#member_name);
+            #member_tokens
+            context.pop_path();
+          }
+        });
+      }
+    } else {
+      present_tokens.push(quote! { value.#member_identifier.is_some() });
+      if !member_tokens.is_empty() {
+        tokens.append_all(quote! {
+          if let Some(member) = &value.#member_identifier {
+            context.push_path(#member_name);
+            #member_tokens
+            context.pop_path();
+          }
+        });
+      }
+    }
+  }
+
+  let mut count_tokens = quote! {
+    [#(#present_tokens),*].into_iter().filter(|present| *present).count()
+  };
+  if let StructRemainder::Map { identifier, .. } = get_struct_remainder(specification, item) {
+    count_tokens.append_all(quote! { + value.#identifier.len() });
+    if let Some(property_names_identifier) = &property_names_identifier {
+      let member_tokens = generate_member_assertion_token_stream(
+        property_names_identifier,
+        quote! { serde_json::Value::from(name.as_str()) },
+      );
+      tokens.append_all(quote! {
+        for name in value.#identifier.keys() {
+          context.push_path(name);
+          #member_tokens
+          context.pop_path();
+        }
+      });
+    }
+  }
+
+  tokens.append_all(generate_properties_count_assertions_token_stream(
+    item,
+    count_tokens,
+  ));
+
+  Ok(tokens)
+}
+
+/// Declares `is_pattern_match`, a function that matches a string against the pattern. The
+/// pattern is an ECMA-262 pattern, it is translated while generating, so an invalid pattern is
+/// reported here and not when validating.
+fn generate_pattern_token_stream(
+  item: &ArenaSchemaItem,
+  pattern: &str,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let pattern =
+    EcmaPattern::new(pattern).map_err(|error| invalid_pattern_error(item, pattern, error))?;
+  let translated = pattern.get_translated();
+
+  let tokens = if pattern.is_regress() {
+    quote! {
+      fn is_pattern_match(value: &str) -> bool {
+        static PATTERN: std::sync::LazyLock<regress::Regex> =
+          std::sync::LazyLock::new(|| regress::Regex::with_flags(#translated, "u").unwrap());
+        PATTERN.find(value).is_some()
+      }
+    }
+  } else {
+    quote! {
+      fn is_pattern_match(value: &str) -> bool {
+        static PATTERN: std::sync::LazyLock<regex::Regex> =
+          std::sync::LazyLock::new(|| regex::Regex::new(#translated).unwrap());
+        PATTERN.is_match(value)
+      }
+    }
+  };
+  Ok(tokens)
+}
+
+/// Declares `is_any_pattern_match`, a function that matches a string against all of the
+/// patterns. Patterns that need regress are matched one by one, the others as a set.
+fn generate_patterns_token_stream<'p>(
+  item: &ArenaSchemaItem,
+  patterns: impl IntoIterator<Item = &'p String>,
+) -> Result<TokenStream, Box<dyn Error>> {
+  let mut regex_patterns = Vec::new();
+  let mut regress_patterns = Vec::new();
+  for pattern in patterns {
+    let pattern =
+      EcmaPattern::new(pattern).map_err(|error| invalid_pattern_error(item, pattern, error))?;
+    let translated = pattern.get_translated().to_owned();
+    if pattern.is_regress() {
+      regress_patterns.push(translated);
+    } else {
+      regex_patterns.push(translated);
+    }
+  }
+
+  let mut declarations_tokens = quote! {};
+  let mut match_tokens = Vec::new();
+  if !regex_patterns.is_empty() {
+    declarations_tokens.append_all(quote! {
+      static PATTERNS: std::sync::LazyLock<regex::RegexSet> =
+        std::sync::LazyLock::new(|| regex::RegexSet::new([#(#regex_patterns),*]).unwrap());
+    });
+    match_tokens.push(quote! { PATTERNS.is_match(value) });
+  }
+  if !regress_patterns.is_empty() {
+    declarations_tokens.append_all(quote! {
+      static REGRESS_PATTERNS: std::sync::LazyLock<Vec<regress::Regex>> =
+        std::sync::LazyLock::new(|| {
+          [#(#regress_patterns),*]
+            .into_iter()
+            .map(|pattern| regress::Regex::with_flags(pattern, "u").unwrap())
+            .collect()
+        });
+    });
+    match_tokens
+      .push(quote! { REGRESS_PATTERNS.iter().any(|pattern| pattern.find(value).is_some()) });
+  }
+
+  Ok(quote! {
+    fn is_any_pattern_match(value: &str) -> bool {
+      #declarations_tokens
+      #(#match_tokens)||*
+    }
+  })
+}
+
+fn invalid_pattern_error(item: &ArenaSchemaItem, pattern: &str, error: PatternError) -> String {
+  match &item.location {
+    Some(location) => format!("invalid pattern {} in {}: {}", pattern, location, error),
+    None => format!("invalid pattern {}: {}", pattern, error),
+  }
+}
@@ -1,3 +1,9 @@
+mod integer_type;
+mod number_type;
 mod specification;
+mod string_format;
 
+pub use integer_type::*;
+pub use number_type::*;
 pub use specification::*;
+pub use string_format::*;
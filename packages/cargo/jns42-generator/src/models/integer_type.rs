@@ -0,0 +1,284 @@
+use jns42_core::{models::ArenaSchemaItem, utilities::Decimal};
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+
+/// The type of integers that are not bounded on both sides by their schema, these types are
+/// always signed
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum UnboundedIntegerType {
+  #[default]
+  I64,
+  I128,
+  /// Arbitrary precision via the `num-bigint` crate
+  #[value(name = "bigint")]
+  BigInt,
+}
+
+/// The rust type of an integer. The narrowest type that holds every valid value is chosen,
+/// the bounds come from the `minimum` and `maximum` keywords and from formats like `int32`.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
+pub enum IntegerType {
+  U8,
+  I8,
+  U16,
+  I16,
+  U32,
+  I32,
+  U64,
+  I64,
+  U128,
+  I128,
+  BigInt,
+}
+
+/// Fixed width types, narrowest first
+const FIXED_INTEGER_TYPES: [IntegerType; 10] = [
+  IntegerType::U8,
+  IntegerType::I8,
+  IntegerType::U16,
+  IntegerType::I16,
+  IntegerType::U32,
+  IntegerType::I32,
+  IntegerType::U64,
+  IntegerType::I64,
+  IntegerType::U128,
+  IntegerType::I128,
+];
+
+/// A bound of an integer and the keyword that it comes from, both the minimum and the
+/// maximum are inclusive
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegerBound {
+  pub value: Decimal,
+  pub keyword: &'static str,
+  /// The value of the keyword, this is what is reported when the bound is violated
+  pub keyword_value: serde_json::Value,
+}
+
+impl IntegerType {
+  pub fn from_item(item: &ArenaSchemaItem, unbounded: UnboundedIntegerType) -> Self {
+    let minimum = get_integer_minimum(item).map(|bound| bound.value);
+    let maximum = get_integer_maximum(item).map(|bound| bound.value);
+    let fits = |r#type: &Self| {
+      minimum
+        .iter()
+        .chain(maximum.iter())
+        .all(|value| r#type.contains(value))
+    };
+
+    if minimum.is_some() && maximum.is_some() {
+      return FIXED_INTEGER_TYPES
+        .into_iter()
+        .find(fits)
+        .unwrap_or(Self::BigInt);
+    }
+
+    // a half open range is signed, like an integer without bounds, a minimum of zero does not
+    // make an unsigned type
+    let candidates: &[Self] = match unbounded {
+      UnboundedIntegerType::I64 => &[Self::I64, Self::I128],
+      UnboundedIntegerType::I128 => &[Self::I128],
+      UnboundedIntegerType::BigInt => &[],
+    };
+    candidates
+      .iter()
+      .copied()
+      .find(fits)
+      .unwrap_or(Self::BigInt)
+  }
+
+  /// The type for a format like `int32` or `uint8`
+  fn from_format(format: &str) -> Option<Self> {
+    match format {
+      "int8" => Some(Self::I8),
+      "int16" => Some(Self::I16),
+      "int32" => Some(Self::I32),
+      "int64" => Some(Self::I64),
+      "uint8" => Some(Self::U8),
+      "uint16" => Some(Self::U16),
+      "uint32" => Some(Self::U32),
+      "uint64" => Some(Self::U64),
+      _ => None,
+    }
+  }
+
+  pub fn is_unsigned(&self) -> bool {
+    matches!(
+      self,
+      Self::U8 | Self::U16 | Self::U32 | Self::U64 | Self::U128
+    )
+  }
+
+  /// The smallest and the largest value of the type, `None` if the type has no bounds
+  pub fn get_range(&self) -> Option<(Decimal, Decimal)> {
+    let (minimum, maximum): (i128, u128) = match self {
+      Self::U8 => (0, u8::MAX.into()),
+      Self::I8 => (i8::MIN.into(), i8::MAX as u128),
+      Self::U16 => (0, u16::MAX.into()),
+      Self::I16 => (i16::MIN.into(), i16::MAX as u128),
+      Self::U32 => (0, u32::MAX.into()),
+      Self::I32 => (i32::MIN.into(), i32::MAX as u128),
+      Self::U64 => (0, u64::MAX.into()),
+      Self::I64 => (i64::MIN.into(), i64::MAX as u128),
+      Self::U128 => (0, u128::MAX),
+      Self::I128 => (i128::MIN, i128::MAX as u128),
+      Self::BigInt => return None,
+    };
+    Some((minimum.into(), maximum.into()))
+  }
+
+  /// `true` if the type holds `value`, an integer
+  pub fn contains(&self, value: &Decimal) -> bool {
+    self
+      .get_range()
+      .is_none_or(|(minimum, maximum)| minimum <= *value && *value <= maximum)
+  }
+
+  /// `true` if values of this type might not fit in a 64 bit integer, for those json numbers
+  /// are parsed with arbitrary precision
+  pub fn is_wide(&self) -> bool {
+    matches!(self, Self::U128 | Self::I128 | Self::BigInt)
+  }
+
+  pub fn get_type_token_stream(&self) -> TokenStream {
+    match self {
+      Self::U8 => quote! { u8 },
+      Self::I8 => quote! { i8 },
+      Self::U16 => quote! { u16 },
+      Self::I16 => quote! { i16 },
+      Self::U32 => quote! { u32 },
+      Self::I32 => quote! { i32 },
+      Self::U64 => quote! { u64 },
+      Self::I64 => quote! { i64 },
+      Self::U128 => quote! { u128 },
+      Self::I128 => quote! { i128 },
+      Self::BigInt => quote! { num_bigint::BigInt },
+    }
+  }
+
+  /// An expression of the type with `value`, an integer that the type holds
+  pub fn get_literal_token_stream(&self, value: &Decimal) -> TokenStream {
+    match (self, value.to_i128(), value.to_u128()) {
+      (Self::BigInt, Some(value), _) => {
+        let value = Literal::i128_suffixed(value);
+        quote! { num_bigint::BigInt::from(#value) }
+      }
+      (Self::BigInt, None, _) => {
+        let value = value.to_plain_string();
+        quote! { #value.parse::<num_bigint::BigInt>().unwrap() }
+      }
+      (_, _, Some(value)) => {
+        let value = Literal::u128_unsuffixed(value);
+        quote! { #value }
+      }
+      (_, Some(value), _) => {
+        let value = Literal::i128_unsuffixed(value);
+        quote! { #value }
+      }
+      (_, None, None) => unreachable!("a fixed width type holds {}", value),
+    }
+  }
+
+  /// A function that turns a whole `f64` into an `Option` of the type
+  pub fn get_from_f64_token_stream(&self) -> TokenStream {
+    let Some((minimum, maximum)) = self.get_range() else {
+      return quote! { <num_bigint::BigInt as num_traits::FromPrimitive>::from_f64 };
+    };
+    // both are powers of two, so they are exact
+    let minimum = minimum.to_f64();
+    let maximum = (&maximum + &Decimal::from(1)).to_f64();
+    let type_tokens = self.get_type_token_stream();
+    quote! { |number| (#minimum..#maximum).contains(&number).then_some(number as #type_tokens) }
+  }
+
+  /// An expression that turns `value`, a reference to the type, into a `f64`
+  pub fn get_to_f64_token_stream(&self) -> TokenStream {
+    match self {
+      Self::BigInt => {
+        quote! { <num_bigint::BigInt as num_traits::ToPrimitive>::to_f64(value).unwrap_or(f64::NAN) }
+      }
+      _ => quote! { *value as f64 },
+    }
+  }
+
+  /// An expression that turns `value`, a reference to the type, into a `BigDecimal`
+  pub fn get_to_decimal_token_stream(&self) -> TokenStream {
+    match self {
+      Self::BigInt => quote! { bigdecimal::BigDecimal::from(value.clone()) },
+      _ => quote! { bigdecimal::BigDecimal::from(*value) },
+    }
+  }
+}
+
+/// The lower bounds of an integer item, every value has to be at least as big as each of them
+pub fn get_integer_minimums(item: &ArenaSchemaItem) -> Vec<IntegerBound> {
+  let mut bounds = Vec::new();
+  if let Some(value) = &item.minimum_inclusive {
+    bounds.push(IntegerBound {
+      value: value.ceil(),
+      keyword: "minimum",
+      keyword_value: value.to_json(),
+    });
+  }
+  if let Some(value) = &item.minimum_exclusive {
+    bounds.push(IntegerBound {
+      value: &value.floor() + &Decimal::from(1),
+      keyword: "exclusiveMinimum",
+      keyword_value: value.to_json(),
+    });
+  }
+  if let Some((format, (minimum, _))) = get_integer_format_range(item) {
+    bounds.push(IntegerBound {
+      value: minimum,
+      keyword: "format",
+      keyword_value: format.into(),
+    });
+  }
+  bounds
+}
+
+/// The upper bounds of an integer item, every value has to be at most as big as each of them
+pub fn get_integer_maximums(item: &ArenaSchemaItem) -> Vec<IntegerBound> {
+  let mut bounds = Vec::new();
+  if let Some(value) = &item.maximum_inclusive {
+    bounds.push(IntegerBound {
+      value: value.floor(),
+      keyword: "maximum",
+      keyword_value: value.to_json(),
+    });
+  }
+  if let Some(value) = &item.maximum_exclusive {
+    bounds.push(IntegerBound {
+      value: &value.ceil() - &Decimal::from(1),
+      keyword: "exclusiveMaximum",
+      keyword_value: value.to_json(),
+    });
+  }
+  if let Some((format, (_, maximum))) = get_integer_format_range(item) {
+    bounds.push(IntegerBound {
+      value: maximum,
+      keyword: "format",
+      keyword_value: format.into(),
+    });
+  }
+  bounds
+}
+
+/// The smallest valid value of an integer item, if there is one
+pub fn get_integer_minimum(item: &ArenaSchemaItem) -> Option<IntegerBound> {
+  get_integer_minimums(item)
+    .into_iter()
+    .max_by(|left, right| left.value.cmp(&right.value))
+}
+
+/// The largest valid value of an integer item, if there is one
+pub fn get_integer_maximum(item: &ArenaSchemaItem) -> Option<IntegerBound> {
+  get_integer_maximums(item)
+    .into_iter()
+    .min_by(|left, right| left.value.cmp(&right.value))
+}
+
+fn get_integer_format_range(item: &ArenaSchemaItem) -> Option<(&str, (Decimal, Decimal))> {
+  let format = item.value_format.as_deref()?;
+  Some((format, IntegerType::from_format(format)?.get_range()?))
+}
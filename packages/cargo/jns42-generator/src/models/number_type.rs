@@ -0,0 +1,58 @@
+use jns42_core::utilities::Decimal;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The rust type of numbers that are not integers
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum NumberType {
+  #[default]
+  F64,
+  /// Exact decimals via the `bigdecimal` crate, so `multipleOf: 0.01` is checked exactly
+  Decimal,
+}
+
+impl NumberType {
+  pub fn get_type_token_stream(&self) -> TokenStream {
+    match self {
+      Self::F64 => quote! { f64 },
+      Self::Decimal => quote! { bigdecimal::BigDecimal },
+    }
+  }
+
+  /// An expression that turns `value`, a `&serde_json::Value`, into an `Option` of the type
+  pub fn get_from_json_token_stream(&self) -> TokenStream {
+    match self {
+      Self::F64 => quote! { value.as_f64() },
+      // json numbers are parsed with arbitrary precision, so the text is exact
+      Self::Decimal => quote! {
+        value
+          .as_number()
+          .and_then(|number| number.to_string().parse::<bigdecimal::BigDecimal>().ok())
+      },
+    }
+  }
+
+  /// An expression of the type with `value`
+  pub fn get_literal_token_stream(&self, value: &Decimal) -> TokenStream {
+    match self {
+      Self::F64 => {
+        let value = value.to_f64();
+        quote! { #value }
+      }
+      Self::Decimal => {
+        let value = value.to_plain_string();
+        quote! { #value.parse::<bigdecimal::BigDecimal>().unwrap() }
+      }
+    }
+  }
+
+  /// Like `get_literal_token_stream`, but a reference for types that are not `Copy`. This is
+  /// what `number` is compared with.
+  pub fn get_operand_token_stream(&self, value: &Decimal) -> TokenStream {
+    let literal_tokens = self.get_literal_token_stream(value);
+    match self {
+      Self::F64 => literal_tokens,
+      Self::Decimal => quote! { &#literal_tokens },
+    }
+  }
+}
@@ -1,15 +1,18 @@
+use super::{IntegerType, NumberType, UnboundedIntegerType};
 use jns42_core::{
   documents::DocumentContext,
-  models::ArenaSchemaItem,
+  error::Error,
+  models::{ApiOperation, ArenaSchemaItem, BoxedSchemaTransform, SchemaTransform, SchemaType},
   naming::{Names, NamesBuilder},
-  schema_transforms,
-  utilities::Arena,
+  utilities::{Arena, EcmaPattern, NodeLocation},
+  validation::is_known_format,
 };
 use once_cell::sync::Lazy;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
 use regex::Regex;
 use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 pub static IDENTIFIER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z]").unwrap());
@@ -17,26 +20,68 @@ pub static IDENTIFIER_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z]")
 pub struct SpecificationConfiguration {
   pub default_type_name: String,
   pub transform_maximum_iterations: usize,
+  /// Write the arena to this directory after every iteration of the transforms
+  pub transform_trace_directory: Option<PathBuf>,
+  pub unbounded_integer_type: UnboundedIntegerType,
+  pub number_type: NumberType,
 }
 
+/// Transforms that make the schemas into something we can generate code for. The `not` and
+/// the dependencies are not resolved, generated validation checks them.
+const OPTIMIZE_TRANSFORMS: [SchemaTransform; 17] = [
+  SchemaTransform::SingleType,
+  SchemaTransform::Explode,
+  SchemaTransform::ResolveSingleAllOf,
+  SchemaTransform::ResolveSingleAnyOf,
+  SchemaTransform::ResolveSingleOneOf,
+  SchemaTransform::FlattenAllOf,
+  SchemaTransform::FlattenAnyOf,
+  SchemaTransform::FlattenOneOf,
+  SchemaTransform::FlipAllOfOneOf,
+  SchemaTransform::FlipAllOfAnyOf,
+  SchemaTransform::InheritReference,
+  SchemaTransform::InheritOneOf,
+  SchemaTransform::InheritAnyOf,
+  SchemaTransform::ResolveAllOf,
+  SchemaTransform::ResolveIfThenElse,
+  SchemaTransform::ResolveUnevaluated,
+  SchemaTransform::Unalias,
+];
+
+const NAME_TRANSFORMS: [SchemaTransform; 1] = [SchemaTransform::Name];
+
+/// Passes that are done to find out why the transforms do not reach a fixed point
+const DIAGNOSTIC_PASSES: usize = 10;
+
 pub struct Specification {
   pub arena: Arena<ArenaSchemaItem>,
   pub names: Names<usize>,
+  pub mockable_keys: HashSet<usize>,
+  /// Operations from api descriptions, empty for plain schemas
+  pub operations: Vec<ApiOperation>,
+  /// The type of integers that are not bounded on both sides
+  pub unbounded_integer_type: UnboundedIntegerType,
+  /// The type of numbers that are not integers
+  pub number_type: NumberType,
 }
 
 impl Specification {
+  #[allow(clippy::result_large_err)]
   pub fn new(
     document_context: &Rc<DocumentContext>,
     configuration: SpecificationConfiguration,
-  ) -> Self {
+  ) -> Result<Self, Error> {
     let SpecificationConfiguration {
       default_type_name,
       transform_maximum_iterations,
+      transform_trace_directory,
+      unbounded_integer_type,
+      number_type,
     } = configuration;
 
     // first load schemas in the arena
 
-    let mut arena = Arena::from_document_context(document_context);
+    let mut arena = Arena::from_document_context(document_context)?;
 
     // generate root keys
 
@@ -57,56 +102,23 @@ impl Specification {
 
     // then optimize the schemas
 
-    {
-      let mut transform_iterations = 0;
-      while arena.apply_transform(transformer) > 0 {
-        transform_iterations += 1;
-        if transform_iterations < transform_maximum_iterations {
-          continue;
-        }
-        panic!("too many iterations");
-      }
-
-      fn transformer(arena: &mut Arena<ArenaSchemaItem>, key: usize) {
-        schema_transforms::single_type::transform(arena, key);
-        schema_transforms::explode::transform(arena, key);
-
-        schema_transforms::resolve_single::all_of::transform(arena, key);
-        schema_transforms::resolve_single::any_of::transform(arena, key);
-        schema_transforms::resolve_single::one_of::transform(arena, key);
-        schema_transforms::flatten::all_of::transform(arena, key);
-        schema_transforms::flatten::any_of::transform(arena, key);
-        schema_transforms::flatten::one_of::transform(arena, key);
-        schema_transforms::flip::all_of_one_of::transform(arena, key);
-        schema_transforms::flip::all_of_any_of::transform(arena, key);
-        schema_transforms::inherit::reference::transform(arena, key);
-        schema_transforms::inherit::one_of::transform(arena, key);
-        schema_transforms::inherit::any_of::transform(arena, key);
-
-        schema_transforms::resolve_all_of::transform(arena, key);
-        schema_transforms::resolve_not::transform(arena, key);
-        schema_transforms::resolve_if_then_else::transform(arena, key);
-
-        schema_transforms::unalias::transform(arena, key);
-      }
-    }
+    transform_arena(
+      &mut arena,
+      &OPTIMIZE_TRANSFORMS,
+      "optimize",
+      transform_maximum_iterations,
+      transform_trace_directory.as_deref(),
+    )?;
 
     // generate names
 
-    {
-      let mut transform_iterations = 0;
-      while arena.apply_transform(transformer) > 0 {
-        transform_iterations += 1;
-        if transform_iterations < transform_maximum_iterations {
-          continue;
-        }
-        panic!("too many iterations");
-      }
-
-      fn transformer(arena: &mut Arena<ArenaSchemaItem>, key: usize) {
-        schema_transforms::name::transform(arena, key);
-      }
-    }
+    transform_arena(
+      &mut arena,
+      &NAME_TRANSFORMS,
+      "name",
+      transform_maximum_iterations,
+      transform_trace_directory.as_deref(),
+    )?;
 
     let primary_type_keys: HashSet<_> = explicit_type_keys
       .into_iter()
@@ -133,7 +145,199 @@ impl Specification {
 
     let names = names_builder.build();
 
-    Self { arena, names }
+    // find out what types we can mock, a type is mockable if we can generate a finite value
+    // for it. We start with nothing and keep adding types until nothing changes, this way
+    // types that can only be mocked via themselves are not mockable.
+
+    let mut mockable_keys = HashSet::new();
+    loop {
+      let count = mockable_keys.len();
+      for (key, item) in arena.iter().enumerate() {
+        if is_mockable_item(item, &mockable_keys) {
+          mockable_keys.insert(key);
+        }
+      }
+      if mockable_keys.len() == count {
+        break;
+      }
+    }
+
+    let operations = explicit_locations
+      .iter()
+      .flat_map(|location| {
+        document_context
+          .get_api_operations(location)
+          .unwrap_or_default()
+      })
+      .collect();
+
+    Ok(Self {
+      arena,
+      names,
+      mockable_keys,
+      operations,
+      unbounded_integer_type,
+      number_type,
+    })
+  }
+}
+
+/// Apply the transforms until the arena does not change anymore. If that takes too many
+/// iterations we find out what keeps changing and fail with that.
+#[allow(clippy::result_large_err)]
+fn transform_arena(
+  arena: &mut Arena<ArenaSchemaItem>,
+  transforms: &[SchemaTransform],
+  phase: &str,
+  maximum_iterations: usize,
+  trace_directory: Option<&Path>,
+) -> Result<(), Error> {
+  let boxed_transforms: Vec<BoxedSchemaTransform> = transforms
+    .iter()
+    .map(|transform| (*transform).into())
+    .collect();
+  let transformer = |arena: &mut Arena<ArenaSchemaItem>, key: usize| {
+    for transform in &boxed_transforms {
+      transform(arena, key);
+    }
+  };
+
+  let mut iterations = 0;
+  if let Some(trace_directory) = trace_directory {
+    write_trace(trace_directory, phase, iterations, arena);
+  }
+  while arena.apply_transform(transformer) > 0 {
+    iterations += 1;
+    if let Some(trace_directory) = trace_directory {
+      write_trace(trace_directory, phase, iterations, arena);
+    }
+    if iterations < maximum_iterations {
+      continue;
+    }
+
+    let diagnostic = arena.diagnose_transforms(transforms, iterations, DIAGNOSTIC_PASSES);
+    Err(Error::TransformNotConverging(diagnostic))?;
+  }
+
+  Ok(())
+}
+
+/// Write the arena as yaml, the trace is for debugging so failing to write it is not fatal
+fn write_trace(
+  trace_directory: &Path,
+  phase: &str,
+  iteration: usize,
+  arena: &Arena<ArenaSchemaItem>,
+) {
+  let path = trace_directory.join(format!("{}-{:03}.yaml", phase, iteration));
+  let document = arena.to_document(None, |_key| None);
+  let result = serde_yaml::to_string(&document)
+    .map_err(|error| error.to_string())
+    .and_then(|data| std::fs::write(&path, data).map_err(|error| error.to_string()));
+  if let Err(error) = result {
+    eprintln!("could not write trace {}: {}", path.display(), error);
+  }
+}
+
+fn is_mockable_item(item: &ArenaSchemaItem, mockable_keys: &HashSet<usize>) -> bool {
+  // we can only mock exact items
+  if !item.exact.unwrap_or_default() {
+    return false;
+  }
+
+  if let Some(reference) = &item.reference {
+    return mockable_keys.contains(reference);
+  }
+
+  if item
+    .options
+    .as_ref()
+    .is_some_and(|options| !options.is_empty())
+  {
+    return true;
+  }
+
+  if let Some(one_of) = &item.one_of {
+    return one_of.iter().any(|key| mockable_keys.contains(key));
+  }
+
+  if item.all_of.is_some()
+    || item.any_of.is_some()
+    || item.not.is_some()
+    || item.r#if.is_some()
+    || item.then.is_some()
+    || item.r#else.is_some()
+  {
+    return false;
+  }
+
+  let Some(r#type) = item
+    .types
+    .as_ref()
+    .filter(|types| types.len() == 1)
+    .and_then(|types| types.first())
+  else {
+    return false;
+  };
+
+  match r#type {
+    SchemaType::Never | SchemaType::Any => false,
+    SchemaType::Null | SchemaType::Boolean | SchemaType::Number => true,
+    // we can only generate integers that are a multiple of an integer
+    SchemaType::Integer => item
+      .multiple_of
+      .as_ref()
+      .is_none_or(|multiple_of| multiple_of.is_integer() && !multiple_of.is_negative()),
+    // one day we might support some formats, anything with a regex cannot be mocked
+    SchemaType::String => {
+      item.value_format.is_none()
+        && item.value_pattern.is_none()
+        // we mock the content, not the string that holds it
+        && ((!item.is_base64_content() && item.get_json_content_schema().is_none())
+          || (item.minimum_length.is_none()
+            && item.maximum_length.is_none()
+            && item
+              .get_json_content_schema()
+              .is_none_or(|key| mockable_keys.contains(&key))))
+    }
+    SchemaType::Array => {
+      !item.unique_items.unwrap_or_default()
+        && item.contains.is_none()
+        && item
+          .array_items
+          .is_none_or(|key| mockable_keys.contains(&key))
+        && item
+          .tuple_items
+          .iter()
+          .flatten()
+          .all(|key| mockable_keys.contains(key))
+    }
+    SchemaType::Object => {
+      let required = item.required.iter().flatten().collect::<HashSet<_>>();
+      item
+        .pattern_properties
+        .as_ref()
+        .is_none_or(|pattern_properties| pattern_properties.is_empty())
+        && item
+          .property_names
+          .is_none_or(|key| mockable_keys.contains(&key))
+        && item
+          .object_properties
+          .iter()
+          .flatten()
+          .filter(|(name, _)| required.contains(name))
+          .all(|(_, key)| mockable_keys.contains(key))
+        // dependent required properties are added to the mock, we do not know how to make a
+        // mock valid for a dependent schema
+        && item.dependent_schemas.is_none()
+        && item
+          .dependent_required
+          .iter()
+          .flatten()
+          .flat_map(|(_, required)| required)
+          .filter_map(|name| item.object_properties.as_ref()?.get(name))
+          .all(|key| mockable_keys.contains(key))
+    }
   }
 }
 
@@ -148,6 +352,86 @@ impl Specification {
     Some(sentence.to_snake_case())
   }
 
+  /// The key of the item with the identity `location`
+  pub fn get_key(&self, location: &NodeLocation) -> Option<usize> {
+    self
+      .arena
+      .iter()
+      .position(|item| item.location.as_ref() == Some(location))
+  }
+
+  /// The simplified schema of the item with `key` and every item it depends on, as a draft
+  /// 2020-12 document
+  pub fn get_document(&self, key: &usize) -> serde_json::Value {
+    self
+      .arena
+      .to_document(Some(*key), |key| self.get_name(&key))
+  }
+
+  /// `true` if any of the schemas has a format that we check
+  pub fn has_formats(&self) -> bool {
+    self
+      .arena
+      .iter()
+      .any(|item| item.value_format.as_deref().is_some_and(is_known_format))
+  }
+
+  /// `true` if any of the patterns can only be matched by the regress crate
+  pub fn has_regress_patterns(&self) -> bool {
+    self.arena.iter().any(|item| {
+      item
+        .value_pattern
+        .iter()
+        .chain(
+          item
+            .pattern_properties
+            .iter()
+            .flat_map(|value| value.keys()),
+        )
+        .any(|pattern| EcmaPattern::new(pattern).is_ok_and(|pattern| pattern.is_regress()))
+    })
+  }
+
+  /// The rust type of an item that is an integer
+  pub fn get_integer_type(&self, item: &ArenaSchemaItem) -> Option<IntegerType> {
+    item
+      .types
+      .as_ref()
+      .filter(|types| **types == [SchemaType::Integer])
+      .map(|_| IntegerType::from_item(item, self.unbounded_integer_type))
+  }
+
+  /// The rust type of an item that is a number
+  pub fn get_number_type(&self, item: &ArenaSchemaItem) -> Option<NumberType> {
+    item
+      .types
+      .as_ref()
+      .filter(|types| **types == [SchemaType::Number])
+      .map(|_| self.number_type)
+  }
+
+  /// `true` if any of the integers might not fit in 64 bits
+  pub fn has_wide_integers(&self) -> bool {
+    self
+      .arena
+      .iter()
+      .filter_map(|item| self.get_integer_type(item))
+      .any(|r#type| r#type.is_wide())
+  }
+
+  /// `true` if any of the integers has arbitrary precision
+  pub fn has_big_integers(&self) -> bool {
+    self
+      .arena
+      .iter()
+      .filter_map(|item| self.get_integer_type(item))
+      .any(|r#type| r#type == IntegerType::BigInt)
+  }
+
+  pub fn is_mockable(&self, key: &usize) -> bool {
+    self.mockable_keys.contains(key)
+  }
+
   pub fn get_identifier(&self, key: &usize) -> Option<Ident> {
     let name = self.get_name(key)?;
     Some(format_ident!("r#{}", name))
@@ -163,7 +447,7 @@ impl Specification {
     Some(quote! {crate::interiors::#identifier})
   }
 
-  pub fn get_interior_name(&self, key: &usize) -> Option<String> {
+  pub fn _get_interior_name(&self, key: &usize) -> Option<String> {
     let name = self.get_name(key)?;
     Some(format!("crate::interiors::{}", name))
   }
@@ -0,0 +1,90 @@
+use jns42_core::models::{ArenaSchemaItem, SchemaType};
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Well known formats of strings that have a strong type in the generated code. Every format
+/// is behind a cargo feature of the generated package, when the feature is off the string is
+/// a plain `String`.
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum StringFormat {
+  Uuid,
+  DateTime,
+  Date,
+  Uri,
+  Ipv4,
+  Ipv6,
+}
+
+impl StringFormat {
+  /// The format of the item if the item is a string with a format we have a type for
+  pub fn from_item(item: &ArenaSchemaItem) -> Option<Self> {
+    if item.types.as_deref() != Some(&[SchemaType::String]) {
+      return None;
+    }
+
+    match item.value_format.as_deref()? {
+      "uuid" => Some(Self::Uuid),
+      "date-time" => Some(Self::DateTime),
+      "date" => Some(Self::Date),
+      "uri" => Some(Self::Uri),
+      "ipv4" => Some(Self::Ipv4),
+      "ipv6" => Some(Self::Ipv6),
+      _ => None,
+    }
+  }
+
+  /// The name of the format as it is in the schema
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      Self::Uuid => "uuid",
+      Self::DateTime => "date-time",
+      Self::Date => "date",
+      Self::Uri => "uri",
+      Self::Ipv4 => "ipv4",
+      Self::Ipv6 => "ipv6",
+    }
+  }
+
+  /// The cargo feature of the generated package that enables the type
+  pub fn get_feature(&self) -> &'static str {
+    match self {
+      Self::Uuid => "uuid",
+      Self::DateTime | Self::Date => "chrono",
+      Self::Uri => "url",
+      Self::Ipv4 | Self::Ipv6 => "ip",
+    }
+  }
+
+  /// The type that holds the value
+  pub fn get_type_token_stream(&self) -> TokenStream {
+    match self {
+      Self::Uuid => quote! { uuid::Uuid },
+      Self::DateTime => quote! { chrono::DateTime<chrono::FixedOffset> },
+      Self::Date => quote! { chrono::NaiveDate },
+      Self::Uri => quote! { url::Url },
+      Self::Ipv4 => quote! { std::net::Ipv4Addr },
+      Self::Ipv6 => quote! { std::net::Ipv6Addr },
+    }
+  }
+
+  /// An expression that parses `value`, a `&str`, into a `Result` of the type
+  pub fn get_parse_token_stream(&self) -> TokenStream {
+    match self {
+      Self::Uuid => quote! { uuid::Uuid::try_parse(value) },
+      Self::DateTime => quote! { chrono::DateTime::parse_from_rfc3339(value) },
+      Self::Date => quote! { chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") },
+      Self::Uri => quote! { url::Url::parse(value) },
+      Self::Ipv4 => quote! { value.parse::<std::net::Ipv4Addr>() },
+      Self::Ipv6 => quote! { value.parse::<std::net::Ipv6Addr>() },
+    }
+  }
+
+  /// An expression that turns `value`, a reference to the type, into something that displays
+  /// like the string in json
+  pub fn get_display_token_stream(&self) -> TokenStream {
+    match self {
+      Self::DateTime => quote! { value.to_rfc3339() },
+      _ => quote! { value },
+    }
+  }
+}
@@ -1,3 +1,4 @@
+mod bundle;
 mod package;
 
 use clap::{Parser, Subcommand};
@@ -13,10 +14,23 @@ pub struct ProgramOptions {
 #[derive(Subcommand, Debug)]
 pub enum ProgramCommands {
   Package(package::CommandOptions),
+  Bundle(bundle::CommandOptions),
 }
 
 pub async fn run_program(options: ProgramOptions) -> Result<(), Box<dyn Error>> {
   match options.command {
     ProgramCommands::Package(options) => package::run_command(options).await,
+    ProgramCommands::Bundle(options) => bundle::run_command(options).await,
   }
 }
+
+/// Print every error, the returned error only tells how many errors there were
+fn report_errors(error: jns42_core::error::Error) -> Box<dyn Error> {
+  eprintln!("{}", error);
+
+  let count = match &error {
+    jns42_core::error::Error::Multiple(errors) => errors.len(),
+    _ => 1,
+  };
+  format!("{} error(s)", count).into()
+}
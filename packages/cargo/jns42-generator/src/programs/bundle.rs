@@ -0,0 +1,68 @@
+use super::report_errors;
+use clap::Parser;
+use jns42_core::documents;
+use jns42_core::documents::DocumentContext;
+use jns42_core::utilities::NodeLocation;
+use std::error::Error;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[derive(Parser, Debug)]
+pub struct CommandOptions {
+  pub schema_location: NodeLocation,
+
+  #[arg(long, default_value = documents::draft_2020_12::META_SCHEMA_ID)]
+  pub default_meta_schema_id: String,
+
+  /// Write the bundle to this file instead of stdout
+  #[arg(long)]
+  pub output: Option<PathBuf>,
+
+  /// Resolve the identities in the bundle from this location, instead of leaving them
+  /// relative to the entry document
+  #[arg(long)]
+  pub base_uri: Option<NodeLocation>,
+}
+
+pub async fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>> {
+  let CommandOptions {
+    schema_location,
+    default_meta_schema_id,
+    output,
+    base_uri,
+    ..
+  } = options;
+
+  // documents are loaded from an absolute location, the identities in the bundle are made
+  // relative to the entry document again
+  let current_location: NodeLocation =
+    format!("{}/", std::env::current_dir()?.display()).parse()?;
+  let schema_location = current_location.join(&schema_location);
+
+  let mut context = Rc::new(DocumentContext::default());
+  context
+    .register_well_known_factories()
+    .map_err(report_errors)?;
+  // the embedded meta schemas are used instead of fetching them
+  context.load_well_known_schemas().map_err(report_errors)?;
+
+  context
+    .load_from_location(
+      schema_location.clone(),
+      schema_location.clone(),
+      None,
+      &default_meta_schema_id,
+    )
+    .await
+    .map_err(report_errors)?;
+
+  let bundle = context.bundle(&schema_location, base_uri.as_ref(), &default_meta_schema_id)?;
+  let data = serde_json::to_string_pretty(&bundle)?;
+
+  match output {
+    Some(output) => std::fs::write(output, data + "\n")?,
+    None => println!("{data}"),
+  }
+
+  Ok(())
+}
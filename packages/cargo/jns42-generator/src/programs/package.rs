@@ -1,5 +1,6 @@
+use super::report_errors;
 use crate::generators::package::{PackageConfiguration, generate_package};
-use crate::models::{Specification, SpecificationConfiguration};
+use crate::models::{NumberType, Specification, SpecificationConfiguration, UnboundedIntegerType};
 use clap::Parser;
 use jns42_core::documents;
 use jns42_core::documents::DocumentContext;
@@ -29,6 +30,24 @@ pub struct CommandOptions {
 
   #[arg(long, default_value = "100")]
   pub transform_maximum_iterations: usize,
+
+  /// Write the schemas to this directory as yaml after every iteration of the transforms
+  #[arg(long)]
+  pub transform_trace: Option<PathBuf>,
+
+  /// The type of integers that do not have both a minimum and a maximum. Unsigned types are
+  /// only chosen when both bounds fit
+  #[arg(long, value_enum, default_value_t)]
+  pub unbounded_integer_type: UnboundedIntegerType,
+
+  /// The type of numbers, `decimal` keeps them exact so `multipleOf: 0.01` works as expected
+  #[arg(long, value_enum, default_value_t)]
+  pub number_type: NumberType,
+
+  /// Do not validate the documents against their meta schema, for instance when the meta
+  /// schema is not embedded and cannot be retrieved
+  #[arg(long)]
+  pub skip_document_validation: bool,
 }
 
 pub async fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>> {
@@ -40,11 +59,23 @@ pub async fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>>
     package_version,
     default_type_name,
     transform_maximum_iterations,
+    transform_trace,
+    unbounded_integer_type,
+    number_type,
+    skip_document_validation,
     ..
   } = options;
 
+  if let Some(transform_trace) = &transform_trace {
+    std::fs::create_dir_all(transform_trace)?;
+  }
+
   let mut context = Rc::new(DocumentContext::default());
-  context.register_well_known_factories().unwrap();
+  context
+    .register_well_known_factories()
+    .map_err(report_errors)?;
+  // the embedded meta schemas are used instead of fetching them
+  context.load_well_known_schemas().map_err(report_errors)?;
 
   context
     .load_from_location(
@@ -54,15 +85,45 @@ pub async fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>>
       &default_meta_schema_id,
     )
     .await
-    .unwrap();
+    .map_err(report_errors)?;
+
+  // mistakes in the documents are reported before any transform runs
+  if !skip_document_validation {
+    let invalid_documents = context
+      .validate_documents(&default_meta_schema_id)
+      .await
+      .map_err(report_errors)?;
+    if !invalid_documents.is_empty() {
+      for (document_location, output) in &invalid_documents {
+        for unit in &output.errors {
+          let Some(error) = &unit.error else {
+            continue;
+          };
+          let location = document_location.push_pointer(parse_pointer(&unit.instance_location));
+          // point to the line and column in the file if we know them
+          let location = context
+            .get_source_position(&location)
+            .map(|position| position.to_string())
+            .unwrap_or_else(|| location.to_string());
+          eprintln!("{}: {} ({})", location, error, unit.keyword_location);
+        }
+      }
+
+      Err(format!("{} invalid document(s)", invalid_documents.len()))?;
+    }
+  }
 
   let specification = Specification::new(
     &context,
     SpecificationConfiguration {
       default_type_name,
       transform_maximum_iterations,
+      transform_trace_directory: transform_trace,
+      unbounded_integer_type,
+      number_type,
     },
-  );
+  )
+  .map_err(report_errors)?;
   generate_package(
     PackageConfiguration {
       package_name: package_name.as_str(),
@@ -76,3 +137,12 @@ pub async fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>>
 
   Ok(())
 }
+
+/// Split a JSON pointer into it's unescaped parts
+fn parse_pointer(pointer: &str) -> Vec<String> {
+  pointer
+    .split('/')
+    .skip(1)
+    .map(|part| part.replace("~1", "/").replace("~0", "~"))
+    .collect()
+}
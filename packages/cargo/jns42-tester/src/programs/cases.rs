@@ -10,6 +10,9 @@ struct CaseConfiguration {
   parse: bool,
   #[serde(default)]
   schemas: Vec<PathBuf>,
+  /// Extra arguments for the rust generator, like `--number-type decimal`
+  #[serde(default)]
+  arguments: Vec<String>,
   #[serde(default)]
   valid: Vec<serde_yaml::Value>,
   #[serde(default)]
@@ -18,6 +21,7 @@ struct CaseConfiguration {
 
 struct CaseInfo {
   parse: bool,
+  arguments: Vec<String>,
   specifications: Vec<SpecificationInfo>,
   valid_values: Vec<serde_json::Value>,
   invalid_values: Vec<serde_json::Value>,
@@ -112,6 +116,7 @@ pub fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>> {
     }
 
     let parse = configuration.parse;
+    let arguments = configuration.arguments;
     let valid_values = configuration
       .valid
       .iter()
@@ -125,6 +130,7 @@ pub fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>> {
 
     case_infos.push(CaseInfo {
       parse,
+      arguments,
       specifications,
       valid_values,
       invalid_values,
@@ -153,6 +159,7 @@ pub fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>> {
           .arg(&default_type_name)
           .arg("--transform-maximum-iterations")
           .arg(transform_maximum_iterations.to_string())
+          .args(&case_info.arguments)
           .arg(&specification_info.path)
           .spawn(),
 
@@ -329,7 +336,7 @@ pub fn run_command(options: CommandOptions) -> Result<(), Box<dyn Error>> {
         }
 
         let status = child.wait()?;
-        assert!(!status.success()); // we expect no success!
+        assert_eq!(status.code(), Some(1)); // we expect the value to be invalid
       }
     }
   }